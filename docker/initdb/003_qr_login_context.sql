@@ -0,0 +1,9 @@
+-- 扫码登录上下文：记录发起登录的 Web 端信息，并增加数字匹配防钓鱼
+ALTER TABLE qr_login_sessions
+    ADD COLUMN IF NOT EXISTS client_ip TEXT,       -- Web 端 IP
+    ADD COLUMN IF NOT EXISTS user_agent TEXT,      -- Web 端 User-Agent
+    ADD COLUMN IF NOT EXISTS client_info TEXT,     -- Web 端上报的客户端信息
+    ADD COLUMN IF NOT EXISTS match_number SMALLINT; -- Web 端展示的两位匹配数字
+
+-- 记录扫码确认时数字不匹配的行为
+ALTER TYPE log_action_type ADD VALUE IF NOT EXISTS 'QR_LOGIN_MISMATCH';
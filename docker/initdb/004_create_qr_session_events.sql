@@ -0,0 +1,14 @@
+-- 扫码登录会话事件：记录推送给 Web 端的状态事件，用于断线重连后按 last_event_id 补发
+CREATE TABLE IF NOT EXISTS qr_session_events (
+    id BIGSERIAL PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    event JSONB NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT fk_qr_session_events_session FOREIGN KEY (session_id)
+        REFERENCES qr_login_sessions(session_id)
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+);
+
+CREATE INDEX idx_qr_session_events_session_id ON qr_session_events(session_id, id);
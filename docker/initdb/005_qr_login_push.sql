@@ -0,0 +1,6 @@
+-- 推送登录审批：复用扫码登录会话表，login_method 区分扫码 (qr) 与推送审批 (push)
+ALTER TABLE qr_login_sessions
+    ADD COLUMN IF NOT EXISTS login_method TEXT NOT NULL DEFAULT 'qr';
+
+-- 查询用户待处理的推送审批
+CREATE INDEX IF NOT EXISTS idx_qr_login_user_status ON qr_login_sessions(user_id, status);
@@ -0,0 +1,23 @@
+-- App 设备密钥：设备注册 Ed25519 公钥后，确认 / 拒绝扫码登录时需携带设备签名
+CREATE TABLE IF NOT EXISTS device_keys (
+    id BIGSERIAL PRIMARY KEY,
+    device_id TEXT NOT NULL UNIQUE,
+    user_id TEXT NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    public_key TEXT NOT NULL UNIQUE,     -- bs58 编码的 32 字节 Ed25519 公钥
+    name TEXT,                           -- 设备名称，如 "iPhone 15"
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMP,
+    revoked_at TIMESTAMP
+);
+
+CREATE INDEX idx_device_keys_user_id ON device_keys(user_id);
+
+-- 已使用的签名 nonce，用于拒绝重放；超过签名有效期后由后台任务清理
+CREATE TABLE IF NOT EXISTS device_key_nonces (
+    device_id TEXT NOT NULL REFERENCES device_keys(device_id) ON DELETE CASCADE,
+    nonce TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (device_id, nonce)
+);
+
+CREATE INDEX idx_device_key_nonces_created_at ON device_key_nonces(created_at);
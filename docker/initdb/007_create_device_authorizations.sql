@@ -0,0 +1,18 @@
+-- OAuth 2.0 设备授权（RFC 8628）：状态、过期时间与签发的 token 复用 qr_login_sessions，
+-- 此表只记录设备码、用户码与轮询信息
+CREATE TABLE IF NOT EXISTS device_authorizations (
+    id BIGSERIAL PRIMARY KEY,
+    session_id TEXT NOT NULL UNIQUE,
+    device_code_hash TEXT NOT NULL UNIQUE,   -- device_code 的 SHA-256，明文只返回给设备一次
+    user_code TEXT NOT NULL UNIQUE,          -- 用户在验证页面输入的代码，如 WDJB-MJHT
+    client_id TEXT NOT NULL,
+    scope TEXT,
+    interval_seconds INTEGER NOT NULL,       -- 最小轮询间隔，收到 slow_down 后增加
+    last_polled_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT fk_device_authorizations_session FOREIGN KEY (session_id)
+        REFERENCES qr_login_sessions(session_id)
+        ON UPDATE NO ACTION
+        ON DELETE CASCADE
+);
@@ -0,0 +1,54 @@
+-- OAuth 2.0 授权服务器：客户端、授权码、refresh token 与用户授权记录
+
+-- 已登记的客户端
+CREATE TABLE IF NOT EXISTS oauth_clients (
+    id BIGSERIAL PRIMARY KEY,
+    client_id TEXT NOT NULL UNIQUE,
+    client_secret_hash TEXT,                       -- 机密客户端的 secret（SHA-256），公开客户端为空
+    name TEXT NOT NULL,
+    redirect_uris JSONB NOT NULL DEFAULT '[]',     -- 精确匹配的回调地址列表
+    scopes TEXT NOT NULL DEFAULT '',               -- 允许申请的 scope（空格分隔）
+    skip_consent BOOLEAN NOT NULL DEFAULT FALSE,   -- 内部可信应用可跳过授权同意页面
+    created_by TEXT REFERENCES users(user_id) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    revoked_at TIMESTAMP
+);
+
+-- 授权码：一次性使用，有效期很短
+CREATE TABLE IF NOT EXISTS oauth_authorization_codes (
+    id BIGSERIAL PRIMARY KEY,
+    code_hash TEXT NOT NULL UNIQUE,
+    client_id TEXT NOT NULL REFERENCES oauth_clients(client_id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    redirect_uri TEXT NOT NULL,
+    scope TEXT NOT NULL DEFAULT '',
+    code_challenge TEXT NOT NULL,
+    code_challenge_method TEXT NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    consumed_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- refresh token：每次使用后轮换，旧 token 被再次使用时吊销整条链
+CREATE TABLE IF NOT EXISTS oauth_refresh_tokens (
+    id BIGSERIAL PRIMARY KEY,
+    token_hash TEXT NOT NULL UNIQUE,
+    client_id TEXT NOT NULL REFERENCES oauth_clients(client_id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    scope TEXT NOT NULL DEFAULT '',
+    expires_at TIMESTAMP NOT NULL,
+    revoked_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_oauth_refresh_tokens_client_user ON oauth_refresh_tokens(client_id, user_id);
+
+-- 用户已同意授予客户端的 scope，再次授权时不再弹出同意页面
+CREATE TABLE IF NOT EXISTS oauth_consents (
+    user_id TEXT NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    client_id TEXT NOT NULL REFERENCES oauth_clients(client_id) ON DELETE CASCADE,
+    scope TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, client_id)
+);
@@ -0,0 +1,8 @@
+-- OpenID Connect：id_token 需要的 nonce 与用户认证时间
+ALTER TABLE oauth_authorization_codes
+    ADD COLUMN IF NOT EXISTS nonce TEXT,
+    ADD COLUMN IF NOT EXISTS auth_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP;
+
+-- 刷新时签发的 id_token 沿用首次授权的 auth_time
+ALTER TABLE oauth_refresh_tokens
+    ADD COLUMN IF NOT EXISTS auth_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP;
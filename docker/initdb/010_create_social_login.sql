@@ -0,0 +1,24 @@
+-- 第三方登录：外部身份与用户的关联，以及授权跳转期间的 state
+
+-- 外部身份：同一提供方的同一 subject 只能关联一个用户
+CREATE TABLE IF NOT EXISTS user_identities (
+    id BIGSERIAL PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    provider TEXT NOT NULL,                        -- 提供方标识，如 github
+    subject TEXT NOT NULL,                         -- 提供方的用户唯一标识
+    email TEXT,                                    -- 提供方返回的邮箱（仅记录）
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_login_at TIMESTAMP,
+    UNIQUE (provider, subject)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_identities_user ON user_identities(user_id);
+
+-- 跳转到提供方前生成的 state，回调时一次性消费
+CREATE TABLE IF NOT EXISTS social_login_states (
+    state_hash TEXT PRIMARY KEY,
+    provider TEXT NOT NULL,
+    code_verifier TEXT NOT NULL,                   -- 与提供方之间的 PKCE
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
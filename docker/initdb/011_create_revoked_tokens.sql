@@ -0,0 +1,10 @@
+-- 已吊销的 access token（JWT 本身无状态，吊销后需要在验证时额外检查）
+CREATE TABLE IF NOT EXISTS revoked_tokens (
+    token_hash TEXT PRIMARY KEY,                   -- token 的 SHA-256
+    user_id TEXT NOT NULL,
+    client_id TEXT,                                -- 通过 OAuth 签发时的客户端
+    expires_at TIMESTAMP NOT NULL,                 -- token 原本的过期时间，之后可清理
+    revoked_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_revoked_tokens_expires ON revoked_tokens(expires_at);
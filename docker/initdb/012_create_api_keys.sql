@@ -0,0 +1,15 @@
+-- API Key：供脚本、定时任务等机器调用 /v2 接口
+CREATE TABLE IF NOT EXISTS api_keys (
+    id BIGSERIAL PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    key_prefix TEXT NOT NULL,                      -- key 的开头部分，便于用户辨认
+    key_hash TEXT NOT NULL UNIQUE,                 -- key 的 SHA-256，明文只在创建时返回一次
+    scopes TEXT NOT NULL,                          -- 空格分隔：read / write
+    expires_at TIMESTAMP,                          -- 为空表示永不过期
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMP,
+    revoked_at TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_user ON api_keys(user_id);
@@ -0,0 +1,14 @@
+-- 服务账号：使用 client_credentials 授权换取代表服务自身（而非用户）的 access token
+
+ALTER TABLE oauth_clients
+    ADD COLUMN IF NOT EXISTS service_account BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS jwt_public_key TEXT;  -- bs58 编码的 Ed25519 公钥，用于校验 client_assertion（RFC 7523）
+
+-- 已使用的 client_assertion，jti 在有效期内只能使用一次
+CREATE TABLE IF NOT EXISTS oauth_client_assertions (
+    jti_hash TEXT PRIMARY KEY,                     -- SHA-256(client_id:jti)
+    client_id TEXT NOT NULL,
+    expires_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_oauth_client_assertions_expires ON oauth_client_assertions(expires_at);
@@ -0,0 +1,22 @@
+-- 用户自助管理：资料、修改密码后吊销其他会话、更换邮箱 / 手机号的验证
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS display_name TEXT,            -- 昵称
+    ADD COLUMN IF NOT EXISTS avatar_url TEXT,              -- 头像地址
+    ADD COLUMN IF NOT EXISTS sessions_revoked_at TIMESTAMP; -- 早于此时间签发的 access token 一律失效
+
+-- 更换邮箱 / 手机号的验证码，验证通过后才会写入 users
+CREATE TABLE IF NOT EXISTS contact_verifications (
+    id BIGSERIAL PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    channel TEXT NOT NULL CHECK (channel IN ('email', 'phone')),
+    target TEXT NOT NULL,                        -- 新的邮箱或手机号
+    code_hash TEXT NOT NULL,                     -- SHA-256(验证码)
+    attempts INTEGER NOT NULL DEFAULT 0,         -- 验证失败次数
+    expires_at TIMESTAMP NOT NULL,
+    consumed_at TIMESTAMP,                       -- 验证通过或被新的验证码取代
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_contact_verifications_user ON contact_verifications(user_id, channel);
+CREATE INDEX IF NOT EXISTS idx_contact_verifications_expires ON contact_verifications(expires_at);
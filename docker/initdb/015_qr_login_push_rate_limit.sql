@@ -0,0 +1,6 @@
+-- 推送登录限流：按发起 IP 与目标用户统计最近的推送次数
+
+CREATE INDEX IF NOT EXISTS idx_qr_login_push_ip ON qr_login_sessions(client_ip, created_at)
+    WHERE login_method = 'push';
+CREATE INDEX IF NOT EXISTS idx_qr_login_push_user ON qr_login_sessions(user_id, created_at)
+    WHERE login_method = 'push';
@@ -0,0 +1,10 @@
+-- OAuth 授权吊销：某客户端为某用户签发的 access token 中，签发时间早于 revoked_at 的一律失效
+-- （吊销 refresh token 或检测到凭证重用时写入，使续期得到的 token 一并失效）
+CREATE TABLE IF NOT EXISTS oauth_grant_revocations (
+    user_id TEXT NOT NULL,                         -- 服务账号为客户端自身的 client_id，因此不设外键
+    client_id TEXT NOT NULL,
+    revoked_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, client_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_oauth_grant_revocations_revoked ON oauth_grant_revocations(revoked_at);
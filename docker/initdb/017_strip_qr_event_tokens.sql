@@ -0,0 +1,2 @@
+-- 扫码登录事件不再携带 Web 端 token（客户端通过状态接口获取），清除历史事件中持久化的 token
+UPDATE qr_session_events SET event = event - 'web_token' WHERE event ? 'web_token';
@@ -0,0 +1,9 @@
+-- 设备注册 / 吊销再次认证时的密码错误记录，用于按用户限制密码猜测；超过统计窗口后由后台任务清理
+CREATE TABLE IF NOT EXISTS device_step_up_failures (
+    id BIGSERIAL PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_device_step_up_failures_user ON device_step_up_failures(user_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_device_step_up_failures_created ON device_step_up_failures(created_at);
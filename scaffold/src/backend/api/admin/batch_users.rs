@@ -0,0 +1,206 @@
+use std::collections::HashSet;
+use actix_web::{web, HttpRequest, HttpResponse};
+use sea_orm::{DatabaseConnection, DbErr, TransactionTrait};
+use serde::Deserialize;
+use tracing::info;
+use crate::backend::AppState;
+use crate::backend::api::admin::handle_users::{apply_user_change, UserChange};
+use crate::backend::api::admin::require_admin;
+use crate::backend::config::admin;
+use crate::backend::errors::{BatchOperationResult, ErrorCode, error_response, SuccessResponse};
+use crate::backend::models::sea_orm_active_enums::UserRoleType;
+
+/// 批量操作的执行方式
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchMode {
+    /// 逐条执行，失败的条目不影响其他条目
+    #[default]
+    PerItem,
+    /// 在一个事务中执行，任意一条失败则全部回滚
+    Transaction,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchAction {
+    Activate,
+    Deactivate,
+    Delete,
+    SetRole,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BatchUsersRequest {
+    pub action: BatchAction,
+    pub user_ids: Vec<String>,
+    /// `set_role` 时必填：`Admin` 或 `User`
+    pub role: Option<UserRoleType>,
+    #[serde(default)]
+    pub mode: BatchMode,
+}
+
+/// 事务模式下出现失败：全部条目视为失败，并附上导致回滚的原因
+pub fn abort_transaction(result: &mut BatchOperationResult, errors: Vec<String>) {
+    result.success_count = 0;
+    result.failed_count = result.total_count;
+    result.errors.extend(errors);
+    result.errors.push("Transaction rolled back, no changes were applied".to_string());
+}
+
+/// 执行前逐条校验，返回每个 user_id 的错误
+///
+/// 重复的 user_id 只执行第一次；管理员不能停用、删除自己或修改自己的角色。
+fn precheck(admin_id: &str, change: &UserChange, user_ids: &[String]) -> Vec<Option<String>> {
+    let mut seen = HashSet::new();
+    user_ids
+        .iter()
+        .map(|user_id| {
+            if !seen.insert(user_id.as_str()) {
+                return Some(format!("{}: duplicate user_id", user_id));
+            }
+            if user_id == admin_id && *change != UserChange::Activate {
+                return Some(format!("{}: admins cannot deactivate, delete or change the role of themselves", user_id));
+            }
+            None
+        })
+        .collect()
+}
+
+fn item_error(user_id: &str, outcome: Result<bool, DbErr>) -> Option<String> {
+    match outcome {
+        Ok(true) => None,
+        Ok(false) => Some(format!("{}: user not found", user_id)),
+        Err(e) => Some(format!("{}: {}", user_id, e)),
+    }
+}
+
+async fn run_in_transaction(
+    db: &DatabaseConnection,
+    user_ids: &[String],
+    change: &UserChange,
+    checks: Vec<Option<String>>,
+    result: &mut BatchOperationResult,
+) -> Result<(), DbErr> {
+    let failures: Vec<String> = checks.into_iter().flatten().collect();
+    if !failures.is_empty() {
+        abort_transaction(result, failures);
+        return Ok(());
+    }
+
+    let txn = db.begin().await?;
+    for user_id in user_ids {
+        let outcome = apply_user_change(&txn, user_id, change).await;
+        if let Some(error) = item_error(user_id, outcome) {
+            txn.rollback().await?;
+            abort_transaction(result, vec![error]);
+            return Ok(());
+        }
+        result.add_success();
+    }
+    txn.commit().await
+}
+
+/// 批量启用、停用、删除用户或修改角色
+///
+/// 路由: POST /v2/admin/users/batch
+///
+/// `mode` 为 `per_item`（默认）时逐条执行，为 `transaction` 时任意一条失败则全部回滚。
+/// 返回 `BatchOperationResult`，`errors` 中每条以 user_id 开头。
+/// 与单个用户的修改相同，启用状态或角色变更后该用户此前签发的 token 立即失效。
+pub async fn batch_users(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    request: web::Json<BatchUsersRequest>,
+) -> HttpResponse {
+    let claims = match require_admin(&req, &state.pg_client).await {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    if request.user_ids.is_empty() || request.user_ids.len() > admin::MAX_BATCH_SIZE {
+        let error_resp = error_response(
+            ErrorCode::InvalidParams,
+            format!("user_ids must contain 1-{} entries", admin::MAX_BATCH_SIZE),
+        );
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+
+    let change = match (request.action, request.role.clone()) {
+        (BatchAction::Activate, _) => UserChange::Activate,
+        (BatchAction::Deactivate, _) => UserChange::Deactivate,
+        (BatchAction::Delete, _) => UserChange::Delete,
+        (BatchAction::SetRole, Some(role)) => UserChange::SetRole(role),
+        (BatchAction::SetRole, None) => {
+            let error_resp = error_response(ErrorCode::MissingRequiredField, "role is required for set_role");
+            return HttpResponse::BadRequest().json(error_resp);
+        }
+    };
+
+    let checks = precheck(&claims.user_id, &change, &request.user_ids);
+    let mut result = BatchOperationResult::new(request.user_ids.len());
+
+    match request.mode {
+        BatchMode::PerItem => {
+            for (user_id, check) in request.user_ids.iter().zip(checks) {
+                let error = match check {
+                    Some(error) => Some(error),
+                    None => item_error(user_id, apply_user_change(&state.pg_client, user_id, &change).await),
+                };
+                match error {
+                    Some(error) => result.add_failure(error),
+                    None => result.add_success(),
+                }
+            }
+        }
+        BatchMode::Transaction => {
+            if let Err(e) = run_in_transaction(&state.pg_client, &request.user_ids, &change, checks, &mut result).await {
+                let error_resp = error_response(
+                    ErrorCode::DatabaseError,
+                    format!("Batch operation failed: {}", e),
+                );
+                return HttpResponse::InternalServerError().json(error_resp);
+            }
+        }
+    }
+
+    info!(
+        "🛠️ Admin {} batch {:?} ({:?}): {} succeeded, {} failed",
+        claims.user_id, change, request.mode, result.success_count, result.failed_count
+    );
+    HttpResponse::Ok().json(SuccessResponse::new(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_precheck() {
+        let checks = precheck("root", &UserChange::Deactivate, &ids(&["alice", "root", "alice"]));
+        assert!(checks[0].is_none());
+        assert!(checks[1].as_deref().unwrap().starts_with("root:"));
+        assert!(checks[2].as_deref().unwrap().contains("duplicate"));
+
+        // 启用自己是允许的
+        let checks = precheck("root", &UserChange::Activate, &ids(&["root"]));
+        assert!(checks[0].is_none());
+    }
+
+    #[test]
+    fn test_abort_transaction_marks_all_failed() {
+        let mut result = BatchOperationResult::new(3);
+        result.add_success();
+        result.add_success();
+        abort_transaction(&mut result, vec!["carol: user not found".to_string()]);
+
+        assert_eq!(result.success_count, 0);
+        assert_eq!(result.failed_count, 3);
+        assert!(result.is_complete());
+        assert_eq!(result.errors.len(), 2);
+    }
+}
@@ -0,0 +1,45 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use tracing::warn;
+use crate::backend::AppState;
+use crate::backend::api::admin::handle_users::delete_user as delete_user_record;
+use crate::backend::api::admin::require_admin;
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+
+/// 删除用户
+///
+/// 路由: DELETE /v2/admin/users/{user_id}
+///
+/// 用户的会话、日志、设备密钥、API Key、OAuth 授权等随之删除，不可恢复；管理员不能删除自己。
+pub async fn delete_user(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    user_id: web::Path<String>,
+) -> HttpResponse {
+    let claims = match require_admin(&req, &state.pg_client).await {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    if claims.user_id == *user_id {
+        let error_resp = error_response(ErrorCode::InvalidParams, "Admins cannot delete themselves");
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+
+    match delete_user_record(&state.pg_client, &user_id).await {
+        Ok(true) => {
+            warn!("🗑️ Admin {} deleted user {}", claims.user_id, user_id);
+            HttpResponse::Ok().json(SuccessResponse::new(serde_json::json!({ "user_id": user_id.as_str() })))
+        }
+        Ok(false) => {
+            let error_resp = error_response(ErrorCode::NotFound, "User not found");
+            HttpResponse::NotFound().json(error_resp)
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Failed to delete user: {}", e),
+            );
+            HttpResponse::InternalServerError().json(error_resp)
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::Serialize;
+use crate::backend::AppState;
+use crate::backend::api::admin::handle_users::find_user;
+use crate::backend::api::admin::require_admin;
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+use crate::backend::models::sea_orm_active_enums::UserRoleType;
+use crate::backend::models::users;
+
+/// 管理接口返回的用户信息（不含密码哈希）
+#[derive(Serialize, Debug)]
+pub struct AdminUserResponse {
+    pub user_id: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub role: UserRoleType,
+    pub is_active: bool,
+    pub is_verified: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<users::Model> for AdminUserResponse {
+    fn from(user: users::Model) -> Self {
+        Self {
+            user_id: user.user_id,
+            email: user.email,
+            phone: user.phone,
+            role: user.role,
+            is_active: user.is_active != Some(false),
+            is_verified: user.is_verified == Some(true),
+            created_at: user.created_at.and_utc().timestamp(),
+            updated_at: user.updated_at.and_utc().timestamp(),
+        }
+    }
+}
+
+/// 查看用户详情
+///
+/// 路由: GET /v2/admin/users/{user_id}
+pub async fn get_user(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    user_id: web::Path<String>,
+) -> HttpResponse {
+    if let Err(resp) = require_admin(&req, &state.pg_client).await {
+        return resp;
+    }
+
+    match find_user(&state.pg_client, &user_id).await {
+        Ok(Some(user)) => HttpResponse::Ok().json(SuccessResponse::new(AdminUserResponse::from(user))),
+        Ok(None) => {
+            let error_resp = error_response(ErrorCode::NotFound, "User not found");
+            HttpResponse::NotFound().json(error_resp)
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Database error: {}", e),
+            );
+            HttpResponse::InternalServerError().json(error_resp)
+        }
+    }
+}
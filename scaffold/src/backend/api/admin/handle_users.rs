@@ -0,0 +1,267 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::sea_query::extension::postgres::PgExpr;
+use sea_orm::sea_query::{Condition, Expr, Func};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, Order, PaginatorTrait,
+    QueryFilter, QueryOrder, Set,
+};
+use serde::Deserialize;
+use tracing::info;
+use crate::backend::models::prelude::Users;
+use crate::backend::models::sea_orm_active_enums::UserRoleType;
+use crate::backend::models::users;
+
+/// 用户列表的筛选条件，`None` 表示不限
+#[derive(Debug, Default)]
+pub struct UserFilter<'a> {
+    pub role: Option<UserRoleType>,
+    pub is_active: Option<bool>,
+    pub is_verified: Option<bool>,
+    pub created_from: Option<NaiveDateTime>,
+    pub created_to: Option<NaiveDateTime>,
+    /// 在 email、phone、user_id 中模糊搜索（不区分大小写）
+    pub search: Option<&'a str>,
+}
+
+/// 用户列表的排序字段
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UserSortField {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    UserId,
+    Email,
+}
+
+impl UserSortField {
+    fn column(self) -> users::Column {
+        match self {
+            Self::CreatedAt => users::Column::CreatedAt,
+            Self::UpdatedAt => users::Column::UpdatedAt,
+            Self::UserId => users::Column::UserId,
+            Self::Email => users::Column::Email,
+        }
+    }
+}
+
+/// 转义 LIKE 通配符，生成包含匹配的模式
+pub fn like_pattern(search: &str) -> String {
+    let escaped = search
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// `is_active` 为 NULL 视为启用，`is_verified` 为 NULL 视为未验证（与建表默认值一致）
+fn filter_condition(filter: &UserFilter<'_>) -> Condition {
+    let mut condition = Condition::all();
+
+    if let Some(role) = filter.role.clone() {
+        condition = condition.add(users::Column::Role.eq(role));
+    }
+    if let Some(is_active) = filter.is_active {
+        condition = condition.add(if is_active {
+            Condition::any()
+                .add(users::Column::IsActive.eq(true))
+                .add(users::Column::IsActive.is_null())
+        } else {
+            Condition::all().add(users::Column::IsActive.eq(false))
+        });
+    }
+    if let Some(is_verified) = filter.is_verified {
+        condition = condition.add(if is_verified {
+            Condition::all().add(users::Column::IsVerified.eq(true))
+        } else {
+            Condition::any()
+                .add(users::Column::IsVerified.eq(false))
+                .add(users::Column::IsVerified.is_null())
+        });
+    }
+    if let Some(from) = filter.created_from {
+        condition = condition.add(users::Column::CreatedAt.gte(from));
+    }
+    if let Some(to) = filter.created_to {
+        condition = condition.add(users::Column::CreatedAt.lt(to));
+    }
+    if let Some(search) = filter.search {
+        let pattern = like_pattern(search);
+        condition = condition.add(
+            Condition::any()
+                .add(Expr::col(users::Column::Email).ilike(pattern.as_str()))
+                .add(Expr::col(users::Column::Phone).ilike(pattern.as_str()))
+                .add(Expr::col(users::Column::UserId).ilike(pattern.as_str())),
+        );
+    }
+
+    condition
+}
+
+/// 分页查询用户，返回当前页与总数；`page` 从 1 开始
+pub async fn list_users_page(
+    db: &DatabaseConnection,
+    filter: &UserFilter<'_>,
+    sort: UserSortField,
+    order: Order,
+    page: u64,
+    page_size: u64,
+) -> Result<(Vec<users::Model>, u64), DbErr> {
+    let paginator = Users::find()
+        .filter(filter_condition(filter))
+        .order_by(sort.column(), order)
+        // 排序字段相同时按 id 排序，保证翻页结果稳定
+        .order_by_asc(users::Column::Id)
+        .paginate(db, page_size);
+
+    let total = paginator.num_items().await?;
+    let items = paginator.fetch_page(page.saturating_sub(1)).await?;
+    Ok((items, total))
+}
+
+pub async fn find_user<C: ConnectionTrait>(db: &C, user_id: &str) -> Result<Option<users::Model>, DbErr> {
+    Users::find()
+        .filter(users::Column::UserId.eq(user_id))
+        .one(db)
+        .await
+}
+
+/// 管理员可修改的用户字段，`None` 表示不修改
+#[derive(Debug, Default)]
+pub struct UserUpdate {
+    pub role: Option<UserRoleType>,
+    pub is_active: Option<bool>,
+    pub is_verified: Option<bool>,
+}
+
+/// 更新用户角色与状态，用户不存在时返回 `None`
+///
+/// 修改角色或启用状态时，该用户此前签发的 token 全部失效（见 `is_token_revoked`）。
+pub async fn update_user<C: ConnectionTrait>(
+    db: &C,
+    user_id: &str,
+    update: UserUpdate,
+) -> Result<Option<users::Model>, DbErr> {
+    let Some(user) = find_user(db, user_id).await? else {
+        return Ok(None);
+    };
+
+    let now = Utc::now().naive_utc();
+    let mut active: users::ActiveModel = user.into();
+    if update.role.is_some() || update.is_active.is_some() {
+        active.sessions_revoked_at = Set(Some(now));
+    }
+    if let Some(role) = update.role {
+        active.role = Set(role);
+    }
+    if let Some(is_active) = update.is_active {
+        active.is_active = Set(Some(is_active));
+    }
+    if let Some(is_verified) = update.is_verified {
+        active.is_verified = Set(Some(is_verified));
+    }
+    active.updated_at = Set(now);
+
+    let updated = active.update(db).await?;
+    info!("Updated user {}", user_id);
+    Ok(Some(updated))
+}
+
+/// 删除用户，关联数据随外键级联删除；返回 `false` 表示用户不存在
+///
+/// 用户行删除后其 token 视为已吊销（见 `is_token_revoked`）；用同一 user_id 新建的账户也不会接受此前签发的 token。
+pub async fn delete_user<C: ConnectionTrait>(db: &C, user_id: &str) -> Result<bool, DbErr> {
+    let result = Users::delete_many()
+        .filter(users::Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected > 0)
+}
+
+/// 批量操作中对单个用户的修改
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserChange {
+    Activate,
+    Deactivate,
+    SetRole(UserRoleType),
+    Delete,
+}
+
+/// 对单个用户执行批量操作中的一项，返回 `false` 表示用户不存在
+///
+/// 与单个用户的修改、删除共用 `update_user` / `delete_user`，可以在事务中调用。
+pub async fn apply_user_change<C: ConnectionTrait>(
+    db: &C,
+    user_id: &str,
+    change: &UserChange,
+) -> Result<bool, DbErr> {
+    let update = match change {
+        UserChange::Activate => UserUpdate { is_active: Some(true), ..Default::default() },
+        UserChange::Deactivate => UserUpdate { is_active: Some(false), ..Default::default() },
+        UserChange::SetRole(role) => UserUpdate { role: Some(role.clone()), ..Default::default() },
+        UserChange::Delete => return delete_user(db, user_id).await,
+    };
+
+    Ok(update_user(db, user_id, update).await?.is_some())
+}
+
+pub struct NewUser<'a> {
+    pub user_id: &'a str,
+    pub email: Option<&'a str>,
+    pub phone: Option<&'a str>,
+    pub password_hash: &'a str,
+    pub role: UserRoleType,
+}
+
+/// 与 user_id、email 或 phone 任一冲突的已有用户，email 不区分大小写
+pub async fn find_conflicting_user<C: ConnectionTrait>(
+    db: &C,
+    user_id: &str,
+    email: Option<&str>,
+    phone: Option<&str>,
+) -> Result<Option<users::Model>, DbErr> {
+    let mut condition = Condition::any().add(users::Column::UserId.eq(user_id));
+    if let Some(email) = email {
+        condition = condition.add(Expr::expr(Func::lower(Expr::col(users::Column::Email))).eq(email.to_lowercase()));
+    }
+    if let Some(phone) = phone {
+        condition = condition.add(users::Column::Phone.eq(phone));
+    }
+
+    Users::find().filter(condition).one(db).await
+}
+
+pub async fn insert_user<C: ConnectionTrait>(db: &C, user: NewUser<'_>) -> Result<users::Model, DbErr> {
+    let now = Utc::now().naive_utc();
+    let new_user = users::ActiveModel {
+        user_id: Set(user.user_id.to_string()),
+        password_hash: Set(user.password_hash.to_string()),
+        email: Set(user.email.map(str::to_string)),
+        phone: Set(user.phone.map(str::to_string)),
+        role: Set(user.role),
+        is_active: Set(Some(true)),
+        is_verified: Set(Some(false)),
+        sessions_revoked_at: Set(Some(now)),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    };
+
+    // 同一 user_id 可能属于已删除的账户，创建之前签发的 token 一律无效
+    let inserted = new_user.insert(db).await?;
+    info!("Imported user {}", inserted.user_id);
+    Ok(inserted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_like_pattern_escapes_wildcards() {
+        assert_eq!(like_pattern("alice"), "%alice%");
+        assert_eq!(like_pattern("100%_off"), "%100\\%\\_off%");
+        assert_eq!(like_pattern("a\\b"), "%a\\\\b%");
+    }
+}
@@ -0,0 +1,392 @@
+use std::collections::HashSet;
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, TransactionTrait};
+use serde::Deserialize;
+use tracing::info;
+use crate::backend::AppState;
+use crate::backend::api::admin::batch_users::{abort_transaction, BatchMode};
+use crate::backend::api::admin::handle_users::{find_conflicting_user, insert_user, NewUser};
+use crate::backend::api::admin::require_admin;
+use crate::backend::config::admin;
+use crate::backend::errors::{BatchOperationResult, ErrorCode, error_response, SuccessResponse};
+use crate::backend::models::sea_orm_active_enums::UserRoleType;
+use crate::backend::utils::contact::{is_valid_email, is_valid_phone, validate_password};
+use crate::backend::utils::hash::hash_password;
+
+static USER_ID_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[A-Za-z0-9_.@-]+$").unwrap());
+
+/// CSV 可用的列，第一行表头必须包含 `user_id`，其余列可选、顺序不限
+const CSV_COLUMNS: [&str; 5] = ["user_id", "email", "phone", "password", "role"];
+
+/// 导入的一行用户数据
+#[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImportUserRow {
+    pub user_id: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    /// 初始密码，不传则无法用密码登录，用户需通过找回密码设置
+    pub password: Option<String>,
+    /// `Admin` 或 `User`（默认）
+    pub role: Option<UserRoleType>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ImportUsersQuery {
+    #[serde(default)]
+    pub mode: BatchMode,
+}
+
+/// 拆分一行 CSV，支持双引号包裹的字段与 `""` 转义（不支持字段内换行）
+fn split_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+fn parse_role(value: &str) -> Result<UserRoleType, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "admin" => Ok(UserRoleType::Admin),
+        "user" => Ok(UserRoleType::User),
+        _ => Err(format!("unknown role: {}", value)),
+    }
+}
+
+/// 解析 CSV，表头错误时返回 `Err`，每一行单独返回解析结果（跳过空行）
+fn parse_csv(text: &str) -> Result<Vec<Result<ImportUserRow, String>>, String> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let header = lines.next().ok_or_else(|| "CSV is empty".to_string())?;
+    let header: Vec<String> = split_csv_line(header.trim_start_matches('\u{feff}'))?
+        .iter()
+        .map(|column| column.trim().to_ascii_lowercase())
+        .collect();
+    if let Some(unknown) = header.iter().find(|column| !CSV_COLUMNS.contains(&column.as_str())) {
+        return Err(format!("Unknown CSV column: {}", unknown));
+    }
+    if !header.iter().any(|column| column == "user_id") {
+        return Err("CSV header must contain user_id".to_string());
+    }
+
+    let rows = lines
+        .map(|line| {
+            let fields = split_csv_line(line)?;
+            if fields.len() != header.len() {
+                return Err(format!("expected {} columns, got {}", header.len(), fields.len()));
+            }
+
+            let mut row = ImportUserRow::default();
+            for (column, value) in header.iter().zip(fields) {
+                let value = Some(value).filter(|v| !v.trim().is_empty());
+                match column.as_str() {
+                    "user_id" => row.user_id = value.unwrap_or_default(),
+                    "email" => row.email = value,
+                    "phone" => row.phone = value,
+                    "password" => row.password = value,
+                    "role" => row.role = value.map(|v| parse_role(v.trim())).transpose()?,
+                    _ => {}
+                }
+            }
+            Ok(row)
+        })
+        .collect();
+    Ok(rows)
+}
+
+/// 去掉首尾空白并校验字段格式（密码不去空白）
+fn validate_row(row: ImportUserRow) -> Result<ImportUserRow, String> {
+    let trimmed = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    let row = ImportUserRow {
+        user_id: row.user_id.trim().to_string(),
+        email: trimmed(row.email),
+        phone: trimmed(row.phone),
+        password: row.password.filter(|p| !p.is_empty()),
+        role: row.role,
+    };
+
+    if row.user_id.is_empty() {
+        return Err("user_id is required".to_string());
+    }
+    if row.user_id.chars().count() > admin::USER_ID_MAX_LEN || !USER_ID_RE.is_match(&row.user_id) {
+        return Err(format!(
+            "user_id must be 1-{} characters of letters, digits, '_', '.', '@' or '-'",
+            admin::USER_ID_MAX_LEN
+        ));
+    }
+    if row.email.as_deref().is_some_and(|email| !is_valid_email(email)) {
+        return Err("invalid email".to_string());
+    }
+    if row.phone.as_deref().is_some_and(|phone| !is_valid_phone(phone)) {
+        return Err("invalid phone".to_string());
+    }
+    if let Some(e) = row.password.as_deref().and_then(|p| validate_password(p).err()) {
+        return Err(e);
+    }
+    Ok(row)
+}
+
+/// 逐行校验并检查文件内 user_id、email、phone 是否重复，错误信息带 1 起始的行号
+fn validate_rows(rows: Vec<Result<ImportUserRow, String>>) -> Vec<Result<ImportUserRow, String>> {
+    let mut seen = HashSet::new();
+    rows.into_iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let row = row.and_then(validate_row).and_then(|row| {
+                let keys = [
+                    Some(format!("user_id:{}", row.user_id)),
+                    row.email.as_ref().map(|email| format!("email:{}", email.to_lowercase())),
+                    row.phone.as_ref().map(|phone| format!("phone:{}", phone)),
+                ];
+                match keys.into_iter().flatten().find(|key| !seen.insert(key.clone())) {
+                    Some(key) => Err(format!("duplicate {} in this import", key.split(':').next().unwrap_or_default())),
+                    None => Ok(row),
+                }
+            });
+            row.map_err(|e| format!("row {}: {}", index + 1, e))
+        })
+        .collect()
+}
+
+/// 计算密码哈希（bcrypt 较慢，在阻塞线程池中执行）；未提供密码时保存不可用的标记
+async fn password_hash_for(index: usize, row: &ImportUserRow) -> Result<String, String> {
+    let Some(password) = row.password.clone() else {
+        return Ok(admin::UNUSABLE_PASSWORD_HASH.to_string());
+    };
+    match web::block(move || hash_password(&password)).await {
+        Ok(Ok(hash)) => Ok(hash),
+        Ok(Err(e)) => Err(format!("row {}: failed to hash password: {}", index + 1, e)),
+        Err(e) => Err(format!("row {}: failed to hash password: {}", index + 1, e)),
+    }
+}
+
+async fn check_conflict<C: ConnectionTrait>(db: &C, index: usize, row: &ImportUserRow) -> Result<(), String> {
+    let conflict = find_conflicting_user(db, &row.user_id, row.email.as_deref(), row.phone.as_deref())
+        .await
+        .map_err(|e| format!("row {}: {}", index + 1, e))?;
+    match conflict {
+        Some(existing) => Err(format!("row {}: conflicts with existing user {}", index + 1, existing.user_id)),
+        None => Ok(()),
+    }
+}
+
+async fn insert_row<C: ConnectionTrait>(db: &C, index: usize, row: &ImportUserRow, password_hash: &str) -> Result<(), String> {
+    insert_user(db, NewUser {
+        user_id: &row.user_id,
+        email: row.email.as_deref(),
+        phone: row.phone.as_deref(),
+        password_hash,
+        role: row.role.clone().unwrap_or(UserRoleType::User),
+    })
+    .await
+    .map(|_| ())
+    .map_err(|e| format!("row {}: {}", index + 1, e))
+}
+
+/// 先检查冲突再计算密码哈希，冲突的行不会消耗 bcrypt 计算
+async fn import_row(db: &DatabaseConnection, index: usize, row: &ImportUserRow) -> Result<(), String> {
+    check_conflict(db, index, row).await?;
+    let password_hash = password_hash_for(index, row).await?;
+    insert_row(db, index, row, &password_hash).await
+}
+
+/// 全部行通过冲突检查后才计算密码哈希；事务只包含插入，不会在 bcrypt 计算期间长时间占用
+async fn import_in_transaction(
+    db: &DatabaseConnection,
+    rows: Vec<ImportUserRow>,
+    result: &mut BatchOperationResult,
+) -> Result<(), DbErr> {
+    let mut failures = Vec::new();
+    for (index, row) in rows.iter().enumerate() {
+        if let Err(error) = check_conflict(db, index, row).await {
+            failures.push(error);
+        }
+    }
+    if !failures.is_empty() {
+        abort_transaction(result, failures);
+        return Ok(());
+    }
+
+    let mut password_hashes = Vec::with_capacity(rows.len());
+    for (index, row) in rows.iter().enumerate() {
+        match password_hash_for(index, row).await {
+            Ok(hash) => password_hashes.push(hash),
+            Err(error) => {
+                abort_transaction(result, vec![error]);
+                return Ok(());
+            }
+        }
+    }
+
+    // 检查之后仍可能有并发创建的用户，插入失败时整体回滚
+    let txn = db.begin().await?;
+    for (index, (row, password_hash)) in rows.iter().zip(&password_hashes).enumerate() {
+        if let Err(error) = insert_row(&txn, index, row, password_hash).await {
+            txn.rollback().await?;
+            abort_transaction(result, vec![error]);
+            return Ok(());
+        }
+        result.add_success();
+    }
+    txn.commit().await
+}
+
+/// 批量导入用户
+///
+/// 路由: POST /v2/admin/users/import?mode=per_item|transaction
+///
+/// 请求体为 CSV（`Content-Type: text/csv`，首行为表头）或 JSON 数组（`application/json`），
+/// 字段为 `user_id`、`email`、`phone`、`password`、`role`。每行单独校验，返回 `BatchOperationResult`，
+/// `errors` 中每条以行号开头；`transaction` 模式下任意一行失败则全部不导入。
+/// 导入的用户未验证邮箱，未提供密码的用户需通过找回密码设置密码后登录。
+pub async fn import_users(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<ImportUsersQuery>,
+    body: web::Bytes,
+) -> HttpResponse {
+    let claims = match require_admin(&req, &state.pg_client).await {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    let parsed = match req.content_type() {
+        "text/csv" => std::str::from_utf8(&body)
+            .map_err(|_| "CSV must be UTF-8 encoded".to_string())
+            .and_then(parse_csv),
+        "application/json" => serde_json::from_slice::<Vec<ImportUserRow>>(&body)
+            .map(|rows| rows.into_iter().map(Ok).collect())
+            .map_err(|e| format!("Invalid JSON: {}", e)),
+        other => Err(format!("Unsupported Content-Type: {}, use text/csv or application/json", other)),
+    };
+    let rows = match parsed {
+        Ok(rows) if rows.is_empty() || rows.len() > admin::MAX_IMPORT_ROWS => {
+            let error_resp = error_response(
+                ErrorCode::InvalidParams,
+                format!("Import must contain 1-{} rows", admin::MAX_IMPORT_ROWS),
+            );
+            return HttpResponse::BadRequest().json(error_resp);
+        }
+        Ok(rows) => validate_rows(rows),
+        Err(e) => {
+            let error_resp = error_response(ErrorCode::InvalidFormat, e);
+            return HttpResponse::BadRequest().json(error_resp);
+        }
+    };
+
+    let mut result = BatchOperationResult::new(rows.len());
+    match query.mode {
+        BatchMode::PerItem => {
+            for (index, row) in rows.iter().enumerate() {
+                let outcome = match row {
+                    Ok(row) => import_row(&state.pg_client, index, row).await,
+                    Err(e) => Err(e.clone()),
+                };
+                match outcome {
+                    Ok(()) => result.add_success(),
+                    Err(e) => result.add_failure(e),
+                }
+            }
+        }
+        BatchMode::Transaction => {
+            let (rows, failures): (Vec<_>, Vec<_>) = rows.into_iter().partition(Result::is_ok);
+            let outcome = if failures.is_empty() {
+                import_in_transaction(&state.pg_client, rows.into_iter().flatten().collect(), &mut result).await
+            } else {
+                abort_transaction(&mut result, failures.into_iter().filter_map(Result::err).collect());
+                Ok(())
+            };
+            if let Err(e) = outcome {
+                let error_resp = error_response(
+                    ErrorCode::DatabaseError,
+                    format!("Import failed: {}", e),
+                );
+                return HttpResponse::InternalServerError().json(error_resp);
+            }
+        }
+    }
+
+    info!(
+        "📥 Admin {} imported users ({:?}): {} succeeded, {} failed",
+        claims.user_id, query.mode, result.success_count, result.failed_count
+    );
+    HttpResponse::Ok().json(SuccessResponse::new(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_csv_line() {
+        assert_eq!(split_csv_line("a,b,,c").unwrap(), vec!["a", "b", "", "c"]);
+        assert_eq!(split_csv_line(r#""x, y","say ""hi""""#).unwrap(), vec!["x, y", r#"say "hi""#]);
+        assert!(split_csv_line(r#""open,b"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let csv = "\u{feff}User_ID,email,role\r\nalice,alice@example.com,admin\r\n\r\nbob,,\r\ncarol,c@example.com\r\ndave,,owner\r\n";
+        let rows = parse_csv(csv).unwrap();
+        assert_eq!(rows.len(), 4);
+
+        let alice = rows[0].as_ref().unwrap();
+        assert_eq!(alice.user_id, "alice");
+        assert_eq!(alice.email.as_deref(), Some("alice@example.com"));
+        assert_eq!(alice.role, Some(UserRoleType::Admin));
+        assert_eq!(rows[1].as_ref().unwrap().email, None);
+        assert!(rows[2].as_ref().unwrap_err().contains("columns"));
+        assert!(rows[3].as_ref().unwrap_err().contains("role"));
+
+        assert!(parse_csv("email\nalice@example.com").is_err());
+        assert!(parse_csv("user_id,nickname\nalice,a").is_err());
+        assert!(parse_csv("").is_err());
+    }
+
+    #[test]
+    fn test_validate_rows() {
+        let row = |user_id: &str, email: Option<&str>, password: Option<&str>| Ok(ImportUserRow {
+            user_id: user_id.to_string(),
+            email: email.map(str::to_string),
+            password: password.map(str::to_string),
+            ..Default::default()
+        });
+
+        let rows = validate_rows(vec![
+            row(" alice ", Some(" Alice@Example.com "), Some("correct horse")),
+            row("bob", Some("alice@example.com"), None),
+            row("", None, None),
+            row("has space", None, None),
+            row("carol", Some("not-an-email"), None),
+            row("dave", None, Some("short")),
+            Err("expected 2 columns, got 3".to_string()),
+        ]);
+
+        let alice = rows[0].as_ref().unwrap();
+        assert_eq!(alice.user_id, "alice");
+        assert_eq!(alice.email.as_deref(), Some("Alice@Example.com"));
+        assert!(rows[1].as_ref().unwrap_err().starts_with("row 2: duplicate email"));
+        assert!(rows[2].as_ref().unwrap_err().starts_with("row 3:"));
+        assert!(rows[3].is_err());
+        assert!(rows[4].as_ref().unwrap_err().contains("email"));
+        assert!(rows[5].as_ref().unwrap_err().contains("password"));
+        assert_eq!(rows[6].as_ref().unwrap_err(), "row 7: expected 2 columns, got 3");
+    }
+}
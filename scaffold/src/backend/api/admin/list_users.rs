@@ -0,0 +1,159 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{DateTime, NaiveDateTime};
+use sea_orm::Order;
+use serde::Deserialize;
+use crate::backend::AppState;
+use crate::backend::api::admin::get_user::AdminUserResponse;
+use crate::backend::api::admin::handle_users::{list_users_page, UserFilter, UserSortField};
+use crate::backend::api::admin::require_admin;
+use crate::backend::config::admin;
+use crate::backend::errors::{ErrorCode, error_response, paginated_response};
+use crate::backend::models::sea_orm_active_enums::UserRoleType;
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl From<SortOrder> for Order {
+    fn from(order: SortOrder) -> Self {
+        match order {
+            SortOrder::Asc => Order::Asc,
+            SortOrder::Desc => Order::Desc,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ListUsersQuery {
+    /// 页码，从 1 开始
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+    /// `Admin` 或 `User`
+    pub role: Option<UserRoleType>,
+    pub is_active: Option<bool>,
+    pub is_verified: Option<bool>,
+    /// 注册时间下限（Unix 时间戳，秒，包含）
+    pub created_from: Option<i64>,
+    /// 注册时间上限（Unix 时间戳，秒，不包含）
+    pub created_to: Option<i64>,
+    /// 在 email、phone、user_id 中模糊搜索
+    pub search: Option<String>,
+    /// `created_at`（默认）、`updated_at`、`user_id`、`email`
+    #[serde(default)]
+    pub sort: UserSortField,
+    /// `asc` 或 `desc`（默认）
+    #[serde(default)]
+    pub order: SortOrder,
+}
+
+/// 规范化分页参数：页码最小为 1，每页条数限制在 1..=`MAX_PAGE_SIZE`
+fn page_params(page: Option<u64>, page_size: Option<u64>) -> (u64, u64) {
+    let page = page.unwrap_or(1).max(1);
+    let page_size = page_size
+        .unwrap_or(admin::DEFAULT_PAGE_SIZE)
+        .clamp(1, admin::MAX_PAGE_SIZE);
+    (page, page_size)
+}
+
+fn timestamp_param(name: &str, value: Option<i64>) -> Result<Option<NaiveDateTime>, String> {
+    value
+        .map(|ts| {
+            DateTime::from_timestamp(ts, 0)
+                .map(|t| t.naive_utc())
+                .ok_or_else(|| format!("{} is not a valid timestamp", name))
+        })
+        .transpose()
+}
+
+/// 用户列表
+///
+/// 路由: GET /v2/admin/users
+///
+/// 支持按角色、启用状态、验证状态、注册时间筛选，按 email / phone / user_id 搜索，排序与分页。
+/// 返回 `PaginatedData`。
+pub async fn list_users(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<ListUsersQuery>,
+) -> HttpResponse {
+    if let Err(resp) = require_admin(&req, &state.pg_client).await {
+        return resp;
+    }
+
+    let (created_from, created_to) = match (
+        timestamp_param("created_from", query.created_from),
+        timestamp_param("created_to", query.created_to),
+    ) {
+        (Ok(from), Ok(to)) => (from, to),
+        (Err(e), _) | (_, Err(e)) => {
+            let error_resp = error_response(ErrorCode::InvalidParams, e);
+            return HttpResponse::BadRequest().json(error_resp);
+        }
+    };
+
+    let search = query.search.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    if search.is_some_and(|s| s.chars().count() > admin::SEARCH_MAX_LEN) {
+        let error_resp = error_response(
+            ErrorCode::InvalidParams,
+            format!("search must be at most {} characters", admin::SEARCH_MAX_LEN),
+        );
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+
+    let filter = UserFilter {
+        role: query.role.clone(),
+        is_active: query.is_active,
+        is_verified: query.is_verified,
+        created_from,
+        created_to,
+        search,
+    };
+    let (page, page_size) = page_params(query.page, query.page_size);
+
+    match list_users_page(&state.pg_client, &filter, query.sort, query.order.into(), page, page_size).await {
+        Ok((users, total)) => {
+            let users: Vec<AdminUserResponse> = users.into_iter().map(AdminUserResponse::from).collect();
+            HttpResponse::Ok().json(paginated_response(users, total, page, page_size))
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Database error: {}", e),
+            );
+            HttpResponse::InternalServerError().json(error_resp)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_params() {
+        assert_eq!(page_params(None, None), (1, admin::DEFAULT_PAGE_SIZE));
+        assert_eq!(page_params(Some(0), Some(0)), (1, 1));
+        assert_eq!(page_params(Some(3), Some(10_000)), (3, admin::MAX_PAGE_SIZE));
+    }
+
+    #[test]
+    fn test_list_users_query() {
+        let query = web::Query::<ListUsersQuery>::from_query(
+            "role=Admin&is_active=false&sort=user_id&order=asc&created_from=1700000000",
+        )
+        .unwrap();
+        assert_eq!(query.role, Some(UserRoleType::Admin));
+        assert_eq!(query.is_active, Some(false));
+        assert_eq!(query.sort, UserSortField::UserId);
+        assert_eq!(query.order, SortOrder::Asc);
+        assert!(timestamp_param("created_from", query.created_from).unwrap().is_some());
+
+        let defaults = web::Query::<ListUsersQuery>::from_query("").unwrap();
+        assert_eq!(defaults.sort, UserSortField::CreatedAt);
+        assert_eq!(defaults.order, SortOrder::Desc);
+    }
+}
@@ -0,0 +1,62 @@
+mod handle_users;
+mod get_user;
+mod list_users;
+mod update_user;
+mod delete_user;
+mod batch_users;
+mod import_users;
+
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError, Scope};
+use sea_orm::{DatabaseConnection, DbErr};
+use crate::backend::api::admin::batch_users::batch_users;
+use crate::backend::api::admin::delete_user::delete_user;
+use crate::backend::api::admin::get_user::get_user;
+use crate::backend::api::admin::handle_users::find_user;
+use crate::backend::api::admin::import_users::import_users;
+use crate::backend::api::admin::list_users::list_users;
+use crate::backend::api::admin::update_user::update_user;
+use crate::backend::errors::{ErrorCode, error_response};
+use crate::backend::models::sea_orm_active_enums::UserRoleType;
+use crate::backend::utils::extractors::extract_claims_from_request;
+use crate::backend::utils::jwt::Claims;
+
+/// 用户当前是否为已启用的管理员，以数据库为准
+pub async fn is_active_admin(db: &DatabaseConnection, user_id: &str) -> Result<bool, DbErr> {
+    let user = find_user(db, user_id).await?;
+    Ok(user.is_some_and(|user| user.role == UserRoleType::Admin && user.is_active != Some(false)))
+}
+
+/// 管理接口仅限管理员调用
+///
+/// 以数据库中的当前角色与启用状态为准，不信任 token 中的 `role`：降级或停用的管理员立即失去权限。
+/// API Key、OAuth 客户端与服务账号的 token 一律拒绝，只接受管理员本人的登录会话。
+pub async fn require_admin(req: &HttpRequest, db: &DatabaseConnection) -> Result<Claims, HttpResponse> {
+    let claims = extract_claims_from_request(req).map_err(|e| e.error_response())?;
+    let forbidden = || {
+        let error_resp = error_response(ErrorCode::PermissionDenied, "Admin permission required");
+        HttpResponse::Forbidden().json(error_resp)
+    };
+    if !claims.is_first_party() {
+        return Err(forbidden());
+    }
+
+    match is_active_admin(db, &claims.user_id).await {
+        Ok(true) => Ok(claims),
+        Ok(false) => Err(forbidden()),
+        Err(e) => {
+            let error_resp = error_response(ErrorCode::DatabaseError, format!("Database error: {}", e));
+            Err(HttpResponse::InternalServerError().json(error_resp))
+        }
+    }
+}
+
+/// 管理员接口（需要认证，仅限管理员）
+pub fn admin_scope() -> Scope {
+    web::scope("/admin")
+        .route("/users", web::get().to(list_users))
+        .route("/users/batch", web::post().to(batch_users))
+        .route("/users/import", web::post().to(import_users))
+        .route("/users/{user_id}", web::get().to(get_user))
+        .route("/users/{user_id}", web::patch().to(update_user))
+        .route("/users/{user_id}", web::delete().to(delete_user))
+}
@@ -0,0 +1,96 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+use tracing::info;
+use crate::backend::AppState;
+use crate::backend::api::admin::get_user::AdminUserResponse;
+use crate::backend::api::admin::handle_users::{update_user as update_user_record, UserUpdate};
+use crate::backend::api::admin::require_admin;
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+use crate::backend::models::sea_orm_active_enums::UserRoleType;
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateUserRequest {
+    /// `Admin` 或 `User`
+    pub role: Option<UserRoleType>,
+    pub is_active: Option<bool>,
+    pub is_verified: Option<bool>,
+}
+
+/// 校验修改内容；管理员不能修改自己的角色或停用自己，避免失去管理权限
+fn validate_update(admin_id: &str, user_id: &str, request: &UpdateUserRequest) -> Result<(), &'static str> {
+    if request.role.is_none() && request.is_active.is_none() && request.is_verified.is_none() {
+        return Err("Nothing to update");
+    }
+    if admin_id == user_id && (request.role.is_some() || request.is_active == Some(false)) {
+        return Err("Admins cannot change their own role or deactivate themselves");
+    }
+    Ok(())
+}
+
+/// 修改用户角色与状态
+///
+/// 路由: PATCH /v2/admin/users/{user_id}
+///
+/// 修改角色或启用状态后，该用户此前签发的 token 立即失效；停用后也无法再通过 API Key 认证或刷新 token。
+pub async fn update_user(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    user_id: web::Path<String>,
+    request: web::Json<UpdateUserRequest>,
+) -> HttpResponse {
+    let claims = match require_admin(&req, &state.pg_client).await {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    if let Err(e) = validate_update(&claims.user_id, &user_id, &request) {
+        let error_resp = error_response(ErrorCode::InvalidParams, e);
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+
+    let request = request.into_inner();
+    info!(
+        "🛠️ Admin {} updating user {}: role={:?}, is_active={:?}, is_verified={:?}",
+        claims.user_id, user_id, request.role, request.is_active, request.is_verified
+    );
+
+    match update_user_record(&state.pg_client, &user_id, UserUpdate {
+        role: request.role,
+        is_active: request.is_active,
+        is_verified: request.is_verified,
+    }).await {
+        Ok(Some(user)) => HttpResponse::Ok().json(SuccessResponse::new(AdminUserResponse::from(user))),
+        Ok(None) => {
+            let error_resp = error_response(ErrorCode::NotFound, "User not found");
+            HttpResponse::NotFound().json(error_resp)
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Failed to update user: {}", e),
+            );
+            HttpResponse::InternalServerError().json(error_resp)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(value: serde_json::Value) -> UpdateUserRequest {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_validate_update() {
+        assert!(validate_update("root", "alice", &request(serde_json::json!({}))).is_err());
+        assert!(validate_update("root", "alice", &request(serde_json::json!({ "role": "Admin" }))).is_ok());
+        assert!(validate_update("root", "alice", &request(serde_json::json!({ "is_active": false }))).is_ok());
+
+        // 不能修改自己的角色或停用自己
+        assert!(validate_update("root", "root", &request(serde_json::json!({ "role": "User" }))).is_err());
+        assert!(validate_update("root", "root", &request(serde_json::json!({ "is_active": false }))).is_err());
+        assert!(validate_update("root", "root", &request(serde_json::json!({ "is_verified": true }))).is_ok());
+    }
+}
@@ -0,0 +1,102 @@
+use actix_web::http::Method;
+use chrono::Utc;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use tracing::warn;
+use crate::backend::api::api_keys::handle_api_keys::{find_usable_api_key, touch_api_key};
+use crate::backend::config::{api_key, jwt};
+use crate::backend::models::users;
+use crate::backend::utils::hash::hash_str;
+use crate::backend::utils::jwt::Claims;
+
+/// 校验 scope 列表，返回规范化（去重、按固定顺序）后的空格分隔字符串
+pub fn normalize_scopes(scopes: &[String]) -> Result<String, String> {
+    if scopes.is_empty() {
+        return Err("At least one scope is required".to_string());
+    }
+    if let Some(unknown) = scopes
+        .iter()
+        .find(|s| s.as_str() != api_key::SCOPE_READ && s.as_str() != api_key::SCOPE_WRITE)
+    {
+        return Err(format!("Unknown scope: {}", unknown));
+    }
+
+    let normalized: Vec<&str> = [api_key::SCOPE_READ, api_key::SCOPE_WRITE]
+        .into_iter()
+        .filter(|known| scopes.iter().any(|s| s == known))
+        .collect();
+    Ok(normalized.join(" "))
+}
+
+/// key 的 scope 是否允许该请求方法：`read` 只允许安全方法，`write` 允许全部
+pub fn scope_allows_method(scopes: &str, method: &Method) -> bool {
+    let mut granted = scopes.split_whitespace();
+    if granted.clone().any(|s| s == api_key::SCOPE_WRITE) {
+        return true;
+    }
+    let safe = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+    safe && granted.any(|s| s == api_key::SCOPE_READ)
+}
+
+/// 使用 API Key 认证，返回 key 所属用户的 Claims；`None` 表示 key 无效、已吊销、已过期或用户已停用
+///
+/// Claims 的 `exp` 不超过一个 JWT 有效期，`scope` 为 key 的 scope。
+pub async fn authenticate_api_key(db: &DatabaseConnection, key: &str) -> Result<Option<Claims>, DbErr> {
+    let Some(stored) = find_usable_api_key(db, &hash_str(key)).await? else {
+        return Ok(None);
+    };
+
+    let user = users::Entity::find()
+        .filter(users::Column::UserId.eq(&stored.user_id))
+        .one(db)
+        .await?
+        .filter(|u| u.is_active != Some(false));
+    let Some(user) = user else {
+        warn!("API key {} belongs to inactive or unknown user {}", stored.id, stored.user_id);
+        return Ok(None);
+    };
+
+    if let Err(e) = touch_api_key(db, stored.id).await {
+        warn!("Failed to update last_used_at of API key {}: {}", stored.id, e);
+    }
+
+    let jwt_exp = Utc::now().timestamp() as usize + jwt::DEFAULT_EXPIRATION_SECONDS;
+    let exp = stored
+        .expires_at
+        .map(|t| (t.and_utc().timestamp() as usize).min(jwt_exp))
+        .unwrap_or(jwt_exp);
+
+    Ok(Some(Claims {
+        user_id: user.user_id.clone(),
+        username: user.user_id,
+        role: Some(user.role),
+        exp,
+        scope: Some(stored.scopes),
+        api_key_id: Some(stored.id),
+        ..Default::default()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_scopes() {
+        let scopes = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(normalize_scopes(&scopes(&["write", "read", "write"])).unwrap(), "read write");
+        assert_eq!(normalize_scopes(&scopes(&["read"])).unwrap(), "read");
+        assert!(normalize_scopes(&scopes(&[])).is_err());
+        assert!(normalize_scopes(&scopes(&["admin"])).is_err());
+    }
+
+    #[test]
+    fn test_scope_allows_method() {
+        assert!(scope_allows_method("read", &Method::GET));
+        assert!(!scope_allows_method("read", &Method::POST));
+        assert!(!scope_allows_method("read", &Method::DELETE));
+        assert!(scope_allows_method("write", &Method::GET));
+        assert!(scope_allows_method("read write", &Method::PUT));
+        assert!(!scope_allows_method("", &Method::GET));
+    }
+}
@@ -0,0 +1,148 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use crate::backend::AppState;
+use crate::backend::api::api_keys::api_key_auth::normalize_scopes;
+use crate::backend::api::api_keys::handle_api_keys::{count_usable_api_keys, insert_api_key, NewApiKey};
+use crate::backend::api::oauth::generate_secret;
+use crate::backend::api::user::require_first_party_session;
+use crate::backend::config::api_key;
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+use crate::backend::models::api_keys;
+use crate::backend::utils::hash::hash_str;
+
+#[derive(Deserialize, Debug)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    /// `read` 和 / 或 `write`
+    pub scopes: Vec<String>,
+    /// 有效期（天），不传表示永不过期
+    pub expires_in_days: Option<i64>,
+}
+
+/// 返回给客户端的 key 信息（不含明文）
+#[derive(Serialize, Debug)]
+pub struct ApiKeyResponse {
+    pub id: i64,
+    pub name: String,
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<i64>,
+    pub created_at: i64,
+    pub last_used_at: Option<i64>,
+}
+
+impl From<api_keys::Model> for ApiKeyResponse {
+    fn from(key: api_keys::Model) -> Self {
+        Self {
+            id: key.id,
+            name: key.name,
+            key_prefix: key.key_prefix,
+            scopes: key.scopes.split_whitespace().map(str::to_string).collect(),
+            expires_at: key.expires_at.map(|t| t.and_utc().timestamp()),
+            created_at: key.created_at.and_utc().timestamp(),
+            last_used_at: key.last_used_at.map(|t| t.and_utc().timestamp()),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct CreateApiKeyResponse {
+    #[serde(flatten)]
+    pub key: ApiKeyResponse,
+    /// 仅在创建时返回一次，服务端只保存其哈希
+    pub api_key: String,
+}
+
+/// 创建 API Key
+///
+/// 路由: POST /v2/api-keys
+///
+/// 请求 `/v2` 接口时通过 `X-API-Key: <key>` 或 `Authorization: ApiKey <key>` 携带。
+/// API Key 只能在用户本人的登录会话中管理，不能用一个 key 或 OAuth token 创建其他 key。
+pub async fn create_api_key(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    request: web::Json<CreateApiKeyRequest>,
+) -> HttpResponse {
+    let claims = match require_first_party_session(&req) {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    let name = request.name.trim();
+    if name.is_empty() || name.chars().count() > api_key::NAME_MAX_LEN {
+        let error_resp = error_response(
+            ErrorCode::InvalidFormat,
+            format!("name must be 1-{} characters", api_key::NAME_MAX_LEN),
+        );
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+
+    let scopes = match normalize_scopes(&request.scopes) {
+        Ok(scopes) => scopes,
+        Err(e) => {
+            let error_resp = error_response(ErrorCode::InvalidParams, e);
+            return HttpResponse::BadRequest().json(error_resp);
+        }
+    };
+
+    let expires_at = match request.expires_in_days {
+        Some(days) if !(1..=api_key::MAX_EXPIRES_IN_DAYS).contains(&days) => {
+            let error_resp = error_response(
+                ErrorCode::InvalidParams,
+                format!("expires_in_days must be 1-{}", api_key::MAX_EXPIRES_IN_DAYS),
+            );
+            return HttpResponse::BadRequest().json(error_resp);
+        }
+        Some(days) => Some(Utc::now().naive_utc() + Duration::days(days)),
+        None => None,
+    };
+
+    match count_usable_api_keys(&state.pg_client, &claims.user_id).await {
+        Ok(count) if count >= api_key::MAX_KEYS_PER_USER => {
+            let error_resp = error_response(
+                ErrorCode::RateLimitExceeded,
+                "Too many API keys, revoke an unused key first",
+            );
+            return HttpResponse::TooManyRequests().json(error_resp);
+        }
+        Ok(_) => {}
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Database error: {}", e),
+            );
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    }
+
+    let plain_key = format!("{}{}", api_key::KEY_PREFIX, generate_secret());
+    let key_prefix: String = plain_key.chars().take(api_key::DISPLAY_PREFIX_LEN).collect();
+    let key_hash = hash_str(&plain_key);
+
+    match insert_api_key(&state.pg_client, NewApiKey {
+        user_id: &claims.user_id,
+        name,
+        key_prefix: &key_prefix,
+        key_hash: &key_hash,
+        scopes: &scopes,
+        expires_at,
+    }).await {
+        Ok(key) => {
+            info!("🔑 User {} created API key {} ({}, scopes: {})", claims.user_id, key.id, name, scopes);
+            HttpResponse::Ok().json(SuccessResponse::new(CreateApiKeyResponse {
+                key: key.into(),
+                api_key: plain_key,
+            }))
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Failed to create API key: {}", e),
+            );
+            HttpResponse::InternalServerError().json(error_resp)
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::sea_query::Condition;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, Set,
+};
+use tracing::info;
+use crate::backend::config::api_key;
+use crate::backend::models::api_keys;
+use crate::backend::models::prelude::ApiKeys;
+
+pub struct NewApiKey<'a> {
+    pub user_id: &'a str,
+    pub name: &'a str,
+    pub key_prefix: &'a str,
+    pub key_hash: &'a str,
+    pub scopes: &'a str,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+pub async fn insert_api_key(db: &DatabaseConnection, key: NewApiKey<'_>) -> Result<api_keys::Model, DbErr> {
+    let new_key = api_keys::ActiveModel {
+        user_id: Set(key.user_id.to_string()),
+        name: Set(key.name.to_string()),
+        key_prefix: Set(key.key_prefix.to_string()),
+        key_hash: Set(key.key_hash.to_string()),
+        scopes: Set(key.scopes.to_string()),
+        expires_at: Set(key.expires_at),
+        created_at: Set(Utc::now().naive_utc()),
+        ..Default::default()
+    };
+
+    let inserted = new_key.insert(db).await?;
+    info!("Created API key {} for user {}", inserted.id, key.user_id);
+    Ok(inserted)
+}
+
+/// 未吊销且未过期
+fn usable(now: NaiveDateTime) -> Condition {
+    Condition::all()
+        .add(api_keys::Column::RevokedAt.is_null())
+        .add(
+            Condition::any()
+                .add(api_keys::Column::ExpiresAt.is_null())
+                .add(api_keys::Column::ExpiresAt.gt(now)),
+        )
+}
+
+/// 用户未吊销的 key（包括已过期的），按创建时间倒序
+pub async fn list_api_keys(db: &DatabaseConnection, user_id: &str) -> Result<Vec<api_keys::Model>, DbErr> {
+    ApiKeys::find()
+        .filter(api_keys::Column::UserId.eq(user_id))
+        .filter(api_keys::Column::RevokedAt.is_null())
+        .order_by_desc(api_keys::Column::CreatedAt)
+        .all(db)
+        .await
+}
+
+pub async fn count_usable_api_keys(db: &DatabaseConnection, user_id: &str) -> Result<u64, DbErr> {
+    ApiKeys::find()
+        .filter(api_keys::Column::UserId.eq(user_id))
+        .filter(usable(Utc::now().naive_utc()))
+        .count(db)
+        .await
+}
+
+pub async fn find_usable_api_key(db: &DatabaseConnection, key_hash: &str) -> Result<Option<api_keys::Model>, DbErr> {
+    ApiKeys::find()
+        .filter(api_keys::Column::KeyHash.eq(key_hash))
+        .filter(usable(Utc::now().naive_utc()))
+        .one(db)
+        .await
+}
+
+/// 更新最近使用时间；距上次更新不足 `LAST_USED_UPDATE_INTERVAL_SECONDS` 时跳过
+pub async fn touch_api_key(db: &DatabaseConnection, id: i64) -> Result<(), DbErr> {
+    let now = Utc::now().naive_utc();
+    let stale_before = now - Duration::seconds(api_key::LAST_USED_UPDATE_INTERVAL_SECONDS);
+    ApiKeys::update_many()
+        .col_expr(api_keys::Column::LastUsedAt, now.into())
+        .filter(api_keys::Column::Id.eq(id))
+        .filter(
+            Condition::any()
+                .add(api_keys::Column::LastUsedAt.is_null())
+                .add(api_keys::Column::LastUsedAt.lt(stale_before)),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// 吊销用户自己的 key，返回 `false` 表示 key 不存在或已吊销
+pub async fn revoke_api_key(db: &DatabaseConnection, user_id: &str, id: i64) -> Result<bool, DbErr> {
+    let result = ApiKeys::update_many()
+        .col_expr(api_keys::Column::RevokedAt, Utc::now().naive_utc().into())
+        .filter(api_keys::Column::Id.eq(id))
+        .filter(api_keys::Column::UserId.eq(user_id))
+        .filter(api_keys::Column::RevokedAt.is_null())
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected > 0)
+}
+
+/// 吊销用户的全部 key（修改密码后调用）
+pub async fn revoke_api_keys_of_user<C: ConnectionTrait>(db: &C, user_id: &str) -> Result<u64, DbErr> {
+    let result = ApiKeys::update_many()
+        .col_expr(api_keys::Column::RevokedAt, Utc::now().naive_utc().into())
+        .filter(api_keys::Column::UserId.eq(user_id))
+        .filter(api_keys::Column::RevokedAt.is_null())
+        .exec(db)
+        .await?;
+
+    if result.rows_affected > 0 {
+        info!("Revoked {} API keys of user {}", result.rows_affected, user_id);
+    }
+    Ok(result.rows_affected)
+}
@@ -0,0 +1,30 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use crate::backend::AppState;
+use crate::backend::api::api_keys::create_api_key::ApiKeyResponse;
+use crate::backend::api::api_keys::handle_api_keys::list_api_keys as list_user_api_keys;
+use crate::backend::api::user::require_first_party_session;
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+
+/// 当前用户未吊销的 API Key（不含明文）
+///
+/// 路由: GET /v2/api-keys
+pub async fn list_api_keys(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    let claims = match require_first_party_session(&req) {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    match list_user_api_keys(&state.pg_client, &claims.user_id).await {
+        Ok(keys) => {
+            let keys: Vec<ApiKeyResponse> = keys.into_iter().map(ApiKeyResponse::from).collect();
+            HttpResponse::Ok().json(SuccessResponse::new(keys))
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Database error: {}", e),
+            );
+            HttpResponse::InternalServerError().json(error_resp)
+        }
+    }
+}
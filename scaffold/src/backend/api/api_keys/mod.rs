@@ -0,0 +1,21 @@
+mod handle_api_keys;
+mod api_key_auth;
+mod create_api_key;
+mod list_api_keys;
+mod revoke_api_key;
+
+use actix_web::{Scope, web};
+use crate::backend::api::api_keys::create_api_key::create_api_key;
+use crate::backend::api::api_keys::list_api_keys::list_api_keys;
+use crate::backend::api::api_keys::revoke_api_key::revoke_api_key;
+
+pub use crate::backend::api::api_keys::api_key_auth::{authenticate_api_key, scope_allows_method};
+pub use crate::backend::api::api_keys::handle_api_keys::revoke_api_keys_of_user;
+
+/// API Key 管理（需要登录会话，不能使用 API Key 调用）
+pub fn api_keys_scope() -> Scope {
+    web::scope("/api-keys")
+        .route("", web::post().to(create_api_key))
+        .route("", web::get().to(list_api_keys))
+        .route("/{id}", web::delete().to(revoke_api_key))
+}
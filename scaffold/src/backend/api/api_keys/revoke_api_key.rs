@@ -0,0 +1,38 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use tracing::info;
+use crate::backend::AppState;
+use crate::backend::api::api_keys::handle_api_keys::revoke_api_key as revoke_user_api_key;
+use crate::backend::api::user::require_first_party_session;
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+
+/// 吊销 API Key，吊销后立即失效
+///
+/// 路由: DELETE /v2/api-keys/{id}
+pub async fn revoke_api_key(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    id: web::Path<i64>,
+) -> HttpResponse {
+    let claims = match require_first_party_session(&req) {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    match revoke_user_api_key(&state.pg_client, &claims.user_id, *id).await {
+        Ok(true) => {
+            info!("🗑️ User {} revoked API key {}", claims.user_id, id);
+            HttpResponse::Ok().json(SuccessResponse::new(serde_json::json!({ "id": *id, "revoked": true })))
+        }
+        Ok(false) => {
+            let error_resp = error_response(ErrorCode::NotFound, "API key not found");
+            HttpResponse::NotFound().json(error_resp)
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Failed to revoke API key: {}", e),
+            );
+            HttpResponse::InternalServerError().json(error_resp)
+        }
+    }
+}
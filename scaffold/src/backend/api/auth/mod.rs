@@ -1,4 +1,5 @@
 use actix_web::{Scope, web};
+use crate::backend::api::social::social_scope;
 
 
 // mod register;
@@ -11,6 +12,7 @@ use actix_web::{Scope, web};
 
 pub fn auth_scope() -> Scope {
     web::scope("/auth")
+        .service(social_scope())                         // 第三方登录
     //     .route("/register", web::post().to(register))  // 用户注册
     //     .route("/login", web::post().to(login))        // 用户登录
     //     .route("/logout", web::post().to(logout))      // 用户登出
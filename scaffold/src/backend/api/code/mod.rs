@@ -6,6 +6,9 @@ use actix_web::{Scope, web};
 use crate::backend::api::code::send_email::send_email_code;
 use crate::backend::api::code::send_phone::send_phone_code;
 
+pub use send_email::send_email as send_verification_email;
+pub use send_phone::send_sms_code;
+
 pub fn code_scope() -> Scope {
     web::scope("/code")
         .route("/send-email", web::post().to(send_email_code))
@@ -24,7 +24,7 @@ fn generate_code(length: usize) -> String {
         .collect()
 }
 
-async fn send_email(email: &str, code: &str) -> Result<(), String> {
+pub async fn send_email(email: &str, code: &str) -> Result<(), String> {
     let email_content = format!(
         "Hello,\n\nYour verification code is: {}\n\nThis code is valid for 10 minutes.",
         code
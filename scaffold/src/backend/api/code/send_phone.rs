@@ -1,6 +1,6 @@
 use actix_web::{HttpResponse, web};
 use serde::Deserialize;
-use tracing::info;
+use tracing::{info, warn};
 use crate::backend::AppState;
 use crate::backend::errors::SuccessResponse;
 
@@ -25,4 +25,15 @@ pub async fn send_phone_code(
     HttpResponse::Ok().json(SuccessResponse::new(PhoneResponse {
         message: "phone code sent successfully".to_string(),
     }))
-}
\ No newline at end of file
+}
+
+/// 发送短信验证码
+///
+/// 尚未接入短信服务商：debug 构建中把验证码写入日志便于本地调试，release 构建直接返回错误。
+pub async fn send_sms_code(phone: &str, code: &str) -> Result<(), String> {
+    if cfg!(debug_assertions) {
+        warn!("📱 SMS provider not configured, verification code for {}: {}", phone, code);
+        return Ok(());
+    }
+    Err("SMS provider is not configured".to_string())
+}
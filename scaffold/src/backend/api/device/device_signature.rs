@@ -0,0 +1,168 @@
+use chrono::Utc;
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use tracing::warn;
+use crate::backend::api::device::handle_device_keys::{
+    consume_nonce, find_active_device, has_registered_device, touch_device,
+};
+use crate::backend::config::{device_key, QrLoginPolicy};
+use crate::backend::errors::{AppError, ErrorCode};
+use crate::backend::utils::signing::verify_ed25519;
+
+/// App 端对确认 / 拒绝登录请求的设备签名
+///
+/// 签名消息为 `device-approval|v1|<action>|<subject>|<nonce>|<timestamp>`，
+/// 使用设备注册时上传公钥对应的 Ed25519 私钥签名。`subject` 在确认 / 拒绝登录时为会话 ID，
+/// 注册设备时为新设备的公钥，吊销设备时为被吊销的设备 ID。
+#[derive(Deserialize, Debug, Clone)]
+pub struct DeviceSignature {
+    pub device_id: String,
+    /// 每次请求随机生成，同一设备不可重复使用
+    pub nonce: String,
+    /// 签名时的 Unix 时间戳（秒）
+    pub timestamp: i64,
+    /// URL 安全 Base64 编码的签名
+    pub signature: String,
+}
+
+impl DeviceSignature {
+    /// 待签名的消息
+    pub fn signing_message(&self, action: &str, subject: &str) -> String {
+        format!(
+            "device-approval|v{}|{}|{}|{}|{}",
+            device_key::SIGNATURE_VERSION,
+            action,
+            subject,
+            self.nonce,
+            self.timestamp
+        )
+    }
+
+    /// 检查 nonce 格式与时间戳是否在允许的偏差内
+    fn check_freshness(&self, now: i64) -> Result<(), AppError> {
+        let nonce_valid = (device_key::NONCE_MIN_LEN..=device_key::NONCE_MAX_LEN).contains(&self.nonce.len())
+            && self.nonce.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !nonce_valid {
+            return Err(AppError::custom(ErrorCode::InvalidParams, "Invalid signature nonce"));
+        }
+
+        if (now - self.timestamp).abs() > device_key::SIGNATURE_MAX_SKEW_SECONDS {
+            return Err(AppError::custom(ErrorCode::DeviceSignatureInvalid, "Signature timestamp is out of range"));
+        }
+
+        Ok(())
+    }
+}
+
+/// 校验 App 端对登录会话的设备签名
+///
+/// 未携带签名时，只有在策略未强制要求且用户从未注册过设备时才放行；
+/// 吊销全部设备不会回到免签名状态。返回签名设备的 ID。
+pub async fn verify_device_signature(
+    db: &DatabaseConnection,
+    user_id: &str,
+    action: &str,
+    session_id: &str,
+    signature: Option<&DeviceSignature>,
+    policy: &QrLoginPolicy,
+) -> Result<Option<String>, AppError> {
+    let Some(signature) = signature else {
+        if policy.require_device_signature || has_registered_device(db, user_id).await? {
+            return Err(AppError::custom(ErrorCode::MissingRequiredField, "device_signature is required"));
+        }
+        return Ok(None);
+    };
+
+    verify_signed_action(db, user_id, action, session_id, signature).await.map(Some)
+}
+
+/// 校验用户已注册设备对 `action` + `subject` 的签名
+///
+/// 校验通过后记录 nonce，同一签名不能再次使用。返回签名设备的 ID。
+pub async fn verify_signed_action(
+    db: &DatabaseConnection,
+    user_id: &str,
+    action: &str,
+    subject: &str,
+    signature: &DeviceSignature,
+) -> Result<String, AppError> {
+    signature.check_freshness(Utc::now().timestamp())?;
+
+    let device = match find_active_device(db, &signature.device_id).await? {
+        Some(device) if device.user_id == user_id => device,
+        _ => {
+            warn!("User {} used unknown or revoked device {}", user_id, signature.device_id);
+            return Err(AppError::custom(ErrorCode::DeviceSignatureInvalid, "Unknown or revoked device"));
+        }
+    };
+
+    let public_key = bs58::decode(&device.public_key)
+        .into_vec()
+        .map_err(|e| AppError::internal(format!("Invalid stored public key: {}", e)))?;
+    let message = signature.signing_message(action, subject);
+    if !verify_ed25519(&public_key, message.as_bytes(), &signature.signature) {
+        warn!("Invalid device signature from device {} for {} {}", device.device_id, action, subject);
+        return Err(AppError::custom(ErrorCode::DeviceSignatureInvalid, "Invalid device signature"));
+    }
+
+    // 签名有效后才记录 nonce，避免伪造请求消耗合法 nonce
+    if !consume_nonce(db, &device.device_id, &signature.nonce).await? {
+        warn!("Replayed device signature from device {} for {} {}", device.device_id, action, subject);
+        return Err(AppError::custom(ErrorCode::DeviceSignatureInvalid, "Signature nonce has already been used"));
+    }
+
+    if let Err(e) = touch_device(db, &device.device_id).await {
+        warn!("Failed to update last_used_at for device {}: {}", device.device_id, e);
+    }
+
+    Ok(device.device_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn signature(nonce: &str, timestamp: i64) -> DeviceSignature {
+        DeviceSignature {
+            device_id: "device".to_string(),
+            nonce: nonce.to_string(),
+            timestamp,
+            signature: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_signing_message_binds_action_and_session() {
+        let sig = signature("0123456789abcdef", 1700000000);
+        assert_eq!(
+            sig.signing_message(device_key::ACTION_CONFIRM, "session"),
+            "device-approval|v1|confirm|session|0123456789abcdef|1700000000"
+        );
+
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let message = sig.signing_message(device_key::ACTION_CONFIRM, "session");
+        let signed = URL_SAFE_NO_PAD.encode(key_pair.sign(message.as_bytes()).as_ref());
+        let public_key = key_pair.public_key().as_ref();
+
+        assert!(verify_ed25519(public_key, message.as_bytes(), &signed));
+        let other_session = sig.signing_message(device_key::ACTION_CONFIRM, "other");
+        assert!(!verify_ed25519(public_key, other_session.as_bytes(), &signed));
+        let other_action = sig.signing_message(device_key::ACTION_REJECT, "session");
+        assert!(!verify_ed25519(public_key, other_action.as_bytes(), &signed));
+    }
+
+    #[test]
+    fn test_check_freshness() {
+        let now = 1700000000;
+        assert!(signature("0123456789abcdef", now).check_freshness(now).is_ok());
+        assert!(signature("0123456789abcdef", now - 60).check_freshness(now).is_ok());
+        assert!(signature("0123456789abcdef", now - 3600).check_freshness(now).is_err());
+        assert!(signature("0123456789abcdef", now + 3600).check_freshness(now).is_err());
+        assert!(signature("short", now).check_freshness(now).is_err());
+        assert!(signature("0123456789abcdef|x", now).check_freshness(now).is_err());
+    }
+}
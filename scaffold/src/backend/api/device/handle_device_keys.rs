@@ -0,0 +1,199 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, Set,
+};
+use tracing::info;
+use crate::backend::models::{device_key_nonces, device_keys, device_step_up_failures};
+use crate::backend::models::prelude::{DeviceKeyNonces, DeviceKeys, DeviceStepUpFailures};
+
+pub async fn insert_device_key(
+    db: &DatabaseConnection,
+    device_id: &str,
+    user_id: &str,
+    public_key: &str,
+    name: Option<&str>,
+) -> Result<device_keys::Model, DbErr> {
+    let new_device = device_keys::ActiveModel {
+        device_id: Set(device_id.to_string()),
+        user_id: Set(user_id.to_string()),
+        public_key: Set(public_key.to_string()),
+        name: Set(name.map(str::to_string)),
+        created_at: Set(Utc::now().naive_utc()),
+        ..Default::default()
+    };
+
+    let inserted = new_device.insert(db).await?;
+    info!("Registered device key {} for user {}", device_id, user_id);
+    Ok(inserted)
+}
+
+/// 用户未吊销的设备，按注册时间倒序
+pub async fn list_active_devices(
+    db: &DatabaseConnection,
+    user_id: &str,
+) -> Result<Vec<device_keys::Model>, DbErr> {
+    DeviceKeys::find()
+        .filter(device_keys::Column::UserId.eq(user_id))
+        .filter(device_keys::Column::RevokedAt.is_null())
+        .order_by_desc(device_keys::Column::CreatedAt)
+        .all(db)
+        .await
+}
+
+pub async fn count_active_devices(db: &DatabaseConnection, user_id: &str) -> Result<u64, DbErr> {
+    DeviceKeys::find()
+        .filter(device_keys::Column::UserId.eq(user_id))
+        .filter(device_keys::Column::RevokedAt.is_null())
+        .count(db)
+        .await
+}
+
+/// 用户是否注册过设备（包括已吊销的设备）
+pub async fn has_registered_device(db: &DatabaseConnection, user_id: &str) -> Result<bool, DbErr> {
+    let count = DeviceKeys::find()
+        .filter(device_keys::Column::UserId.eq(user_id))
+        .count(db)
+        .await?;
+    Ok(count > 0)
+}
+
+pub async fn find_active_device(
+    db: &DatabaseConnection,
+    device_id: &str,
+) -> Result<Option<device_keys::Model>, DbErr> {
+    DeviceKeys::find()
+        .filter(device_keys::Column::DeviceId.eq(device_id))
+        .filter(device_keys::Column::RevokedAt.is_null())
+        .one(db)
+        .await
+}
+
+/// 吊销用户的设备，返回是否有设备被吊销
+pub async fn revoke_device(
+    db: &DatabaseConnection,
+    user_id: &str,
+    device_id: &str,
+) -> Result<bool, DbErr> {
+    let result = DeviceKeys::update_many()
+        .col_expr(device_keys::Column::RevokedAt, Utc::now().naive_utc().into())
+        .filter(device_keys::Column::DeviceId.eq(device_id))
+        .filter(device_keys::Column::UserId.eq(user_id))
+        .filter(device_keys::Column::RevokedAt.is_null())
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected > 0)
+}
+
+/// 吊销用户的全部设备（修改密码后调用）
+pub async fn revoke_devices_of_user<C: ConnectionTrait>(db: &C, user_id: &str) -> Result<u64, DbErr> {
+    let result = DeviceKeys::update_many()
+        .col_expr(device_keys::Column::RevokedAt, Utc::now().naive_utc().into())
+        .filter(device_keys::Column::UserId.eq(user_id))
+        .filter(device_keys::Column::RevokedAt.is_null())
+        .exec(db)
+        .await?;
+
+    if result.rows_affected > 0 {
+        info!("Revoked {} devices of user {}", result.rows_affected, user_id);
+    }
+    Ok(result.rows_affected)
+}
+
+pub async fn touch_device(db: &DatabaseConnection, device_id: &str) -> Result<(), DbErr> {
+    DeviceKeys::update_many()
+        .col_expr(device_keys::Column::LastUsedAt, Utc::now().naive_utc().into())
+        .filter(device_keys::Column::DeviceId.eq(device_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// 记录已使用的 nonce，返回 `false` 表示该 nonce 已被使用过（重放）
+///
+/// 依赖 (device_id, nonce) 主键保证多实例下同样只有一次能成功
+pub async fn consume_nonce(
+    db: &DatabaseConnection,
+    device_id: &str,
+    nonce: &str,
+) -> Result<bool, DbErr> {
+    let record = device_key_nonces::ActiveModel {
+        device_id: Set(device_id.to_string()),
+        nonce: Set(nonce.to_string()),
+        created_at: Set(Utc::now().naive_utc()),
+    };
+
+    let inserted = DeviceKeyNonces::insert(record)
+        .on_conflict(
+            OnConflict::columns([
+                device_key_nonces::Column::DeviceId,
+                device_key_nonces::Column::Nonce,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(inserted > 0)
+}
+
+/// 删除早于指定时间的 nonce（超过签名有效期后无需再防重放）
+pub async fn purge_nonces_before(db: &DatabaseConnection, before: NaiveDateTime) -> Result<u64, DbErr> {
+    let result = DeviceKeyNonces::delete_many()
+        .filter(device_key_nonces::Column::CreatedAt.lt(before))
+        .exec(db)
+        .await?;
+
+    if result.rows_affected > 0 {
+        info!("Purged {} device signature nonces", result.rows_affected);
+    }
+    Ok(result.rows_affected)
+}
+
+/// 用户在 `since` 之后的再次认证密码错误次数
+pub async fn count_step_up_failures_since(
+    db: &DatabaseConnection,
+    user_id: &str,
+    since: NaiveDateTime,
+) -> Result<u64, DbErr> {
+    DeviceStepUpFailures::find()
+        .filter(device_step_up_failures::Column::UserId.eq(user_id))
+        .filter(device_step_up_failures::Column::CreatedAt.gt(since))
+        .count(db)
+        .await
+}
+
+pub async fn record_step_up_failure(db: &DatabaseConnection, user_id: &str) -> Result<(), DbErr> {
+    device_step_up_failures::ActiveModel {
+        user_id: Set(user_id.to_string()),
+        created_at: Set(Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    Ok(())
+}
+
+/// 再次认证成功后清空该用户的密码错误记录
+pub async fn clear_step_up_failures(db: &DatabaseConnection, user_id: &str) -> Result<(), DbErr> {
+    DeviceStepUpFailures::delete_many()
+        .filter(device_step_up_failures::Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn purge_step_up_failures_before(db: &DatabaseConnection, before: NaiveDateTime) -> Result<u64, DbErr> {
+    let result = DeviceStepUpFailures::delete_many()
+        .filter(device_step_up_failures::Column::CreatedAt.lt(before))
+        .exec(db)
+        .await?;
+
+    if result.rows_affected > 0 {
+        info!("Purged {} device step-up failures", result.rows_affected);
+    }
+    Ok(result.rows_affected)
+}
@@ -0,0 +1,30 @@
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use crate::backend::AppState;
+use crate::backend::api::device::handle_device_keys::list_active_devices;
+use crate::backend::api::device::register_device::DeviceResponse;
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+use crate::backend::utils::extractors::extract_claims_from_request;
+
+/// 当前用户已注册（未吊销）的设备
+///
+/// 路由: GET /v2/devices
+pub async fn list_devices(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    let claims = match extract_claims_from_request(&req) {
+        Ok(claims) => claims,
+        Err(e) => return e.error_response(),
+    };
+
+    match list_active_devices(&state.pg_client, &claims.user_id).await {
+        Ok(devices) => {
+            let devices: Vec<DeviceResponse> = devices.into_iter().map(DeviceResponse::from).collect();
+            HttpResponse::Ok().json(SuccessResponse::new(devices))
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Database error: {}", e),
+            );
+            HttpResponse::InternalServerError().json(error_resp)
+        }
+    }
+}
@@ -0,0 +1,24 @@
+mod handle_device_keys;
+mod device_signature;
+mod register_device;
+mod list_devices;
+mod revoke_device;
+mod step_up;
+
+use actix_web::{Scope, web};
+use crate::backend::api::device::register_device::register_device;
+use crate::backend::api::device::list_devices::list_devices;
+use crate::backend::api::device::revoke_device::revoke_device;
+
+pub use crate::backend::api::device::device_signature::{verify_device_signature, DeviceSignature};
+pub use crate::backend::api::device::handle_device_keys::{
+    purge_nonces_before, purge_step_up_failures_before, revoke_devices_of_user,
+};
+
+/// 设备密钥管理（需要认证）
+pub fn device_scope() -> Scope {
+    web::scope("/devices")
+        .route("", web::post().to(register_device))
+        .route("", web::get().to(list_devices))
+        .route("/{device_id}", web::delete().to(revoke_device))
+}
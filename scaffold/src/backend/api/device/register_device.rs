@@ -0,0 +1,133 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use sea_orm::SqlErr;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+use crate::backend::AppState;
+use crate::backend::api::device::handle_device_keys::{count_active_devices, insert_device_key};
+use crate::backend::api::device::step_up::{verify_step_up, StepUpAuth};
+use crate::backend::api::user::require_first_party_session;
+use crate::backend::config::device_key;
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+use crate::backend::models::device_keys;
+
+#[derive(Deserialize, Debug)]
+pub struct RegisterDeviceRequest {
+    /// bs58 编码的 32 字节 Ed25519 公钥
+    pub public_key: String,
+    /// 设备名称，如 "iPhone 15"
+    pub name: Option<String>,
+    /// 已注册设备对新公钥的签名，或当前密码（注册第一台设备时只能使用密码）
+    #[serde(flatten)]
+    pub auth: StepUpAuth,
+}
+
+/// 返回给客户端的设备信息
+#[derive(Serialize, Debug)]
+pub struct DeviceResponse {
+    pub device_id: String,
+    pub public_key: String,
+    pub name: Option<String>,
+    pub created_at: i64,
+    pub last_used_at: Option<i64>,
+}
+
+impl From<device_keys::Model> for DeviceResponse {
+    fn from(device: device_keys::Model) -> Self {
+        Self {
+            device_id: device.device_id,
+            public_key: device.public_key,
+            name: device.name,
+            created_at: device.created_at.and_utc().timestamp(),
+            last_used_at: device.last_used_at.map(|t| t.and_utc().timestamp()),
+        }
+    }
+}
+
+/// 注册设备密钥
+///
+/// 路由: POST /v2/devices
+///
+/// App 在本地生成 Ed25519 密钥对并上传公钥，之后确认 / 拒绝扫码登录时
+/// 需携带该设备的签名（见 `DeviceSignature`）。
+///
+/// 只接受用户本人登录的会话，并需要再次认证（见 `StepUpAuth`）。
+pub async fn register_device(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    request: web::Json<RegisterDeviceRequest>,
+) -> HttpResponse {
+    let claims = match require_first_party_session(&req) {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+    let request = request.into_inner();
+
+    let public_key = request.public_key.trim();
+    let key_valid = bs58::decode(public_key)
+        .into_vec()
+        .is_ok_and(|bytes| bytes.len() == 32);
+    if !key_valid {
+        let error_resp = error_response(
+            ErrorCode::InvalidFormat,
+            "public_key must be a bs58 encoded 32-byte Ed25519 public key",
+        );
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+
+    let name = request
+        .name
+        .as_deref()
+        .map(|name| name.trim().chars().take(device_key::NAME_MAX_LEN).collect::<String>())
+        .filter(|name| !name.is_empty());
+
+    match count_active_devices(&state.pg_client, &claims.user_id).await {
+        Ok(count) if count >= device_key::MAX_DEVICES_PER_USER => {
+            let error_resp = error_response(
+                ErrorCode::RateLimitExceeded,
+                "Too many registered devices, revoke an old device first",
+            );
+            return HttpResponse::BadRequest().json(error_resp);
+        }
+        Ok(_) => {}
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Database error: {}", e),
+            );
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    }
+
+    if let Err(resp) = verify_step_up(
+        &state.pg_client,
+        &claims.user_id,
+        device_key::ACTION_REGISTER,
+        public_key,
+        request.auth,
+    ).await {
+        return resp;
+    }
+
+    let device_id = Uuid::new_v4().to_string();
+    match insert_device_key(&state.pg_client, &device_id, &claims.user_id, public_key, name.as_deref()).await {
+        Ok(device) => {
+            info!("📱 User {} registered device {}", claims.user_id, device_id);
+            HttpResponse::Ok().json(SuccessResponse::new(DeviceResponse::from(device)))
+        }
+        Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+            let error_resp = error_response(
+                ErrorCode::ResourceAlreadyExists,
+                "This public key is already registered",
+            );
+            HttpResponse::Conflict().json(error_resp)
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Failed to register device: {}", e),
+            );
+            HttpResponse::InternalServerError().json(error_resp)
+        }
+    }
+}
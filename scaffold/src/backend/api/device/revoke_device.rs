@@ -0,0 +1,59 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use tracing::info;
+use crate::backend::AppState;
+use crate::backend::api::device::handle_device_keys::revoke_device as revoke_device_key;
+use crate::backend::api::device::step_up::{verify_step_up, StepUpAuth};
+use crate::backend::api::user::require_first_party_session;
+use crate::backend::config::device_key;
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+
+/// 吊销设备密钥（如设备丢失），吊销后该设备的签名不再被接受
+///
+/// 路由: DELETE /v2/devices/{device_id}
+///
+/// 只接受用户本人登录的会话；请求体需携带其他设备（或被吊销设备本身）对设备 ID 的签名，
+/// 或当前密码（见 `StepUpAuth`）。
+pub async fn revoke_device(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    device_id: web::Path<String>,
+    request: Option<web::Json<StepUpAuth>>,
+) -> HttpResponse {
+    let claims = match require_first_party_session(&req) {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    let auth = request.map(web::Json::into_inner).unwrap_or_default();
+    if let Err(resp) = verify_step_up(&state.pg_client, &claims.user_id, device_key::ACTION_REVOKE, &device_id, auth).await {
+        return resp;
+    }
+
+    match revoke_device_key(&state.pg_client, &claims.user_id, &device_id).await {
+        Ok(true) => {
+            info!("🗑️ User {} revoked device {}", claims.user_id, device_id);
+
+            #[derive(serde::Serialize)]
+            struct RevokeResponse {
+                device_id: String,
+                revoked: bool,
+            }
+
+            HttpResponse::Ok().json(SuccessResponse::new(RevokeResponse {
+                device_id: device_id.into_inner(),
+                revoked: true,
+            }))
+        }
+        Ok(false) => {
+            let error_resp = error_response(ErrorCode::NotFound, "Device not found");
+            HttpResponse::NotFound().json(error_resp)
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Failed to revoke device: {}", e),
+            );
+            HttpResponse::InternalServerError().json(error_resp)
+        }
+    }
+}
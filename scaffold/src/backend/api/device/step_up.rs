@@ -0,0 +1,108 @@
+use actix_web::{web, HttpResponse, ResponseError};
+use chrono::{Duration, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::Deserialize;
+use tracing::warn;
+use crate::backend::api::device::device_signature::{verify_signed_action, DeviceSignature};
+use crate::backend::api::device::handle_device_keys::{
+    clear_step_up_failures, count_step_up_failures_since, record_step_up_failure,
+};
+use crate::backend::config::device_key;
+use crate::backend::errors::{ErrorCode, error_response};
+use crate::backend::models::users;
+use crate::backend::utils::hash::verify_password;
+
+/// 注册 / 吊销设备前的再次认证，二选一
+///
+/// 仅凭 bearer token 不能变更设备：token 泄露后，攻击者可以吊销用户的设备
+/// 或登记自己的设备，从而绕过设备签名。
+#[derive(Deserialize, Debug, Default)]
+pub struct StepUpAuth {
+    /// 用户其他已注册设备对本次操作的签名
+    pub device_signature: Option<DeviceSignature>,
+    /// 当前密码
+    pub password: Option<String>,
+}
+
+/// 校验再次认证，`action` 与 `subject` 见 `DeviceSignature::signing_message`
+///
+/// 密码认证按用户限制失败次数：统计窗口内错误达到 `device_key::STEP_UP_MAX_FAILURES` 次后，
+/// 在窗口结束前只能使用设备签名认证。
+pub async fn verify_step_up(
+    db: &DatabaseConnection,
+    user_id: &str,
+    action: &str,
+    subject: &str,
+    auth: StepUpAuth,
+) -> Result<(), HttpResponse> {
+    if let Some(signature) = &auth.device_signature {
+        return verify_signed_action(db, user_id, action, subject, signature)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.error_response());
+    }
+
+    let Some(password) = auth.password else {
+        let error_resp = error_response(
+            ErrorCode::MissingRequiredField,
+            "device_signature or password is required",
+        );
+        return Err(HttpResponse::BadRequest().json(error_resp));
+    };
+
+    let since = Utc::now().naive_utc() - Duration::seconds(device_key::STEP_UP_FAILURE_WINDOW_SECONDS);
+    match count_step_up_failures_since(db, user_id, since).await {
+        Ok(failures) if failures >= device_key::STEP_UP_MAX_FAILURES => {
+            warn!("❌ User {} exceeded device re-authentication password attempts", user_id);
+            let error_resp = error_response(
+                ErrorCode::RateLimitExceeded,
+                "Too many incorrect passwords, please try again later",
+            );
+            return Err(HttpResponse::TooManyRequests().json(error_resp));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            let error_resp = error_response(ErrorCode::DatabaseError, format!("Database error: {}", e));
+            return Err(HttpResponse::InternalServerError().json(error_resp));
+        }
+    }
+
+    let user = match users::Entity::find()
+        .filter(users::Column::UserId.eq(user_id))
+        .one(db)
+        .await
+    {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            let error_resp = error_response(ErrorCode::NotFound, "User not found");
+            return Err(HttpResponse::NotFound().json(error_resp));
+        }
+        Err(e) => {
+            let error_resp = error_response(ErrorCode::DatabaseError, format!("Database error: {}", e));
+            return Err(HttpResponse::InternalServerError().json(error_resp));
+        }
+    };
+
+    // bcrypt 计算较慢，放到阻塞线程池中执行
+    let password_hash = user.password_hash;
+    match web::block(move || verify_password(&password, &password_hash)).await {
+        Ok(true) => {
+            if let Err(e) = clear_step_up_failures(db, user_id).await {
+                warn!("Failed to clear step-up failures of {}: {}", user_id, e);
+            }
+            Ok(())
+        }
+        Ok(false) => {
+            warn!("❌ User {} failed device {} re-authentication: wrong password", user_id, action);
+            if let Err(e) = record_step_up_failure(db, user_id).await {
+                warn!("Failed to record step-up failure of {}: {}", user_id, e);
+            }
+            let error_resp = error_response(ErrorCode::PermissionDenied, "Password is incorrect");
+            Err(HttpResponse::Forbidden().json(error_resp))
+        }
+        Err(e) => {
+            let error_resp = error_response(ErrorCode::InternalError, format!("Failed to verify password: {}", e));
+            Err(HttpResponse::InternalServerError().json(error_resp))
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, DbErr, Set};
+use tracing::info;
+use crate::backend::models::sea_orm_active_enums::LogActionType;
+use crate::backend::models::user_logs;
+
+pub async fn insert_user_log(
+    db: &DatabaseConnection,
+    user_id: &str,
+    action: LogActionType,
+    ip_address: &str,
+    user_agent: Option<&str>,
+) -> Result<user_logs::Model, DbErr> {
+    let new_log = user_logs::ActiveModel {
+        user_id: Set(user_id.to_string()),
+        action: Set(action.clone()),
+        ip_address: Set(ip_address.to_string()),
+        user_agent: Set(user_agent.map(|ua| ua.to_string())),
+        created_at: Set(Utc::now().naive_utc()),
+        ..Default::default()
+    };
+
+    let inserted = new_log.insert(db).await?;
+    info!("Inserted user log {:?} for {}", action, user_id);
+    Ok(inserted)
+}
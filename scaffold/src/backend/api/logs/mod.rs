@@ -0,0 +1 @@
+pub mod handle_user_logs;
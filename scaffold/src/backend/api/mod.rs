@@ -2,4 +2,11 @@ pub mod code;
 pub mod auth;
 pub mod qr_login;
 pub mod user;
+pub mod logs;
+pub mod notify;
+pub mod device;
+pub mod oauth;
+pub mod social;
 
+pub mod api_keys;
+pub mod admin;
@@ -0,0 +1,9 @@
+mod user_ws;
+
+use actix_web::web;
+use crate::backend::api::notify::user_ws::ws_user_channel;
+
+/// 已登录用户的 WebSocket 通知路由 (挂载在 /v2/ws)
+pub fn user_ws_route() -> actix_web::Route {
+    web::get().to(ws_user_channel)
+}
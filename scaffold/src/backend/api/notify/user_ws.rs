@@ -0,0 +1,126 @@
+use actix_web::{web, HttpRequest, HttpResponse, Error};
+use actix_ws::{Message, Session};
+use chrono::Utc;
+use std::time::Duration;
+use tracing::info;
+use crate::backend::AppState;
+use crate::backend::api::oauth::active_claims;
+use crate::backend::errors::{ErrorCode, error_response};
+use crate::backend::utils::extractors::extract_token_from_head;
+use crate::backend::ws_manager::WsManager;
+use crate::backend::ws_protocol::{UserEvent, WsCommand};
+
+/// 已登录用户的实时通知通道
+///
+/// 路由: GET /v2/ws（浏览器可通过 `?access_token=<token>` 传递 token）
+///
+/// 同一用户可在多个设备上同时连接，服务端通过 `WsManager::notify_user` 推送
+/// 强制下线、新设备登录提醒、管理员广播等 `UserEvent`。
+/// token 过期时推送 `forced_logout` 并关闭连接；已吊销的 token 以及 API Key、OAuth 客户端与服务账号的 token 无法建立连接。
+pub async fn ws_user_channel(
+    req: HttpRequest,
+    stream: web::Payload,
+    ws_manager: web::Data<WsManager>,
+    state: web::Data<AppState>,
+) -> Result<HttpResponse, Error> {
+    let token = match extract_token_from_head(req.head()) {
+        Ok(token) => token,
+        Err(e) => {
+            let error_resp = error_response(ErrorCode::TokenInvalid, e.message());
+            return Ok(HttpResponse::Unauthorized().json(error_resp));
+        }
+    };
+    let claims = match active_claims(&state.pg_client, &token).await {
+        Ok(Some(claims)) => claims,
+        Ok(None) => {
+            let error_resp = error_response(ErrorCode::TokenInvalid, "Invalid, expired or revoked token");
+            return Ok(HttpResponse::Unauthorized().json(error_resp));
+        }
+        Err(e) => {
+            let error_resp = error_response(ErrorCode::DatabaseError, format!("Database error: {}", e));
+            return Ok(HttpResponse::InternalServerError().json(error_resp));
+        }
+    };
+    if !claims.is_first_party() {
+        let error_resp = error_response(ErrorCode::PermissionDenied, "Notifications require a user login session");
+        return Ok(HttpResponse::Forbidden().json(error_resp));
+    }
+    let user_id = claims.user_id.clone();
+
+    let (response, session, mut msg_stream) = actix_ws::handle(&req, stream)?;
+
+    let Some(connection_id) = ws_manager.add_user_connection(&user_id, session.clone()).await else {
+        let mut session = session;
+        let event = UserEvent::error(ErrorCode::RateLimitExceeded, "Too many connections for this user");
+        let _ = session.text(event.to_message()).await;
+        let _ = session.close(None).await;
+        return Ok(response);
+    };
+
+    let mut session_clone = session.clone();
+    let _ = session_clone
+        .text(UserEvent::Connected { user_id: user_id.clone() }.to_message())
+        .await;
+
+    let ws_manager = ws_manager.into_inner();
+    let remaining = (claims.exp as i64 - Utc::now().timestamp()).max(0) as u64;
+
+    actix_web::rt::spawn(async move {
+        let mut session = session;
+        let mut heartbeat_interval = actix_web::rt::time::interval(Duration::from_secs(30));
+        let token_expiry = actix_web::rt::time::sleep(Duration::from_secs(remaining));
+        tokio::pin!(token_expiry);
+
+        loop {
+            tokio::select! {
+                Some(Ok(msg)) = msg_stream.recv() => {
+                    match msg {
+                        Message::Ping(bytes) => {
+                            if session.pong(&bytes).await.is_err() {
+                                break;
+                            }
+                        }
+                        Message::Close(_) => {
+                            info!("🔌 Client closed user WebSocket: {}", user_id);
+                            break;
+                        }
+                        Message::Text(text) => {
+                            if !handle_command(&text, &mut session).await {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                // 心跳检测（30秒）
+                _ = heartbeat_interval.tick() => {
+                    if session.ping(b"").await.is_err() {
+                        info!("❌ Heartbeat failed for user: {}", user_id);
+                        break;
+                    }
+                }
+                // token 过期后断开连接，客户端需重新登录
+                _ = &mut token_expiry => {
+                    info!("⏰ Token expired, closing user WebSocket: {}", user_id);
+                    let _ = session.text(UserEvent::forced_logout("Token expired").to_message()).await;
+                    let _ = session.close(None).await;
+                    break;
+                }
+            }
+        }
+
+        ws_manager.remove_user_connection(&user_id, connection_id).await;
+    });
+
+    Ok(response)
+}
+
+/// 处理客户端命令，返回是否保持连接
+async fn handle_command(text: &str, session: &mut Session) -> bool {
+    let event = match WsCommand::parse(text) {
+        Ok(WsCommand::Ping) => UserEvent::ping(),
+        Ok(_) => UserEvent::error(ErrorCode::InvalidParams, "Unsupported command"),
+        Err(e) => UserEvent::error(ErrorCode::InvalidParams, &e),
+    };
+    session.text(event.to_message()).await.is_ok()
+}
@@ -0,0 +1,277 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use crate::backend::AppState;
+use crate::backend::api::oauth::code_grant::generate_secret;
+use crate::backend::api::oauth::consent_page::{consent_page, error_page};
+use crate::backend::api::oauth::handle_oauth_clients::{client_redirect_uris, find_active_client};
+use crate::backend::api::oauth::handle_oauth_grants::{
+    find_consent, insert_authorization_code, upsert_consent, NewAuthorizationCode,
+};
+use crate::backend::api::oauth::oauth_error::OAuthError;
+use crate::backend::api::oauth::pkce::is_valid_challenge;
+use crate::backend::api::oauth::redirect_uri::append_query;
+use crate::backend::api::oauth::scope::{covers, parse_scopes, resolve_scopes};
+use crate::backend::api::user::require_first_party_session;
+use crate::backend::config::{oauth, oauth_config};
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+use crate::backend::models::oauth_clients;
+use crate::backend::utils::hash::hash_str;
+
+/// 授权请求参数（RFC 6749 §4.1.1 + RFC 7636 §4.3）
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct AuthorizeParams {
+    pub response_type: Option<String>,
+    pub client_id: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub scope: Option<String>,
+    pub state: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    /// OpenID Connect：原样写入 id_token，供客户端防重放
+    pub nonce: Option<String>,
+}
+
+/// 校验通过的授权请求
+pub struct ValidatedAuthorize {
+    pub client: oauth_clients::Model,
+    pub redirect_uri: String,
+    pub scope: String,
+    pub code_challenge: String,
+}
+
+pub enum AuthorizeError {
+    /// 客户端或回调地址无效，不能重定向回客户端（RFC 6749 §4.1.2.1）
+    Invalid(OAuthError),
+    /// 其余错误通过回调地址告知客户端
+    Redirect(String),
+}
+
+/// 构造带错误信息的回调地址
+fn error_redirect(redirect_uri: &str, error: OAuthError, state: Option<&str>) -> String {
+    let mut params = vec![("error", error.error)];
+    if let Some(description) = error.error_description.as_deref() {
+        params.push(("error_description", description));
+    }
+    if let Some(state) = state {
+        params.push(("state", state));
+    }
+    append_query(redirect_uri, &params)
+}
+
+/// 校验授权请求
+pub async fn validate_authorize_request(
+    db: &DatabaseConnection,
+    params: &AuthorizeParams,
+) -> Result<ValidatedAuthorize, AuthorizeError> {
+    let Some(client_id) = params.client_id.as_deref() else {
+        return Err(AuthorizeError::Invalid(OAuthError::invalid_request("client_id is required")));
+    };
+
+    let client = find_active_client(db, client_id)
+        .await
+        .map_err(|e| AuthorizeError::Invalid(OAuthError::server_error(format!("Database error: {}", e))))?
+        .ok_or_else(|| AuthorizeError::Invalid(OAuthError::invalid_request("Unknown client_id")))?;
+
+    // 回调地址必须与登记的完全一致；只登记了一个时可以省略
+    let registered = client_redirect_uris(&client);
+    let redirect_uri = match params.redirect_uri.as_deref() {
+        Some(uri) if registered.iter().any(|r| r == uri) => uri.to_string(),
+        None if registered.len() == 1 => registered[0].clone(),
+        _ => {
+            warn!("Rejected authorization request with unregistered redirect_uri for client {}", client_id);
+            return Err(AuthorizeError::Invalid(OAuthError::invalid_request("Invalid redirect_uri")));
+        }
+    };
+
+    let state = params.state.as_deref();
+    let redirect_error = |error| AuthorizeError::Redirect(error_redirect(&redirect_uri, error, state));
+
+    if params.response_type.as_deref() != Some("code") {
+        return Err(redirect_error(OAuthError::unsupported_response_type()));
+    }
+
+    // 所有客户端都必须使用 PKCE，且只支持 S256
+    let code_challenge = match (params.code_challenge.as_deref(), params.code_challenge_method.as_deref()) {
+        (Some(challenge), Some(oauth::PKCE_METHOD_S256)) if is_valid_challenge(challenge) => challenge.to_string(),
+        _ => {
+            return Err(redirect_error(OAuthError::invalid_request(
+                "A valid code_challenge with code_challenge_method=S256 is required",
+            )));
+        }
+    };
+
+    if params.nonce.as_ref().is_some_and(|n| n.len() > oauth::NONCE_MAX_LEN) {
+        return Err(redirect_error(OAuthError::invalid_request("nonce is too long")));
+    }
+
+    let scope = resolve_scopes(params.scope.as_deref(), &client.scopes)
+        .map_err(|e| redirect_error(OAuthError::invalid_scope(e)))?;
+
+    Ok(ValidatedAuthorize { client, redirect_uri, scope, code_challenge })
+}
+
+/// 授权端点
+///
+/// 路由: GET /v1/oauth/authorize
+///
+/// 校验授权请求后展示授权同意页面。配置了 `OAUTH_CONSENT_PAGE_URL` 时带上原始查询参数
+/// 重定向到自定义页面，否则使用内置页面；页面再以登录用户身份调用 `POST /v2/oauth/authorize`。
+pub async fn authorize(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    params: web::Query<AuthorizeParams>,
+) -> HttpResponse {
+    match validate_authorize_request(&state.pg_client, &params).await {
+        Ok(validated) => {
+            info!("Authorization request from client {} (scope: {})", validated.client.client_id, validated.scope);
+            match oauth_config().consent_page_url.as_deref() {
+                Some(page) => HttpResponse::Found()
+                    .insert_header((header::LOCATION, format!("{}?{}", page, req.query_string())))
+                    .finish(),
+                None => consent_page(),
+            }
+        }
+        Err(AuthorizeError::Invalid(e)) => error_page(&e),
+        Err(AuthorizeError::Redirect(location)) => HttpResponse::Found()
+            .insert_header((header::LOCATION, location))
+            .finish(),
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AuthorizeDecisionRequest {
+    #[serde(flatten)]
+    pub params: AuthorizeParams,
+    /// 用户在同意页面的选择；不传表示先查询是否需要用户同意
+    pub approve: Option<bool>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct AuthorizeDecision {
+    /// 需要用户在同意页面确认
+    pub consent_required: bool,
+    pub client_name: String,
+    pub scope: String,
+    /// 授权完成（或被拒绝）后浏览器应跳转的回调地址，携带 `code` 或 `error`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_to: Option<String>,
+}
+
+/// 以当前登录用户身份完成授权
+///
+/// 路由: POST /v2/oauth/authorize
+///
+/// 第一方客户端（`skip_consent`）或用户已同意过所申请的 scope 时直接签发授权码；
+/// 否则返回 `consent_required`，由同意页面展示后携带 `approve` 再次调用。
+/// 只接受用户本人登录的会话，API Key 与 OAuth token 不能用于授权其他客户端。
+pub async fn authorize_decision(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    request: web::Json<AuthorizeDecisionRequest>,
+) -> HttpResponse {
+    let claims = match require_first_party_session(&req) {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    let validated = match validate_authorize_request(&state.pg_client, &request.params).await {
+        Ok(validated) => validated,
+        Err(AuthorizeError::Invalid(e)) => {
+            let error_resp = error_response(
+                ErrorCode::InvalidParams,
+                e.error_description.unwrap_or_else(|| e.error.to_string()),
+            );
+            return HttpResponse::BadRequest().json(error_resp);
+        }
+        Err(AuthorizeError::Redirect(location)) => {
+            return HttpResponse::Ok().json(SuccessResponse::new(AuthorizeDecision {
+                consent_required: false,
+                client_name: String::new(),
+                scope: String::new(),
+                redirect_to: Some(location),
+            }));
+        }
+    };
+    let client = &validated.client;
+    let oauth_state = request.params.state.as_deref();
+
+    let decision = |consent_required, redirect_to| {
+        HttpResponse::Ok().json(SuccessResponse::new(AuthorizeDecision {
+            consent_required,
+            client_name: client.name.clone(),
+            scope: validated.scope.clone(),
+            redirect_to,
+        }))
+    };
+
+    if request.approve == Some(false) {
+        info!("🚫 User {} denied authorization for client {}", claims.user_id, client.client_id);
+        let error = OAuthError::access_denied("The user denied the authorization request");
+        return decision(false, Some(error_redirect(&validated.redirect_uri, error, oauth_state)));
+    }
+
+    let existing_consent = if client.skip_consent {
+        None
+    } else {
+        match find_consent(&state.pg_client, &claims.user_id, &client.client_id).await {
+            Ok(consent) => consent,
+            Err(e) => {
+                let error_resp = error_response(ErrorCode::DatabaseError, format!("Database error: {}", e));
+                return HttpResponse::InternalServerError().json(error_resp);
+            }
+        }
+    };
+    let consented = client.skip_consent
+        || existing_consent.as_ref().is_some_and(|c| covers(&c.scope, &validated.scope));
+
+    if !consented {
+        if request.approve.is_none() {
+            return decision(true, None);
+        }
+
+        // 合并此前同意过的 scope，避免缩小范围的授权覆盖原有同意
+        let merged = match existing_consent {
+            Some(c) => parse_scopes(&format!("{} {}", c.scope, validated.scope)).join(" "),
+            None => validated.scope.clone(),
+        };
+        if let Err(e) = upsert_consent(&state.pg_client, &claims.user_id, &client.client_id, &merged).await {
+            let error_resp = error_response(ErrorCode::DatabaseError, format!("Failed to save consent: {}", e));
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    }
+
+    // auth_time 为用户登录（签发当前会话 token）的时间，而不是授权的时间
+    let auth_time = claims
+        .iat
+        .and_then(|iat| DateTime::from_timestamp(iat as i64, 0))
+        .map(|t| t.naive_utc())
+        .unwrap_or_else(|| Utc::now().naive_utc());
+
+    let code = generate_secret();
+    let code_hash = hash_str(&code);
+    if let Err(e) = insert_authorization_code(&state.pg_client, NewAuthorizationCode {
+        code_hash: &code_hash,
+        client_id: &client.client_id,
+        user_id: &claims.user_id,
+        redirect_uri: &validated.redirect_uri,
+        scope: &validated.scope,
+        code_challenge: &validated.code_challenge,
+        code_challenge_method: oauth::PKCE_METHOD_S256,
+        nonce: request.params.nonce.as_deref(),
+        auth_time,
+    }).await {
+        let error_resp = error_response(ErrorCode::DatabaseError, format!("Failed to issue code: {}", e));
+        return HttpResponse::InternalServerError().json(error_resp);
+    }
+
+    info!("✅ User {} authorized client {} (scope: {})", claims.user_id, client.client_id, validated.scope);
+
+    let mut params = vec![("code", code.as_str())];
+    if let Some(s) = oauth_state {
+        params.push(("state", s));
+    }
+    decision(false, Some(append_query(&validated.redirect_uri, &params)))
+}
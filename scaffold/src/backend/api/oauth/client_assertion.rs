@@ -0,0 +1,168 @@
+use actix_web::HttpRequest;
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use tracing::warn;
+use crate::backend::api::oauth::handle_oauth_clients::{find_active_client, record_client_assertion};
+use crate::backend::api::oauth::issuer::public_base_url;
+use crate::backend::api::oauth::oauth_error::OAuthError;
+use crate::backend::config::oauth;
+use crate::backend::models::oauth_clients;
+use crate::backend::utils::hash::hash_str;
+
+/// client_assertion 的负载（RFC 7523 §3），`iss`、`sub`、`aud` 由 `Validation` 校验
+#[derive(Deserialize, Debug)]
+pub struct AssertionClaims {
+    pub exp: i64,
+    pub jti: String,
+}
+
+#[derive(Deserialize)]
+struct UnverifiedIssuer {
+    iss: Option<String>,
+}
+
+/// 断言的签发方（即 client_id），在验证签名前读取，仅用于查找客户端公钥
+fn unverified_issuer(assertion: &str) -> Option<String> {
+    let mut validation = Validation::new(Algorithm::EdDSA);
+    validation.insecure_disable_signature_validation();
+    validation.validate_exp = false;
+    validation.validate_aud = false;
+    validation.required_spec_claims.clear();
+
+    decode::<UnverifiedIssuer>(assertion, &DecodingKey::from_secret(&[]), &validation)
+        .ok()
+        .and_then(|data| data.claims.iss)
+}
+
+/// 校验断言的签名、签发方、受众与有效期
+///
+/// `public_key` 为 bs58 编码的 Ed25519 公钥；`audiences` 为可接受的 `aud`（token 端点地址或签发方）。
+pub fn verify_assertion(
+    assertion: &str,
+    public_key: &str,
+    client_id: &str,
+    audiences: &[String],
+) -> Result<AssertionClaims, OAuthError> {
+    let key_bytes = bs58::decode(public_key)
+        .into_vec()
+        .map_err(|_| OAuthError::server_error("Client public key is invalid"))?;
+
+    let mut validation = Validation::new(Algorithm::EdDSA);
+    validation.set_issuer(&[client_id]);
+    validation.set_audience(audiences);
+    validation.sub = Some(client_id.to_string());
+    validation.set_required_spec_claims(&["exp", "iss", "sub", "aud"]);
+
+    let claims = decode::<AssertionClaims>(assertion, &DecodingKey::from_ed_der(&key_bytes), &validation)
+        .map_err(|e| {
+            warn!("Client assertion rejected for {}: {}", client_id, e);
+            OAuthError::invalid_client("Client assertion is invalid")
+        })?
+        .claims;
+
+    if claims.exp - Utc::now().timestamp() > oauth::CLIENT_ASSERTION_MAX_LIFETIME_SECONDS {
+        return Err(OAuthError::invalid_client("Client assertion lifetime is too long"));
+    }
+    if claims.jti.is_empty() {
+        return Err(OAuthError::invalid_client("Client assertion must contain jti"));
+    }
+    Ok(claims)
+}
+
+/// 使用 JWT 断言认证客户端（RFC 7523 `private_key_jwt`）
+///
+/// 断言由客户端用登记的 Ed25519 私钥签名，`iss` 与 `sub` 均为 client_id，
+/// `aud` 为 token 端点地址；同一 `jti` 只能使用一次。
+pub async fn authenticate_client_assertion(
+    req: &HttpRequest,
+    db: &DatabaseConnection,
+    form_client_id: Option<&str>,
+    assertion_type: Option<&str>,
+    assertion: &str,
+) -> Result<oauth_clients::Model, OAuthError> {
+    if assertion_type != Some(oauth::CLIENT_ASSERTION_TYPE_JWT_BEARER) {
+        return Err(OAuthError::invalid_request("Unsupported client_assertion_type"));
+    }
+
+    let client_id = unverified_issuer(assertion)
+        .ok_or_else(|| OAuthError::invalid_client("Client assertion is invalid"))?;
+    if form_client_id.is_some_and(|id| id != client_id) {
+        return Err(OAuthError::invalid_request("client_id does not match the client assertion"));
+    }
+
+    let client = find_active_client(db, &client_id)
+        .await
+        .map_err(|e| OAuthError::server_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| OAuthError::invalid_client("Unknown client"))?;
+    let Some(public_key) = client.jwt_public_key.as_deref() else {
+        return Err(OAuthError::invalid_client("Client has no registered public key"));
+    };
+
+    let issuer = public_base_url(req);
+    let audiences = [format!("{}/v1/oauth/token", issuer), issuer];
+    let claims = verify_assertion(assertion, public_key, &client_id, &audiences)?;
+
+    let expires_at = DateTime::from_timestamp(claims.exp, 0)
+        .map(|t| t.naive_utc())
+        .unwrap_or_else(|| Utc::now().naive_utc());
+    let jti_hash = hash_str(&format!("{}:{}", client_id, claims.jti));
+    let first_use = record_client_assertion(db, &jti_hash, &client_id, expires_at)
+        .await
+        .map_err(|e| OAuthError::server_error(format!("Database error: {}", e)))?;
+    if !first_use {
+        warn!("Client assertion replay detected for {}", client_id);
+        return Err(OAuthError::invalid_client("Client assertion has already been used"));
+    }
+
+    Ok(client)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, Header};
+    use crate::backend::utils::jwt::{load_key_bytes, load_keys};
+
+    const AUDIENCE: &str = "https://auth.example.com/v1/oauth/token";
+
+    fn sign(claims: serde_json::Value) -> String {
+        let (encoding_key, _) = load_keys();
+        encode(&Header::new(Algorithm::EdDSA), &claims, &encoding_key).unwrap()
+    }
+
+    fn public_key() -> String {
+        bs58::encode(load_key_bytes().1).into_string()
+    }
+
+    #[test]
+    fn test_verify_assertion() {
+        let exp = Utc::now().timestamp() + 60;
+        let assertion = sign(serde_json::json!({
+            "iss": "svc", "sub": "svc", "aud": AUDIENCE, "exp": exp, "jti": "a1",
+        }));
+        assert_eq!(unverified_issuer(&assertion).as_deref(), Some("svc"));
+
+        let claims = verify_assertion(&assertion, &public_key(), "svc", &[AUDIENCE.to_string()]).unwrap();
+        assert_eq!(claims.jti, "a1");
+
+        // 签发方、受众不匹配
+        assert!(verify_assertion(&assertion, &public_key(), "other", &[AUDIENCE.to_string()]).is_err());
+        assert!(verify_assertion(&assertion, &public_key(), "svc", &["https://evil".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_verify_assertion_rejects_long_lived_or_missing_jti() {
+        let long_lived = sign(serde_json::json!({
+            "iss": "svc", "sub": "svc", "aud": AUDIENCE,
+            "exp": Utc::now().timestamp() + 3600, "jti": "a2",
+        }));
+        assert!(verify_assertion(&long_lived, &public_key(), "svc", &[AUDIENCE.to_string()]).is_err());
+
+        let without_jti = sign(serde_json::json!({
+            "iss": "svc", "sub": "svc", "aud": AUDIENCE, "exp": Utc::now().timestamp() + 60,
+        }));
+        assert!(verify_assertion(&without_jti, &public_key(), "svc", &[AUDIENCE.to_string()]).is_err());
+    }
+}
@@ -0,0 +1,57 @@
+use actix_web::http::header::Header;
+use actix_web::HttpRequest;
+use actix_web_httpauth::headers::authorization::{Authorization, Basic};
+use sea_orm::DatabaseConnection;
+use tracing::warn;
+use crate::backend::api::oauth::handle_oauth_clients::find_active_client;
+use crate::backend::api::oauth::oauth_error::OAuthError;
+use crate::backend::models::oauth_clients;
+use crate::backend::utils::hash::{constant_time_eq, hash_str};
+
+/// 认证 token 接口的调用方（RFC 6749 §2.3）
+///
+/// 凭证可以放在 HTTP Basic 头中，也可以放在表单的 `client_id` / `client_secret` 中，
+/// 但不能同时使用两种方式。公开客户端（无 secret）只需提供 `client_id`，
+/// 机密客户端必须提供正确的 `client_secret`。
+pub async fn authenticate_client(
+    req: &HttpRequest,
+    db: &DatabaseConnection,
+    form_client_id: Option<&str>,
+    form_client_secret: Option<&str>,
+) -> Result<oauth_clients::Model, OAuthError> {
+    let (client_id, client_secret) = match Authorization::<Basic>::parse(req) {
+        Ok(auth) => {
+            if form_client_secret.is_some() {
+                return Err(OAuthError::invalid_request("Multiple client authentication methods used"));
+            }
+            let basic = auth.into_scheme();
+            if form_client_id.is_some_and(|id| id != basic.user_id()) {
+                return Err(OAuthError::invalid_request("client_id does not match the Authorization header"));
+            }
+            (basic.user_id().to_string(), basic.password().map(str::to_string))
+        }
+        Err(_) => {
+            let client_id = form_client_id
+                .ok_or_else(|| OAuthError::invalid_client("Client authentication required"))?;
+            (client_id.to_string(), form_client_secret.map(str::to_string))
+        }
+    };
+
+    let client = find_active_client(db, &client_id)
+        .await
+        .map_err(|e| OAuthError::server_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| OAuthError::invalid_client("Unknown client"))?;
+
+    let authenticated = match (client.client_secret_hash.as_deref(), client_secret.as_deref()) {
+        (Some(expected), Some(secret)) => constant_time_eq(&hash_str(secret), expected),
+        // 没有 secret 的服务账号只能使用 client_assertion 认证
+        (None, None) => !client.service_account,
+        _ => false,
+    };
+    if !authenticated {
+        warn!("Client authentication failed for {}", client_id);
+        return Err(OAuthError::invalid_client("Client authentication failed"));
+    }
+
+    Ok(client)
+}
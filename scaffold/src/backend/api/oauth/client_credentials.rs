@@ -0,0 +1,69 @@
+use actix_web::HttpRequest;
+use chrono::Utc;
+use sea_orm::DatabaseConnection;
+use tracing::info;
+use crate::backend::api::oauth::client_assertion::authenticate_client_assertion;
+use crate::backend::api::oauth::client_auth::authenticate_client;
+use crate::backend::api::oauth::oauth_error::OAuthError;
+use crate::backend::api::oauth::scope::resolve_scopes;
+use crate::backend::api::oauth::token::{TokenRequest, TokenResponse};
+use crate::backend::config::oauth;
+use crate::backend::utils::jwt::{create_jwt, Claims};
+
+/// 服务账号使用客户端凭证换取 token（RFC 6749 §4.4）
+///
+/// 支持 client_secret 与 `private_key_jwt` 断言两种认证方式。签发的 access token 代表服务自身：
+/// `user_id` 为 client_id、`service_account` 为 true、不携带用户角色；有效期很短且不签发 refresh token。
+/// 服务账号不代表任何用户，`Auth` 中间件拒绝此类 token 访问用户接口。
+pub async fn exchange_client_credentials(
+    req: &HttpRequest,
+    db: &DatabaseConnection,
+    form: &TokenRequest,
+) -> Result<TokenResponse, OAuthError> {
+    let client = match form.client_assertion.as_deref() {
+        Some(assertion) => {
+            if form.client_secret.is_some() {
+                return Err(OAuthError::invalid_request("Multiple client authentication methods used"));
+            }
+            authenticate_client_assertion(
+                req,
+                db,
+                form.client_id.as_deref(),
+                form.client_assertion_type.as_deref(),
+                assertion,
+            ).await?
+        }
+        None => authenticate_client(req, db, form.client_id.as_deref(), form.client_secret.as_deref()).await?,
+    };
+
+    if !client.service_account {
+        return Err(OAuthError::unauthorized_client("Only service accounts may use the client_credentials grant"));
+    }
+
+    let scope = resolve_scopes(form.scope.as_deref(), &client.scopes).map_err(OAuthError::invalid_scope)?;
+
+    let claims = Claims {
+        user_id: client.client_id.clone(),
+        username: client.name.clone(),
+        exp: Utc::now().timestamp() as usize + oauth::SERVICE_TOKEN_TTL_SECONDS,
+        client_id: Some(client.client_id.clone()),
+        scope: Some(scope).filter(|s| !s.is_empty()),
+        service_account: true,
+        typ: Some(oauth::ACCESS_TOKEN_TYP.to_string()),
+        ..Default::default()
+    };
+
+    info!(
+        "🤖 Service account {} obtained an access token (scope: {})",
+        client.client_id,
+        claims.scope.as_deref().unwrap_or_default()
+    );
+    Ok(TokenResponse {
+        access_token: create_jwt(&claims),
+        token_type: "Bearer",
+        expires_in: oauth::SERVICE_TOKEN_TTL_SECONDS as i64,
+        scope: claims.scope,
+        refresh_token: None,
+        id_token: None,
+    })
+}
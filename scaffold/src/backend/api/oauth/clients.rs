@@ -0,0 +1,277 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use uuid::Uuid;
+use crate::backend::AppState;
+use crate::backend::api::admin::require_admin;
+use crate::backend::api::oauth::code_grant::generate_secret;
+use crate::backend::api::oauth::handle_oauth_clients::{
+    client_redirect_uris, insert_client, list_active_clients, revoke_client, NewClient,
+};
+use crate::backend::api::oauth::redirect_uri::validate_redirect_uri;
+use crate::backend::api::oauth::scope::parse_scopes;
+use crate::backend::config::oauth;
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+use crate::backend::models::oauth_clients;
+use crate::backend::utils::hash::hash_str;
+
+#[derive(Deserialize, Debug)]
+pub struct RegisterClientRequest {
+    pub name: String,
+    /// 服务账号不使用回调地址
+    #[serde(default)]
+    pub redirect_uris: Vec<String>,
+    /// 客户端可申请的 scope（空格分隔）
+    #[serde(default)]
+    pub scopes: String,
+    /// 机密客户端（服务端应用）会生成 client_secret；SPA、原生应用等公开客户端设为 `false`
+    #[serde(default = "default_confidential")]
+    pub confidential: bool,
+    /// 第一方应用可跳过授权确认页
+    #[serde(default)]
+    pub skip_consent: bool,
+    /// 服务账号：只能通过 client_credentials 获取代表服务自身的 token
+    #[serde(default)]
+    pub service_account: bool,
+    /// 服务账号用于签名 client_assertion 的 Ed25519 公钥（bs58）；
+    /// 只使用断言认证的服务账号可将 `confidential` 设为 `false`
+    pub public_key: Option<String>,
+}
+
+fn default_confidential() -> bool {
+    true
+}
+
+#[derive(Serialize, Debug)]
+pub struct ClientResponse {
+    pub client_id: String,
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    pub scopes: String,
+    pub confidential: bool,
+    pub skip_consent: bool,
+    pub service_account: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    pub created_at: i64,
+}
+
+impl From<oauth_clients::Model> for ClientResponse {
+    fn from(client: oauth_clients::Model) -> Self {
+        Self {
+            redirect_uris: client_redirect_uris(&client),
+            client_id: client.client_id,
+            name: client.name,
+            scopes: client.scopes,
+            confidential: client.client_secret_hash.is_some(),
+            skip_consent: client.skip_consent,
+            service_account: client.service_account,
+            public_key: client.jwt_public_key,
+            created_at: client.created_at.and_utc().timestamp(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct RegisterClientResponse {
+    #[serde(flatten)]
+    pub client: ClientResponse,
+    /// 仅在注册时返回一次，服务端只保存其哈希
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+}
+
+/// 校验客户端类型相关的参数
+///
+/// 普通客户端必须登记回调地址；服务账号不能登记回调地址，且必须有 client_secret 或公钥之一
+fn validate_client_kind(request: &RegisterClientRequest) -> Result<(), String> {
+    if !request.service_account {
+        if request.redirect_uris.is_empty() {
+            return Err("redirect_uris must not be empty".to_string());
+        }
+        if request.public_key.is_some() {
+            return Err("public_key is only supported for service accounts".to_string());
+        }
+        return Ok(());
+    }
+
+    if !request.redirect_uris.is_empty() {
+        return Err("Service accounts must not register redirect_uris".to_string());
+    }
+    match request.public_key.as_deref().map(str::trim) {
+        Some(key) if !bs58::decode(key).into_vec().is_ok_and(|bytes| bytes.len() == 32) => {
+            Err("public_key must be a bs58 encoded 32-byte Ed25519 public key".to_string())
+        }
+        None if !request.confidential => {
+            Err("Service accounts need a client_secret or a public_key".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 注册 OAuth 客户端
+///
+/// 路由: POST /v2/oauth/clients
+pub async fn register_client(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    request: web::Json<RegisterClientRequest>,
+) -> HttpResponse {
+    let claims = match require_admin(&req, &state.pg_client).await {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    let name = request.name.trim();
+    if name.is_empty() || name.chars().count() > oauth::CLIENT_NAME_MAX_LEN {
+        let error_resp = error_response(
+            ErrorCode::InvalidFormat,
+            format!("name must be 1-{} characters", oauth::CLIENT_NAME_MAX_LEN),
+        );
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+
+    if let Err(e) = validate_client_kind(&request) {
+        let error_resp = error_response(ErrorCode::InvalidParams, e);
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+
+    if request.redirect_uris.len() > oauth::MAX_REDIRECT_URIS {
+        let error_resp = error_response(
+            ErrorCode::InvalidFormat,
+            format!("redirect_uris must contain 1-{} entries", oauth::MAX_REDIRECT_URIS),
+        );
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+    if let Some(e) = request.redirect_uris.iter().find_map(|uri| validate_redirect_uri(uri).err()) {
+        let error_resp = error_response(ErrorCode::InvalidFormat, e);
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+
+    let client_id = Uuid::new_v4().to_string();
+    let client_secret = request.confidential.then(generate_secret);
+    let client_secret_hash = client_secret.as_deref().map(hash_str);
+    let scopes = parse_scopes(&request.scopes).join(" ");
+    let public_key = request.public_key.as_deref().map(str::trim);
+
+    let client = match insert_client(&state.pg_client, NewClient {
+        client_id: &client_id,
+        client_secret_hash: client_secret_hash.as_deref(),
+        name,
+        redirect_uris: &request.redirect_uris,
+        scopes: &scopes,
+        skip_consent: request.skip_consent && !request.service_account,
+        created_by: &claims.user_id,
+        service_account: request.service_account,
+        jwt_public_key: public_key,
+    }).await {
+        Ok(client) => client,
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Failed to register client: {}", e),
+            );
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    };
+
+    info!(
+        "🔑 Admin {} registered OAuth {} {} ({})",
+        claims.user_id,
+        if request.service_account { "service account" } else { "client" },
+        client_id,
+        name
+    );
+
+    HttpResponse::Ok().json(SuccessResponse::new(RegisterClientResponse {
+        client: client.into(),
+        client_secret,
+    }))
+}
+
+/// 列出 OAuth 客户端
+///
+/// 路由: GET /v2/oauth/clients
+pub async fn list_clients(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(resp) = require_admin(&req, &state.pg_client).await {
+        return resp;
+    }
+
+    match list_active_clients(&state.pg_client).await {
+        Ok(clients) => {
+            let clients: Vec<ClientResponse> = clients.into_iter().map(Into::into).collect();
+            HttpResponse::Ok().json(SuccessResponse::new(clients))
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Failed to list clients: {}", e),
+            );
+            HttpResponse::InternalServerError().json(error_resp)
+        }
+    }
+}
+
+/// 吊销 OAuth 客户端
+///
+/// 路由: DELETE /v2/oauth/clients/{client_id}
+///
+/// 吊销后客户端无法再发起授权或换取 token。
+pub async fn delete_client(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    client_id: web::Path<String>,
+) -> HttpResponse {
+    let claims = match require_admin(&req, &state.pg_client).await {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    match revoke_client(&state.pg_client, &client_id).await {
+        Ok(true) => {
+            info!("🗑️ Admin {} revoked OAuth client {}", claims.user_id, client_id);
+            HttpResponse::Ok().json(SuccessResponse::new(serde_json::json!({ "client_id": client_id.as_str() })))
+        }
+        Ok(false) => {
+            let error_resp = error_response(ErrorCode::NotFound, "Client not found");
+            HttpResponse::NotFound().json(error_resp)
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Failed to revoke client: {}", e),
+            );
+            HttpResponse::InternalServerError().json(error_resp)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(value: serde_json::Value) -> RegisterClientRequest {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_validate_client_kind() {
+        let web_app = request(serde_json::json!({ "name": "app", "redirect_uris": ["https://app/cb"] }));
+        assert!(validate_client_kind(&web_app).is_ok());
+        assert!(validate_client_kind(&request(serde_json::json!({ "name": "app" }))).is_err());
+
+        let service = request(serde_json::json!({ "name": "svc", "service_account": true }));
+        assert!(validate_client_kind(&service).is_ok());
+
+        // 只用断言认证的服务账号必须登记公钥
+        let key = bs58::encode([7u8; 32]).into_string();
+        let assertion_only = serde_json::json!({ "name": "svc", "service_account": true, "confidential": false });
+        assert!(validate_client_kind(&request(assertion_only.clone())).is_err());
+        let mut with_key = assertion_only;
+        with_key["public_key"] = key.into();
+        assert!(validate_client_kind(&request(with_key)).is_ok());
+
+        let with_redirect = serde_json::json!({ "name": "svc", "service_account": true, "redirect_uris": ["https://a/cb"] });
+        assert!(validate_client_kind(&request(with_redirect)).is_err());
+    }
+}
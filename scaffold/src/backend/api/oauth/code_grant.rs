@@ -0,0 +1,212 @@
+use actix_web::HttpRequest;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{NaiveDateTime, Utc};
+use rand::{thread_rng, RngCore};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use tracing::{info, warn};
+use crate::backend::api::oauth::client_auth::authenticate_client;
+use crate::backend::api::oauth::handle_oauth_grants::{
+    consume_authorization_code, find_authorization_code, find_refresh_token, insert_refresh_token,
+    revoke_refresh_token, revoke_refresh_tokens_for,
+};
+use crate::backend::api::oauth::issuer::configured_base_url;
+use crate::backend::api::oauth::oauth_error::OAuthError;
+use crate::backend::api::oauth::oidc::{is_openid, scoped_user_claims, IdTokenClaims};
+use crate::backend::api::oauth::pkce::verify_pkce;
+use crate::backend::api::oauth::scope::covers;
+use crate::backend::api::oauth::token::{TokenRequest, TokenResponse};
+use crate::backend::config::oauth;
+use crate::backend::models::users;
+use crate::backend::utils::hash::hash_str;
+use crate::backend::utils::jwt::{create_id_token, create_jwt, Claims};
+
+fn db_error(e: sea_orm::DbErr) -> OAuthError {
+    OAuthError::server_error(format!("Database error: {}", e))
+}
+
+/// 生成授权码、refresh token、client_secret 等随机凭证（URL 安全 Base64）
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; oauth::SECRET_BYTES];
+    thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// 授权码换取 token（RFC 6749 §4.1.3，要求 PKCE）
+pub async fn exchange_authorization_code(
+    req: &HttpRequest,
+    db: &DatabaseConnection,
+    form: &TokenRequest,
+) -> Result<TokenResponse, OAuthError> {
+    let client = authenticate_client(req, db, form.client_id.as_deref(), form.client_secret.as_deref()).await?;
+
+    let (Some(code), Some(redirect_uri), Some(code_verifier)) = (
+        form.code.as_deref(),
+        form.redirect_uri.as_deref(),
+        form.code_verifier.as_deref(),
+    ) else {
+        return Err(OAuthError::invalid_request("code, redirect_uri and code_verifier are required"));
+    };
+
+    let authorization_code = find_authorization_code(db, &hash_str(code))
+        .await
+        .map_err(db_error)?
+        .filter(|c| c.client_id == client.client_id)
+        .ok_or_else(|| OAuthError::invalid_grant("Invalid authorization code"))?;
+
+    // 授权码被重复使用说明可能已泄露，吊销基于它签发的 refresh token（RFC 6749 §4.1.2）
+    if authorization_code.consumed_at.is_some() {
+        warn!("Authorization code reuse detected for client {}", client.client_id);
+        revoke_refresh_tokens_for(db, &client.client_id, &authorization_code.user_id)
+            .await
+            .map_err(db_error)?;
+        return Err(OAuthError::invalid_grant("Authorization code has already been used"));
+    }
+
+    if authorization_code.redirect_uri != redirect_uri {
+        return Err(OAuthError::invalid_grant("redirect_uri does not match the authorization request"));
+    }
+
+    if !verify_pkce(
+        code_verifier,
+        &authorization_code.code_challenge,
+        &authorization_code.code_challenge_method,
+    ) {
+        return Err(OAuthError::invalid_grant("PKCE verification failed"));
+    }
+
+    if !consume_authorization_code(db, authorization_code.id).await.map_err(db_error)? {
+        return Err(OAuthError::invalid_grant("Authorization code has expired or already been used"));
+    }
+
+    let user = find_active_user(db, &authorization_code.user_id).await?;
+    info!("✅ Authorization code exchanged by client {} for user {}", client.client_id, user.user_id);
+    issue_tokens(db, &user, &client.client_id, &authorization_code.scope, IdTokenContext {
+        auth_time: authorization_code.auth_time,
+        nonce: authorization_code.nonce,
+    }).await
+}
+
+/// 使用 refresh token 换取新 token（RFC 6749 §6）
+///
+/// 每次刷新都会轮换 refresh token；已吊销的 refresh token 被再次使用时，
+/// 吊销该客户端为该用户签发的全部 refresh token。
+pub async fn exchange_refresh_token(
+    req: &HttpRequest,
+    db: &DatabaseConnection,
+    form: &TokenRequest,
+) -> Result<TokenResponse, OAuthError> {
+    let client = authenticate_client(req, db, form.client_id.as_deref(), form.client_secret.as_deref()).await?;
+
+    let Some(refresh_token) = form.refresh_token.as_deref() else {
+        return Err(OAuthError::invalid_request("refresh_token is required"));
+    };
+
+    let stored = find_refresh_token(db, &hash_str(refresh_token))
+        .await
+        .map_err(db_error)?
+        .filter(|t| t.client_id == client.client_id)
+        .ok_or_else(|| OAuthError::invalid_grant("Invalid refresh token"))?;
+
+    if stored.revoked_at.is_some() {
+        warn!("Refresh token reuse detected for client {} user {}", client.client_id, stored.user_id);
+        revoke_refresh_tokens_for(db, &client.client_id, &stored.user_id)
+            .await
+            .map_err(db_error)?;
+        return Err(OAuthError::invalid_grant("Refresh token has been revoked"));
+    }
+
+    if stored.expires_at < Utc::now().naive_utc() {
+        return Err(OAuthError::invalid_grant("Refresh token has expired"));
+    }
+
+    // 刷新时只能缩小 scope（RFC 6749 §6）
+    let scope = match form.scope.as_deref().filter(|s| !s.trim().is_empty()) {
+        Some(requested) if !covers(&stored.scope, requested) => {
+            return Err(OAuthError::invalid_scope("Requested scope exceeds the original grant"));
+        }
+        Some(requested) => requested.split_whitespace().collect::<Vec<_>>().join(" "),
+        None => stored.scope.clone(),
+    };
+
+    if !revoke_refresh_token(db, stored.id).await.map_err(db_error)? {
+        return Err(OAuthError::invalid_grant("Refresh token has been revoked"));
+    }
+
+    let user = find_active_user(db, &stored.user_id).await?;
+    info!("🔄 Refresh token rotated for client {} user {}", client.client_id, user.user_id);
+    // 刷新时签发的 id_token 不携带 nonce（OpenID Connect Core §12.2）
+    issue_tokens(db, &user, &client.client_id, &scope, IdTokenContext {
+        auth_time: stored.auth_time,
+        nonce: None,
+    }).await
+}
+
+async fn find_active_user(db: &DatabaseConnection, user_id: &str) -> Result<users::Model, OAuthError> {
+    users::Entity::find()
+        .filter(users::Column::UserId.eq(user_id))
+        .one(db)
+        .await
+        .map_err(db_error)?
+        .filter(|u| u.is_active != Some(false))
+        .ok_or_else(|| OAuthError::invalid_grant("User account is disabled"))
+}
+
+/// 签发 id_token 所需的上下文
+struct IdTokenContext {
+    auth_time: NaiveDateTime,
+    nonce: Option<String>,
+}
+
+/// 签发 access token 与 refresh token；scope 包含 `openid` 时同时签发 id_token
+///
+/// id_token 的 `iss` 取配置的站点根地址，未配置时不签发任何 token
+///
+/// access token 带有 `typ`，不携带角色，不会被续签，只能访问 scope 覆盖的接口
+async fn issue_tokens(
+    db: &DatabaseConnection,
+    user: &users::Model,
+    client_id: &str,
+    scope: &str,
+    oidc: IdTokenContext,
+) -> Result<TokenResponse, OAuthError> {
+    let issuer = if is_openid(scope) { Some(configured_base_url()?) } else { None };
+
+    let claims = Claims {
+        user_id: user.user_id.clone(),
+        username: user.user_id.clone(),
+        exp: Utc::now().timestamp() as usize + oauth::ACCESS_TOKEN_TTL_SECONDS,
+        client_id: Some(client_id.to_string()),
+        scope: Some(scope.to_string()).filter(|s| !s.is_empty()),
+        typ: Some(oauth::ACCESS_TOKEN_TYP.to_string()),
+        ..Default::default()
+    };
+    let access_token = create_jwt(&claims);
+
+    let refresh_token = generate_secret();
+    insert_refresh_token(db, &hash_str(&refresh_token), client_id, &user.user_id, scope, oidc.auth_time)
+        .await
+        .map_err(db_error)?;
+
+    let id_token = issuer.map(|issuer| {
+        let now = Utc::now().timestamp();
+        create_id_token(&IdTokenClaims {
+            iss: issuer.to_string(),
+            sub: user.user_id.clone(),
+            aud: client_id.to_string(),
+            exp: now + oauth::ID_TOKEN_TTL_SECONDS,
+            iat: now,
+            auth_time: oidc.auth_time.and_utc().timestamp(),
+            nonce: oidc.nonce,
+            user_claims: scoped_user_claims(user, scope),
+        })
+    });
+
+    Ok(TokenResponse {
+        access_token,
+        token_type: "Bearer",
+        expires_in: oauth::ACCESS_TOKEN_TTL_SECONDS as i64,
+        scope: claims.scope,
+        refresh_token: Some(refresh_token),
+        id_token,
+    })
+}
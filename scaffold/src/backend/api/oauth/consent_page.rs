@@ -0,0 +1,119 @@
+use actix_web::{http::header, HttpResponse};
+use crate::backend::api::oauth::oauth_error::OAuthError;
+
+/// 内置的授权同意页面
+///
+/// 由 `GET /v1/oauth/authorize` 返回，页面使用 localStorage 中的登录 token
+/// 携带原始查询参数调用 `POST /v2/oauth/authorize`，按结果展示同意界面或直接跳转回客户端。
+pub fn consent_page() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        // 禁止被第三方页面嵌入，防止点击劫持诱导用户点击“允许”
+        .insert_header((header::X_FRAME_OPTIONS, "DENY"))
+        .insert_header((header::CONTENT_SECURITY_POLICY, "frame-ancestors 'none'"))
+        .body(CONSENT_PAGE)
+}
+
+/// 无法重定向回客户端的授权错误（未知客户端、回调地址不匹配等），直接展示给用户
+pub fn error_page(error: &OAuthError) -> HttpResponse {
+    let description = error.error_description.as_deref().unwrap_or(error.error);
+    HttpResponse::build(error.status())
+        .content_type("text/html; charset=utf-8")
+        .body(ERROR_PAGE.replace("{description}", &escape_html(description)))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+const ERROR_PAGE: &str = r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head><meta charset="utf-8"><title>授权失败</title></head>
+<body style="font-family: sans-serif; text-align: center; margin-top: 15vh; color: #333;">
+<h1>授权请求无效</h1>
+<p>{description}</p>
+</body>
+</html>"#;
+
+const CONSENT_PAGE: &str = r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>授权登录</title>
+<style>
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; background: #f5f6fa; margin: 0; }
+.card { max-width: 420px; margin: 15vh auto; background: #fff; border-radius: 12px; padding: 32px; text-align: center; box-shadow: 0 4px 20px rgba(0,0,0,.08); }
+h1 { font-size: 20px; color: #333; }
+p { color: #666; line-height: 1.6; }
+ul { text-align: left; background: #f8f9fc; border-radius: 8px; padding: 12px 32px; }
+button { margin: 16px 6px 0; padding: 12px 24px; border: 0; border-radius: 8px; background: #667eea; color: #fff; font-size: 15px; cursor: pointer; }
+button.secondary { background: #e0e0e0; color: #333; }
+.hidden { display: none; }
+</style>
+</head>
+<body>
+<div class="card">
+<h1>授权登录</h1>
+<div id="consent" class="hidden">
+<p><strong id="client"></strong> 请求访问你的账号：</p>
+<ul id="scopes"></ul>
+<button id="approve">允许</button><button id="deny" class="secondary">拒绝</button>
+</div>
+<p id="message">正在处理…</p>
+</div>
+<script>
+const token = localStorage.getItem('token');
+const $ = (id) => document.getElementById(id);
+const params = Object.fromEntries(new URLSearchParams(location.search));
+
+function show(message) { $('message').textContent = message; }
+
+async function decide(approve) {
+    const resp = await fetch('/v2/oauth/authorize', {
+        method: 'POST',
+        headers: { 'Authorization': 'Bearer ' + token, 'Content-Type': 'application/json' },
+        body: JSON.stringify(approve === undefined ? params : { ...params, approve }),
+    });
+    const json = await resp.json();
+    if (!resp.ok) throw new Error(json.msg || '请求失败');
+    const decision = json.data;
+    if (decision.redirect_to) {
+        location.replace(decision.redirect_to);
+        return;
+    }
+    $('client').textContent = decision.client_name;
+    const scopes = $('scopes');
+    scopes.replaceChildren();
+    (decision.scope ? decision.scope.split(' ') : ['基本信息']).forEach((scope) => {
+        const item = document.createElement('li');
+        item.textContent = scope;
+        scopes.appendChild(item);
+    });
+    $('consent').classList.remove('hidden');
+    show('');
+}
+
+async function run(approve) {
+    try {
+        await decide(approve);
+    } catch (e) {
+        show(e.message);
+    }
+}
+
+if (token) {
+    run(undefined);
+} else {
+    show('请先在此浏览器中登录，然后重新打开此页面。');
+}
+$('approve').onclick = () => run(true);
+$('deny').onclick = () => run(false);
+</script>
+</body>
+</html>"#;
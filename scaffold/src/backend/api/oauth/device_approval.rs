@@ -0,0 +1,232 @@
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use chrono::Utc;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use crate::backend::AppState;
+use crate::backend::api::oauth::device_authorization::{format_user_code, normalize_user_code};
+use crate::backend::api::oauth::handle_device_authorizations::find_by_user_code;
+use crate::backend::api::qr_login::{
+    find_session_by_id, summarize_user_agent, update_session_confirmed, update_session_status,
+};
+use crate::backend::config::{jwt, oauth};
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+use crate::backend::models::{device_authorizations, qr_login_sessions, users};
+use crate::backend::utils::extractors::{extract_claims_from_request, extract_token_from_request};
+use crate::backend::utils::jwt::{create_jwt, Claims};
+use crate::backend::ws_manager::WsManager;
+use crate::backend::ws_protocol::UserEvent;
+
+/// 待批准的设备授权信息，展示给用户核对
+#[derive(Serialize, Debug)]
+pub struct DeviceApprovalInfo {
+    pub user_code: String,
+    pub client_id: String,
+    pub scope: Option<String>,
+    /// 发起授权的设备 IP
+    pub ip: Option<String>,
+    pub browser: Option<String>,
+    pub created_at: i64,
+    pub expires_at: i64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DeviceApprovalRequest {
+    pub user_code: String,
+    /// `true` 批准，`false` 拒绝
+    pub approve: bool,
+}
+
+/// 按用户码查找仍可批准的设备授权
+async fn find_pending_authorization(
+    db: &DatabaseConnection,
+    user_code: &str,
+) -> Result<(device_authorizations::Model, qr_login_sessions::Model), HttpResponse> {
+    let Some(code) = normalize_user_code(user_code) else {
+        let error_resp = error_response(ErrorCode::InvalidFormat, "Invalid user code");
+        return Err(HttpResponse::BadRequest().json(error_resp));
+    };
+
+    let db_error = |e: sea_orm::DbErr| {
+        let error_resp = error_response(ErrorCode::DatabaseError, format!("Database error: {}", e));
+        HttpResponse::InternalServerError().json(error_resp)
+    };
+
+    let authorization = find_by_user_code(db, &code).await.map_err(db_error)?;
+    let session = match authorization {
+        Some(ref a) => find_session_by_id(db, &a.session_id).await.map_err(db_error)?,
+        None => None,
+    };
+    let (Some(authorization), Some(session)) = (authorization, session) else {
+        let error_resp = error_response(ErrorCode::NotFound, "User code not found");
+        return Err(HttpResponse::NotFound().json(error_resp));
+    };
+
+    if session.status != "pending" && session.status != "scanned" {
+        let error_resp = error_response(ErrorCode::ResourceConflict, "This code has already been used");
+        return Err(HttpResponse::BadRequest().json(error_resp));
+    }
+
+    if session.expires_at < Utc::now().naive_utc() {
+        let error_resp = error_response(ErrorCode::ResourceExpired, "This code has expired");
+        return Err(HttpResponse::BadRequest().json(error_resp));
+    }
+
+    Ok((authorization, session))
+}
+
+/// 查询设备授权信息
+///
+/// 路由: GET /v2/oauth/device/{user_code}
+///
+/// 已登录用户在验证页面输入用户码后调用，返回发起授权的客户端与设备信息供用户核对。
+pub async fn get_device_authorization(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    user_code: web::Path<String>,
+) -> HttpResponse {
+    if let Err(e) = extract_claims_from_request(&req) {
+        return e.error_response();
+    }
+
+    let (authorization, session) = match find_pending_authorization(&state.pg_client, &user_code).await {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    // 与扫码一致，用户打开确认界面即标记为已扫码
+    let session = if session.status == "pending" {
+        match update_session_status(&state.pg_client, &session.session_id, "scanned").await {
+            Ok(Some(s)) => s,
+            Ok(None) => {
+                let error_resp = error_response(ErrorCode::ResourceConflict, "Session is no longer pending");
+                return HttpResponse::Conflict().json(error_resp);
+            }
+            Err(e) => {
+                let error_resp = error_response(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to update session: {}", e),
+                );
+                return HttpResponse::InternalServerError().json(error_resp);
+            }
+        }
+    } else {
+        session
+    };
+
+    HttpResponse::Ok().json(SuccessResponse::new(DeviceApprovalInfo {
+        user_code: format_user_code(&authorization.user_code),
+        client_id: authorization.client_id,
+        scope: authorization.scope,
+        ip: session.client_ip,
+        browser: session.user_agent.as_deref().map(summarize_user_agent),
+        created_at: session.created_at.and_utc().timestamp(),
+        expires_at: session.expires_at.and_utc().timestamp(),
+    }))
+}
+
+/// 批准或拒绝设备授权
+///
+/// 路由: POST /v2/oauth/device/approve
+///
+/// 批准后为当前用户签发 token，设备下一次轮询 `/v1/oauth/token` 时取得。
+pub async fn approve_device_authorization(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    ws_manager: web::Data<WsManager>,
+    request: web::Json<DeviceApprovalRequest>,
+) -> HttpResponse {
+    let (claims, bearer_token) = match extract_claims_from_request(&req)
+        .and_then(|claims| extract_token_from_request(&req).map(|token| (claims, token)))
+    {
+        Ok(found) => found,
+        Err(e) => return e.error_response(),
+    };
+
+    let (authorization, session) = match find_pending_authorization(&state.pg_client, &request.user_code).await {
+        Ok(found) => found,
+        Err(resp) => return resp,
+    };
+
+    if !request.approve {
+        match update_session_status(&state.pg_client, &session.session_id, "rejected").await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                let error_resp = error_response(ErrorCode::ResourceConflict, "Session is no longer pending");
+                return HttpResponse::Conflict().json(error_resp);
+            }
+            Err(e) => {
+                let error_resp = error_response(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to update session: {}", e),
+                );
+                return HttpResponse::InternalServerError().json(error_resp);
+            }
+        }
+        info!("🚫 User {} denied device authorization for client {}", claims.user_id, authorization.client_id);
+        return HttpResponse::Ok().json(SuccessResponse::new(serde_json::json!({ "status": "rejected" })));
+    }
+
+    let user = match users::Entity::find()
+        .filter(users::Column::UserId.eq(&claims.user_id))
+        .one(&state.pg_client)
+        .await
+    {
+        Ok(Some(u)) if u.is_active != Some(false) => u,
+        Ok(_) => {
+            warn!("Inactive or unknown user {} tried to approve a device", claims.user_id);
+            let error_resp = error_response(ErrorCode::PermissionDenied, "User account is disabled");
+            return HttpResponse::Forbidden().json(error_resp);
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Database error: {}", e),
+            );
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    };
+
+    let device_claims = Claims {
+        user_id: user.user_id.clone(),
+        username: user.user_id.clone(),
+        exp: Utc::now().timestamp() as usize + jwt::DEFAULT_EXPIRATION_SECONDS,
+        client_id: Some(authorization.client_id.clone()),
+        scope: authorization.scope.clone(),
+        typ: Some(oauth::ACCESS_TOKEN_TYP.to_string()),
+        ..Default::default()
+    };
+    let device_token = create_jwt(&device_claims);
+
+    match update_session_confirmed(
+        &state.pg_client,
+        &session.session_id,
+        &user.user_id,
+        &device_token,
+        &bearer_token,
+    ).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            let error_resp = error_response(ErrorCode::ResourceConflict, "Session is no longer pending");
+            return HttpResponse::Conflict().json(error_resp);
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Failed to update session: {}", e),
+            );
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    }
+
+    info!("✅ User {} approved device authorization for client {}", user.user_id, authorization.client_id);
+
+    // 提醒该用户已登录的其他设备
+    let browser = session.user_agent.as_deref().map(summarize_user_agent);
+    ws_manager.notify_user(
+        &user.user_id,
+        UserEvent::new_login("device", session.client_ip.as_deref(), browser.as_deref()),
+    ).await;
+
+    HttpResponse::Ok().json(SuccessResponse::new(serde_json::json!({ "status": "confirmed" })))
+}
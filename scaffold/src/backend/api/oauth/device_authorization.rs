@@ -0,0 +1,193 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use rand::{thread_rng, Rng, RngCore};
+use sea_orm::SqlErr;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use uuid::Uuid;
+use crate::backend::AppState;
+use crate::backend::api::oauth::handle_device_authorizations::insert_device_authorization;
+use crate::backend::api::oauth::handle_oauth_clients::find_active_client;
+use crate::backend::api::oauth::issuer::configured_base_url;
+use crate::backend::api::oauth::oauth_error::OAuthError;
+use crate::backend::api::oauth::scope::resolve_scopes;
+use crate::backend::api::qr_login::{insert_device_session, LoginContext};
+use crate::backend::config::{device_grant, qr_code};
+use crate::backend::utils::hash::hash_str;
+
+#[derive(Deserialize, Debug)]
+pub struct DeviceAuthorizationRequest {
+    pub client_id: String,
+    pub scope: Option<String>,
+}
+
+/// 设备授权响应（RFC 8628 §3.2）
+#[derive(Serialize, Debug)]
+pub struct DeviceAuthorizationResponse {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: String,
+    pub expires_in: i64,
+    pub interval: i32,
+}
+
+/// 设备授权请求
+///
+/// 路由: POST /v1/oauth/device_authorization（application/x-www-form-urlencoded）
+///
+/// CLI、电视等输入受限的设备调用此接口获得 `device_code` 与 `user_code`，
+/// 提示用户在其他设备上打开 `verification_uri` 并输入 `user_code`，
+/// 然后按 `interval` 轮询 `/v1/oauth/token` 直到用户批准、拒绝或过期。
+///
+/// 只接受已登记的客户端，申请的 scope 必须在客户端允许的范围内。
+pub async fn device_authorization(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    form: web::Form<DeviceAuthorizationRequest>,
+) -> HttpResponse {
+    let client_id = form.client_id.trim();
+    if client_id.is_empty() || client_id.len() > device_grant::CLIENT_ID_MAX_LEN {
+        return OAuthError::invalid_request("Invalid client_id").to_response();
+    }
+
+    let client = match find_active_client(&state.pg_client, client_id).await {
+        Ok(Some(client)) => client,
+        Ok(None) => return OAuthError::invalid_client("Unknown client_id").to_response(),
+        Err(e) => return OAuthError::server_error(format!("Database error: {}", e)).to_response(),
+    };
+    if client.service_account {
+        return OAuthError::unauthorized_client("Service accounts cannot use the device flow").to_response();
+    }
+    let scope = match resolve_scopes(form.scope.as_deref(), &client.scopes) {
+        Ok(scope) => scope,
+        Err(e) => return OAuthError::invalid_scope(e).to_response(),
+    };
+    let base_url = match configured_base_url() {
+        Ok(base_url) => base_url,
+        Err(e) => return e.to_response(),
+    };
+
+    info!("Received device authorization request from client: {}", client_id);
+
+    // 会话的状态、过期与签发的 token 复用扫码登录会话
+    let session_id = Uuid::new_v4().to_string();
+    let ttl_seconds = qr_code::TTL_SECONDS as i64;
+    let context = LoginContext::from_request(&req, Some(client_id));
+
+    if let Err(e) = insert_device_session(&state.pg_client, &session_id, ttl_seconds, &context).await {
+        return OAuthError::server_error(format!("Failed to create session: {}", e)).to_response();
+    }
+
+    let device_code = generate_device_code();
+    let device_code_hash = hash_str(&device_code);
+
+    // 用户码空间较大，冲突极少；冲突时重新生成
+    let mut user_code = generate_user_code();
+    let mut attempts = 0;
+    loop {
+        match insert_device_authorization(
+            &state.pg_client,
+            &session_id,
+            &device_code_hash,
+            &user_code,
+            client_id,
+            Some(&scope),
+            device_grant::POLL_INTERVAL_SECONDS,
+        ).await {
+            Ok(_) => break,
+            Err(e) if attempts < 3 && matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                warn!("User code collision, regenerating: {}", e);
+                user_code = generate_user_code();
+                attempts += 1;
+            }
+            Err(e) => {
+                return OAuthError::server_error(format!("Failed to create device authorization: {}", e))
+                    .to_response();
+            }
+        }
+    }
+
+    let display_code = format_user_code(&user_code);
+    let verification_uri = format!("{}/device", base_url);
+
+    HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .json(DeviceAuthorizationResponse {
+            verification_uri_complete: format!("{}?user_code={}", verification_uri, display_code),
+            verification_uri,
+            device_code,
+            user_code: display_code,
+            expires_in: ttl_seconds,
+            interval: device_grant::POLL_INTERVAL_SECONDS,
+        })
+}
+
+/// 生成 device_code（URL 安全 Base64），数据库中只保存其哈希
+fn generate_device_code() -> String {
+    let mut bytes = [0u8; device_grant::DEVICE_CODE_BYTES];
+    thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// 生成用户码（不含分隔符）
+fn generate_user_code() -> String {
+    let mut rng = thread_rng();
+    (0..device_grant::USER_CODE_LENGTH)
+        .map(|_| {
+            let index = rng.gen_range(0, device_grant::USER_CODE_ALPHABET.len());
+            device_grant::USER_CODE_ALPHABET[index] as char
+        })
+        .collect()
+}
+
+/// 用户码展示格式，如 `WDJB-MJHT`
+pub fn format_user_code(code: &str) -> String {
+    let (left, right) = code.split_at(code.len() / 2);
+    format!("{}-{}", left, right)
+}
+
+/// 规范化用户输入的用户码：忽略大小写、空格与分隔符
+pub fn normalize_user_code(input: &str) -> Option<String> {
+    let code: String = input
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    let valid = code.len() == device_grant::USER_CODE_LENGTH
+        && code.bytes().all(|b| device_grant::USER_CODE_ALPHABET.contains(&b));
+    valid.then_some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_code_roundtrip() {
+        let code = generate_user_code();
+        assert_eq!(code.len(), device_grant::USER_CODE_LENGTH);
+
+        let display = format_user_code(&code);
+        assert_eq!(display.len(), device_grant::USER_CODE_LENGTH + 1);
+        assert_eq!(normalize_user_code(&display), Some(code.clone()));
+        assert_eq!(normalize_user_code(&display.to_lowercase()), Some(code));
+    }
+
+    #[test]
+    fn test_normalize_user_code() {
+        assert_eq!(normalize_user_code(" wdjb mjht "), Some("WDJBMJHT".to_string()));
+        assert_eq!(normalize_user_code("WDJB-MJH"), None);
+        // 字符集不包含元音
+        assert_eq!(normalize_user_code("AEIO-UAEI"), None);
+    }
+
+    #[test]
+    fn test_device_code_is_url_safe() {
+        let code = generate_device_code();
+        assert!(code.len() >= 43);
+        assert!(code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_ne!(code, generate_device_code());
+    }
+}
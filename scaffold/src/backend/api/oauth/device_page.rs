@@ -0,0 +1,108 @@
+use actix_web::{http::header, HttpResponse};
+
+/// 设备验证页面
+///
+/// 路由: /device[?user_code=XXXX-XXXX]
+///
+/// 用户在已登录的浏览器中打开此页面，输入设备上显示的用户码，
+/// 核对客户端与设备信息后批准或拒绝。页面使用 localStorage 中的登录 token
+/// 调用 `/v2/oauth/device/*` 接口。
+pub async fn device_verification_page() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        // 禁止被第三方页面嵌入，防止点击劫持诱导用户批准设备
+        .insert_header((header::X_FRAME_OPTIONS, "DENY"))
+        .insert_header((header::CONTENT_SECURITY_POLICY, "frame-ancestors 'none'"))
+        .body(DEVICE_PAGE)
+}
+
+const DEVICE_PAGE: &str = r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>设备登录</title>
+<style>
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; background: #f5f6fa; margin: 0; }
+.card { max-width: 420px; margin: 15vh auto; background: #fff; border-radius: 12px; padding: 32px; text-align: center; box-shadow: 0 4px 20px rgba(0,0,0,.08); }
+h1 { font-size: 20px; color: #333; }
+p { color: #666; line-height: 1.6; }
+input { font-size: 24px; letter-spacing: 4px; text-align: center; text-transform: uppercase; width: 220px; padding: 8px; border: 1px solid #ccc; border-radius: 8px; }
+button { margin: 16px 6px 0; padding: 12px 24px; border: 0; border-radius: 8px; background: #667eea; color: #fff; font-size: 15px; cursor: pointer; }
+button.secondary { background: #e0e0e0; color: #333; }
+.hidden { display: none; }
+.details { text-align: left; background: #f8f9fc; border-radius: 8px; padding: 12px 16px; }
+</style>
+</head>
+<body>
+<div class="card">
+<h1>设备登录</h1>
+<div id="enter">
+<p>请输入设备上显示的代码</p>
+<input id="code" maxlength="9" autocomplete="off" placeholder="XXXX-XXXX">
+<div><button id="lookup">下一步</button></div>
+</div>
+<div id="confirm" class="hidden">
+<p>以下设备正在请求登录你的账号，请确认是你本人操作：</p>
+<div class="details" id="details"></div>
+<button id="approve">允许</button><button id="deny" class="secondary">拒绝</button>
+</div>
+<p id="message"></p>
+</div>
+<script>
+const token = localStorage.getItem('token');
+const $ = (id) => document.getElementById(id);
+const params = new URLSearchParams(location.search);
+$('code').value = params.get('user_code') || '';
+
+function show(message) { $('message').textContent = message; }
+
+async function call(method, url, body) {
+    const resp = await fetch(url, {
+        method,
+        headers: { 'Authorization': 'Bearer ' + token, 'Content-Type': 'application/json' },
+        body: body ? JSON.stringify(body) : undefined,
+    });
+    const json = await resp.json();
+    if (!resp.ok) throw new Error(json.msg || '请求失败');
+    return json.data;
+}
+
+if (!token) {
+    $('enter').classList.add('hidden');
+    show('请先在此浏览器中登录，然后重新打开此页面。');
+}
+
+$('lookup').onclick = async () => {
+    try {
+        const info = await call('GET', '/v2/oauth/device/' + encodeURIComponent($('code').value));
+        const details = $('details');
+        details.replaceChildren();
+        [['应用', info.client_id], ['权限', info.scope || '-'], ['设备', info.browser || '-'], ['IP', info.ip || '-']]
+            .forEach(([label, value]) => {
+                const line = document.createElement('div');
+                line.textContent = label + '：' + value;
+                details.appendChild(line);
+            });
+        $('enter').classList.add('hidden');
+        $('confirm').classList.remove('hidden');
+        show('');
+    } catch (e) {
+        show(e.message);
+    }
+};
+
+async function respond(approve) {
+    try {
+        await call('POST', '/v2/oauth/device/approve', { user_code: $('code').value, approve });
+        $('confirm').classList.add('hidden');
+        show(approve ? '已允许，请返回设备继续操作。' : '已拒绝该设备的登录请求。');
+    } catch (e) {
+        show(e.message);
+    }
+}
+$('approve').onclick = () => respond(true);
+$('deny').onclick = () => respond(false);
+</script>
+</body>
+</html>"#;
@@ -0,0 +1,60 @@
+use actix_web::HttpResponse;
+use serde_json::json;
+use crate::backend::api::oauth::issuer::configured_base_url;
+use crate::backend::config::{device_grant, oauth};
+use crate::backend::utils::jwt::public_jwk;
+
+/// OpenID Connect 发现文档
+///
+/// 路由: GET /.well-known/openid-configuration
+///
+/// `issuer` 与 id_token 中的 `iss` 一致，OIDC 客户端库据此自动发现各个端点；
+/// 取配置的站点根地址（`QR_LOGIN_BASE_URL`），未配置时返回错误。
+pub async fn openid_configuration() -> HttpResponse {
+    let issuer = match configured_base_url() {
+        Ok(issuer) => issuer,
+        Err(e) => return e.to_response(),
+    };
+
+    HttpResponse::Ok().json(json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{}/v1/oauth/authorize", issuer),
+        "token_endpoint": format!("{}/v1/oauth/token", issuer),
+        "userinfo_endpoint": format!("{}/v1/oauth/userinfo", issuer),
+        "jwks_uri": format!("{}/v1/oauth/jwks", issuer),
+        "introspection_endpoint": format!("{}/v1/oauth/introspect", issuer),
+        "revocation_endpoint": format!("{}/v1/oauth/revoke", issuer),
+        "device_authorization_endpoint": format!("{}/v1/oauth/device_authorization", issuer),
+        "response_types_supported": ["code"],
+        "grant_types_supported": [
+            oauth::GRANT_AUTHORIZATION_CODE,
+            oauth::GRANT_REFRESH_TOKEN,
+            oauth::GRANT_CLIENT_CREDENTIALS,
+            device_grant::GRANT_TYPE,
+        ],
+        "subject_types_supported": ["public"],
+        "id_token_signing_alg_values_supported": ["EdDSA"],
+        "scopes_supported": [
+            oauth::SCOPE_OPENID,
+            oauth::SCOPE_PROFILE,
+            oauth::SCOPE_EMAIL,
+            oauth::SCOPE_PHONE,
+        ],
+        "claims_supported": [
+            "iss", "sub", "aud", "exp", "iat", "auth_time", "nonce",
+            "preferred_username", "updated_at", "email", "email_verified", "phone_number",
+        ],
+        "token_endpoint_auth_methods_supported": ["client_secret_basic", "client_secret_post", "private_key_jwt", "none"],
+        "token_endpoint_auth_signing_alg_values_supported": ["EdDSA"],
+        "introspection_endpoint_auth_methods_supported": ["client_secret_basic", "client_secret_post"],
+        "revocation_endpoint_auth_methods_supported": ["client_secret_basic", "client_secret_post", "none"],
+        "code_challenge_methods_supported": [oauth::PKCE_METHOD_S256],
+    }))
+}
+
+/// id_token 签名公钥
+///
+/// 路由: GET /v1/oauth/jwks
+pub async fn jwks() -> HttpResponse {
+    HttpResponse::Ok().json(json!({ "keys": [public_jwk()] }))
+}
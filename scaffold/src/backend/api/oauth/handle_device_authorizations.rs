@@ -0,0 +1,66 @@
+use chrono::Utc;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set};
+use tracing::info;
+use crate::backend::models::device_authorizations;
+use crate::backend::models::prelude::DeviceAuthorizations;
+
+pub async fn insert_device_authorization(
+    db: &DatabaseConnection,
+    session_id: &str,
+    device_code_hash: &str,
+    user_code: &str,
+    client_id: &str,
+    scope: Option<&str>,
+    interval_seconds: i32,
+) -> Result<device_authorizations::Model, DbErr> {
+    let new_authorization = device_authorizations::ActiveModel {
+        session_id: Set(session_id.to_string()),
+        device_code_hash: Set(device_code_hash.to_string()),
+        user_code: Set(user_code.to_string()),
+        client_id: Set(client_id.to_string()),
+        scope: Set(scope.map(str::to_string)),
+        interval_seconds: Set(interval_seconds),
+        created_at: Set(Utc::now().naive_utc()),
+        ..Default::default()
+    };
+
+    let inserted = new_authorization.insert(db).await?;
+    info!("Inserted device authorization for session {} (client: {})", session_id, client_id);
+    Ok(inserted)
+}
+
+pub async fn find_by_device_code_hash(
+    db: &DatabaseConnection,
+    device_code_hash: &str,
+) -> Result<Option<device_authorizations::Model>, DbErr> {
+    DeviceAuthorizations::find()
+        .filter(device_authorizations::Column::DeviceCodeHash.eq(device_code_hash))
+        .one(db)
+        .await
+}
+
+pub async fn find_by_user_code(
+    db: &DatabaseConnection,
+    user_code: &str,
+) -> Result<Option<device_authorizations::Model>, DbErr> {
+    DeviceAuthorizations::find()
+        .filter(device_authorizations::Column::UserCode.eq(user_code))
+        .one(db)
+        .await
+}
+
+/// 记录一次轮询，并设置之后的最小轮询间隔
+pub async fn record_poll(
+    db: &DatabaseConnection,
+    id: i64,
+    interval_seconds: i32,
+) -> Result<(), DbErr> {
+    DeviceAuthorizations::update_many()
+        .col_expr(device_authorizations::Column::LastPolledAt, Expr::value(Utc::now().naive_utc()))
+        .col_expr(device_authorizations::Column::IntervalSeconds, Expr::value(interval_seconds))
+        .filter(device_authorizations::Column::Id.eq(id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use tracing::info;
+use crate::backend::models::{oauth_client_assertions, oauth_clients};
+use crate::backend::models::prelude::{OauthClientAssertions, OauthClients};
+
+pub struct NewClient<'a> {
+    pub client_id: &'a str,
+    pub client_secret_hash: Option<&'a str>,
+    pub name: &'a str,
+    pub redirect_uris: &'a [String],
+    pub scopes: &'a str,
+    pub skip_consent: bool,
+    pub created_by: &'a str,
+    /// 服务账号只能使用 client_credentials 授权
+    pub service_account: bool,
+    /// 服务账号用于 client_assertion 的 Ed25519 公钥（bs58）
+    pub jwt_public_key: Option<&'a str>,
+}
+
+pub async fn insert_client(
+    db: &DatabaseConnection,
+    client: NewClient<'_>,
+) -> Result<oauth_clients::Model, DbErr> {
+    let new_client = oauth_clients::ActiveModel {
+        client_id: Set(client.client_id.to_string()),
+        client_secret_hash: Set(client.client_secret_hash.map(str::to_string)),
+        name: Set(client.name.to_string()),
+        redirect_uris: Set(serde_json::json!(client.redirect_uris)),
+        scopes: Set(client.scopes.to_string()),
+        skip_consent: Set(client.skip_consent),
+        created_by: Set(Some(client.created_by.to_string())),
+        service_account: Set(client.service_account),
+        jwt_public_key: Set(client.jwt_public_key.map(str::to_string)),
+        created_at: Set(Utc::now().naive_utc()),
+        ..Default::default()
+    };
+
+    let inserted = new_client.insert(db).await?;
+    info!("Registered OAuth client {} ({})", client.client_id, client.name);
+    Ok(inserted)
+}
+
+pub async fn find_active_client(
+    db: &DatabaseConnection,
+    client_id: &str,
+) -> Result<Option<oauth_clients::Model>, DbErr> {
+    OauthClients::find()
+        .filter(oauth_clients::Column::ClientId.eq(client_id))
+        .filter(oauth_clients::Column::RevokedAt.is_null())
+        .one(db)
+        .await
+}
+
+/// 未吊销的客户端，按创建时间倒序
+pub async fn list_active_clients(db: &DatabaseConnection) -> Result<Vec<oauth_clients::Model>, DbErr> {
+    OauthClients::find()
+        .filter(oauth_clients::Column::RevokedAt.is_null())
+        .order_by_desc(oauth_clients::Column::CreatedAt)
+        .all(db)
+        .await
+}
+
+/// 吊销客户端，返回是否有客户端被吊销
+pub async fn revoke_client(db: &DatabaseConnection, client_id: &str) -> Result<bool, DbErr> {
+    let result = OauthClients::update_many()
+        .col_expr(oauth_clients::Column::RevokedAt, Utc::now().naive_utc().into())
+        .filter(oauth_clients::Column::ClientId.eq(client_id))
+        .filter(oauth_clients::Column::RevokedAt.is_null())
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected > 0)
+}
+
+/// 客户端登记的回调地址
+pub fn client_redirect_uris(client: &oauth_clients::Model) -> Vec<String> {
+    serde_json::from_value(client.redirect_uris.clone()).unwrap_or_default()
+}
+
+/// 记录已使用的 client_assertion，返回 `false` 表示该 jti 已被使用过（重放）
+pub async fn record_client_assertion(
+    db: &DatabaseConnection,
+    jti_hash: &str,
+    client_id: &str,
+    expires_at: NaiveDateTime,
+) -> Result<bool, DbErr> {
+    let assertion = oauth_client_assertions::ActiveModel {
+        jti_hash: Set(jti_hash.to_string()),
+        client_id: Set(client_id.to_string()),
+        expires_at: Set(expires_at),
+    };
+
+    let inserted = OauthClientAssertions::insert(assertion)
+        .on_conflict(OnConflict::column(oauth_client_assertions::Column::JtiHash).do_nothing().to_owned())
+        .exec_without_returning(db)
+        .await?;
+    Ok(inserted > 0)
+}
+
+/// 删除已过期的 client_assertion 记录（过期的断言本身已无法通过校验）
+pub async fn purge_client_assertions_before(db: &DatabaseConnection, now: NaiveDateTime) -> Result<u64, DbErr> {
+    let result = OauthClientAssertions::delete_many()
+        .filter(oauth_client_assertions::Column::ExpiresAt.lt(now))
+        .exec(db)
+        .await?;
+
+    if result.rows_affected > 0 {
+        info!("Purged {} expired client assertions", result.rows_affected);
+    }
+    Ok(result.rows_affected)
+}
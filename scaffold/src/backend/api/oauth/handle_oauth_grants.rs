@@ -0,0 +1,198 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set};
+use tracing::{info, warn};
+use crate::backend::api::oauth::handle_revoked_tokens::revoke_grant;
+use crate::backend::config::oauth;
+use crate::backend::models::{oauth_authorization_codes, oauth_consents, oauth_refresh_tokens};
+use crate::backend::models::prelude::{OauthAuthorizationCodes, OauthConsents, OauthRefreshTokens};
+
+pub struct NewAuthorizationCode<'a> {
+    pub code_hash: &'a str,
+    pub client_id: &'a str,
+    pub user_id: &'a str,
+    pub redirect_uri: &'a str,
+    pub scope: &'a str,
+    pub code_challenge: &'a str,
+    pub code_challenge_method: &'a str,
+    /// OpenID Connect 授权请求的 nonce，原样写入 id_token
+    pub nonce: Option<&'a str>,
+    /// 用户完成认证的时间
+    pub auth_time: NaiveDateTime,
+}
+
+pub async fn insert_authorization_code(
+    db: &DatabaseConnection,
+    code: NewAuthorizationCode<'_>,
+) -> Result<oauth_authorization_codes::Model, DbErr> {
+    let now = Utc::now().naive_utc();
+    let new_code = oauth_authorization_codes::ActiveModel {
+        code_hash: Set(code.code_hash.to_string()),
+        client_id: Set(code.client_id.to_string()),
+        user_id: Set(code.user_id.to_string()),
+        redirect_uri: Set(code.redirect_uri.to_string()),
+        scope: Set(code.scope.to_string()),
+        code_challenge: Set(code.code_challenge.to_string()),
+        code_challenge_method: Set(code.code_challenge_method.to_string()),
+        nonce: Set(code.nonce.map(str::to_string)),
+        auth_time: Set(code.auth_time),
+        expires_at: Set(now + Duration::seconds(oauth::AUTHORIZATION_CODE_TTL_SECONDS)),
+        created_at: Set(now),
+        ..Default::default()
+    };
+
+    let inserted = new_code.insert(db).await?;
+    info!("Issued authorization code for user {} (client: {})", code.user_id, code.client_id);
+    Ok(inserted)
+}
+
+pub async fn find_authorization_code(
+    db: &DatabaseConnection,
+    code_hash: &str,
+) -> Result<Option<oauth_authorization_codes::Model>, DbErr> {
+    OauthAuthorizationCodes::find()
+        .filter(oauth_authorization_codes::Column::CodeHash.eq(code_hash))
+        .one(db)
+        .await
+}
+
+/// 消费授权码，返回 `false` 表示授权码已被使用或已过期
+///
+/// 条件更新保证并发请求中只有一个能成功
+pub async fn consume_authorization_code(db: &DatabaseConnection, id: i64) -> Result<bool, DbErr> {
+    let now = Utc::now().naive_utc();
+    let result = OauthAuthorizationCodes::update_many()
+        .col_expr(oauth_authorization_codes::Column::ConsumedAt, now.into())
+        .filter(oauth_authorization_codes::Column::Id.eq(id))
+        .filter(oauth_authorization_codes::Column::ConsumedAt.is_null())
+        .filter(oauth_authorization_codes::Column::ExpiresAt.gt(now))
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected > 0)
+}
+
+pub async fn insert_refresh_token(
+    db: &DatabaseConnection,
+    token_hash: &str,
+    client_id: &str,
+    user_id: &str,
+    scope: &str,
+    auth_time: NaiveDateTime,
+) -> Result<oauth_refresh_tokens::Model, DbErr> {
+    let now = Utc::now().naive_utc();
+    let new_token = oauth_refresh_tokens::ActiveModel {
+        token_hash: Set(token_hash.to_string()),
+        client_id: Set(client_id.to_string()),
+        user_id: Set(user_id.to_string()),
+        scope: Set(scope.to_string()),
+        auth_time: Set(auth_time),
+        expires_at: Set(now + Duration::seconds(oauth::REFRESH_TOKEN_TTL_SECONDS)),
+        created_at: Set(now),
+        ..Default::default()
+    };
+
+    new_token.insert(db).await
+}
+
+pub async fn find_refresh_token(
+    db: &DatabaseConnection,
+    token_hash: &str,
+) -> Result<Option<oauth_refresh_tokens::Model>, DbErr> {
+    OauthRefreshTokens::find()
+        .filter(oauth_refresh_tokens::Column::TokenHash.eq(token_hash))
+        .one(db)
+        .await
+}
+
+/// 吊销单个 refresh token，返回 `false` 表示它已被吊销（轮换时并发重用）
+pub async fn revoke_refresh_token(db: &DatabaseConnection, id: i64) -> Result<bool, DbErr> {
+    let result = OauthRefreshTokens::update_many()
+        .col_expr(oauth_refresh_tokens::Column::RevokedAt, Utc::now().naive_utc().into())
+        .filter(oauth_refresh_tokens::Column::Id.eq(id))
+        .filter(oauth_refresh_tokens::Column::RevokedAt.is_null())
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected > 0)
+}
+
+/// 吊销某客户端为某用户签发的全部 refresh token，已签发的 access token 一并失效
+///
+/// 检测到授权码或 refresh token 被重用时调用，视为凭证已泄露
+pub async fn revoke_refresh_tokens_for(
+    db: &DatabaseConnection,
+    client_id: &str,
+    user_id: &str,
+) -> Result<u64, DbErr> {
+    revoke_grant(db, client_id, user_id).await?;
+    let result = OauthRefreshTokens::update_many()
+        .col_expr(oauth_refresh_tokens::Column::RevokedAt, Utc::now().naive_utc().into())
+        .filter(oauth_refresh_tokens::Column::ClientId.eq(client_id))
+        .filter(oauth_refresh_tokens::Column::UserId.eq(user_id))
+        .filter(oauth_refresh_tokens::Column::RevokedAt.is_null())
+        .exec(db)
+        .await?;
+
+    if result.rows_affected > 0 {
+        warn!(
+            "Revoked {} refresh tokens of user {} for client {}",
+            result.rows_affected, user_id, client_id
+        );
+    }
+    Ok(result.rows_affected)
+}
+
+/// 吊销用户在所有客户端的 refresh token（修改密码后调用）
+pub async fn revoke_refresh_tokens_of_user<C: ConnectionTrait>(db: &C, user_id: &str) -> Result<u64, DbErr> {
+    let result = OauthRefreshTokens::update_many()
+        .col_expr(oauth_refresh_tokens::Column::RevokedAt, Utc::now().naive_utc().into())
+        .filter(oauth_refresh_tokens::Column::UserId.eq(user_id))
+        .filter(oauth_refresh_tokens::Column::RevokedAt.is_null())
+        .exec(db)
+        .await?;
+
+    if result.rows_affected > 0 {
+        info!("Revoked {} refresh tokens of user {}", result.rows_affected, user_id);
+    }
+    Ok(result.rows_affected)
+}
+
+pub async fn find_consent(
+    db: &DatabaseConnection,
+    user_id: &str,
+    client_id: &str,
+) -> Result<Option<oauth_consents::Model>, DbErr> {
+    OauthConsents::find_by_id((user_id.to_string(), client_id.to_string()))
+        .one(db)
+        .await
+}
+
+/// 记录用户对客户端的授权同意，已有记录时覆盖 scope
+pub async fn upsert_consent(
+    db: &DatabaseConnection,
+    user_id: &str,
+    client_id: &str,
+    scope: &str,
+) -> Result<(), DbErr> {
+    let now = Utc::now().naive_utc();
+    let consent = oauth_consents::ActiveModel {
+        user_id: Set(user_id.to_string()),
+        client_id: Set(client_id.to_string()),
+        scope: Set(scope.to_string()),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+
+    OauthConsents::insert(consent)
+        .on_conflict(
+            OnConflict::columns([oauth_consents::Column::UserId, oauth_consents::Column::ClientId])
+                .update_columns([oauth_consents::Column::Scope, oauth_consents::Column::UpdatedAt])
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    info!("Recorded consent of user {} for client {}", user_id, client_id);
+    Ok(())
+}
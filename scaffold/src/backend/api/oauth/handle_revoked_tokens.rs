@@ -0,0 +1,155 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect, Set};
+use tracing::info;
+use crate::backend::config::jwt;
+use crate::backend::models::{oauth_grant_revocations, revoked_tokens, users};
+use crate::backend::models::prelude::{OauthGrantRevocations, RevokedTokens, Users};
+use crate::backend::utils::hash::hash_str;
+use crate::backend::utils::jwt::{verify_jwt, Claims};
+
+/// 吊销 access token，记录保留到 token 原本的过期时间
+pub async fn revoke_access_token(db: &DatabaseConnection, token: &str, claims: &Claims) -> Result<(), DbErr> {
+    let now = Utc::now().naive_utc();
+    let expires_at = DateTime::from_timestamp(claims.exp as i64, 0)
+        .map(|t| t.naive_utc())
+        .unwrap_or(now);
+    let revoked = revoked_tokens::ActiveModel {
+        token_hash: Set(hash_str(token)),
+        user_id: Set(claims.user_id.clone()),
+        client_id: Set(claims.client_id.clone()),
+        expires_at: Set(expires_at),
+        revoked_at: Set(now),
+    };
+
+    // 重复吊销是幂等的
+    RevokedTokens::insert(revoked)
+        .on_conflict(OnConflict::column(revoked_tokens::Column::TokenHash).do_nothing().to_owned())
+        .exec_without_returning(db)
+        .await?;
+    info!("Revoked access token of user {}", claims.user_id);
+    Ok(())
+}
+
+/// 吊销某客户端为某用户的整个授权：此前签发的 access token（包括续期得到的）全部失效
+///
+/// 吊销 refresh token 或检测到凭证重用时调用；之后新签发的 token 不受影响。
+pub async fn revoke_grant(db: &DatabaseConnection, client_id: &str, user_id: &str) -> Result<(), DbErr> {
+    let revocation = oauth_grant_revocations::ActiveModel {
+        user_id: Set(user_id.to_string()),
+        client_id: Set(client_id.to_string()),
+        revoked_at: Set(Utc::now().naive_utc()),
+    };
+
+    OauthGrantRevocations::insert(revocation)
+        .on_conflict(
+            OnConflict::columns([
+                oauth_grant_revocations::Column::UserId,
+                oauth_grant_revocations::Column::ClientId,
+            ])
+            .update_column(oauth_grant_revocations::Column::RevokedAt)
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+    info!("Revoked grant of user {} for client {}", user_id, client_id);
+    Ok(())
+}
+
+/// access token 是否已被吊销；`Auth` 中间件与 token 自省使用同一判断
+///
+/// 除了单独吊销的 token，签发时间早于所属授权的吊销时间（见 `revoke_grant`）
+/// 或用户 `sessions_revoked_at`（例如修改密码）的 token 也视为已吊销；用户已被删除时同样视为已吊销。
+pub async fn is_token_revoked(db: &DatabaseConnection, token: &str, claims: &Claims) -> Result<bool, DbErr> {
+    if RevokedTokens::find_by_id(hash_str(token)).one(db).await?.is_some() {
+        return Ok(true);
+    }
+    if let Some(client_id) = &claims.client_id {
+        let grant_revoked_at = OauthGrantRevocations::find_by_id((claims.user_id.clone(), client_id.clone()))
+            .one(db)
+            .await?
+            .map(|revocation| revocation.revoked_at);
+        if issued_before(claims, grant_revoked_at) {
+            return Ok(true);
+        }
+    }
+    if claims.service_account {
+        return Ok(false);
+    }
+
+    let sessions_revoked_at: Option<Option<NaiveDateTime>> = Users::find()
+        .select_only()
+        .column(users::Column::SessionsRevokedAt)
+        .filter(users::Column::UserId.eq(&claims.user_id))
+        .into_tuple()
+        .one(db)
+        .await?;
+    match sessions_revoked_at {
+        Some(revoked_at) => Ok(issued_before(claims, revoked_at)),
+        None => Ok(true),
+    }
+}
+
+/// token 是否签发于 `revoked_at` 之前，按毫秒比较
+///
+/// 只有 `iat` 的旧 token 视为在该秒开始时签发；都没有的视为更早签发
+fn issued_before(claims: &Claims, revoked_at: Option<NaiveDateTime>) -> bool {
+    let issued_at_ms = claims
+        .iat_ms
+        .unwrap_or_else(|| claims.iat.unwrap_or(0) as i64 * 1000);
+    revoked_at.is_some_and(|t| issued_at_ms < t.and_utc().timestamp_millis())
+}
+
+/// 验证 access token 的签名与有效期，并确认未被吊销，返回 `None` 表示 token 无效
+pub async fn active_claims(db: &DatabaseConnection, token: &str) -> Result<Option<Claims>, DbErr> {
+    let Ok(data) = verify_jwt(token) else {
+        return Ok(None);
+    };
+    if is_token_revoked(db, token, &data.claims).await? {
+        return Ok(None);
+    }
+    Ok(Some(data.claims))
+}
+
+/// 删除已过期的吊销记录（过期的 token 本身已无法通过验证）
+///
+/// 授权吊销记录在超过 access token 最长有效期后同样不再起作用。
+pub async fn purge_revoked_tokens_before(db: &DatabaseConnection, now: NaiveDateTime) -> Result<u64, DbErr> {
+    let result = RevokedTokens::delete_many()
+        .filter(revoked_tokens::Column::ExpiresAt.lt(now))
+        .exec(db)
+        .await?;
+
+    let grant_cutoff = now - Duration::seconds(jwt::DEFAULT_EXPIRATION_SECONDS as i64);
+    let grants = OauthGrantRevocations::delete_many()
+        .filter(oauth_grant_revocations::Column::RevokedAt.lt(grant_cutoff))
+        .exec(db)
+        .await?;
+
+    let purged = result.rows_affected + grants.rows_affected;
+    if purged > 0 {
+        info!("Purged {} expired revoked tokens and grant revocations", purged);
+    }
+    Ok(purged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issued_before() {
+        let revoked_at = DateTime::from_timestamp(1_800_000_000, 500_000_000).map(|t| t.naive_utc());
+        let claims = |iat: Option<usize>, iat_ms: Option<i64>| Claims { iat, iat_ms, ..Default::default() };
+
+        assert!(!issued_before(&claims(Some(1_700_000_000), Some(1_700_000_000_000)), None));
+        assert!(issued_before(&claims(Some(1_799_999_999), Some(1_799_999_999_900)), revoked_at));
+        assert!(issued_before(&claims(None, None), revoked_at));
+        // 同一秒内、吊销之前签发的 token 同样失效
+        assert!(issued_before(&claims(Some(1_800_000_000), Some(1_800_000_000_200)), revoked_at));
+        assert!(issued_before(&claims(Some(1_800_000_000), None), revoked_at));
+        // 吊销之后签发的新 token 仍然有效
+        assert!(!issued_before(&claims(Some(1_800_000_000), Some(1_800_000_000_500)), revoked_at));
+        assert!(!issued_before(&claims(Some(1_800_000_000), Some(1_800_000_000_800)), revoked_at));
+    }
+}
@@ -0,0 +1,158 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use chrono::Utc;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use crate::backend::AppState;
+use crate::backend::api::oauth::client_auth::authenticate_client;
+use crate::backend::api::oauth::handle_oauth_grants::find_refresh_token;
+use crate::backend::api::oauth::handle_revoked_tokens::active_claims;
+use crate::backend::api::oauth::oauth_error::OAuthError;
+use crate::backend::models::{oauth_clients, oauth_refresh_tokens};
+use crate::backend::models::sea_orm_active_enums::UserRoleType;
+use crate::backend::utils::hash::hash_str;
+use crate::backend::utils::jwt::Claims;
+
+/// 自省与吊销接口的请求参数（RFC 7662 §2.1 / RFC 7009 §2.1）
+///
+/// `token_type_hint` 可以省略：两种 token 的格式不同，服务端总能自行判断。
+#[derive(Deserialize, Debug)]
+pub struct TokenOperationRequest {
+    pub token: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+}
+
+/// token 自省结果（RFC 7662 §2.2），token 无效时只返回 `active: false`
+#[derive(Serialize, Debug, Default, PartialEq, Eq)]
+pub struct IntrospectionResponse {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<UserRoleType>,
+}
+
+impl IntrospectionResponse {
+    pub fn inactive() -> Self {
+        Self::default()
+    }
+}
+
+impl From<Claims> for IntrospectionResponse {
+    fn from(claims: Claims) -> Self {
+        Self {
+            active: true,
+            scope: claims.scope,
+            client_id: claims.client_id,
+            username: Some(claims.username),
+            token_type: Some("Bearer"),
+            exp: Some(claims.exp as i64),
+            sub: Some(claims.user_id),
+            role: claims.role,
+        }
+    }
+}
+
+impl From<oauth_refresh_tokens::Model> for IntrospectionResponse {
+    fn from(token: oauth_refresh_tokens::Model) -> Self {
+        Self {
+            active: true,
+            scope: Some(token.scope).filter(|s| !s.is_empty()),
+            client_id: Some(token.client_id),
+            username: Some(token.user_id.clone()),
+            token_type: Some("refresh_token"),
+            exp: Some(token.expires_at.and_utc().timestamp()),
+            sub: Some(token.user_id),
+            role: None,
+        }
+    }
+}
+
+/// token 自省
+///
+/// 路由: POST /v1/oauth/introspect（application/x-www-form-urlencoded）
+///
+/// 供资源服务确认 token 是否仍然有效，只允许机密客户端调用。access token 的判断与
+/// `Auth` 中间件一致（签名、有效期、是否已吊销）；refresh token 只对签发它的客户端可见。
+pub async fn introspect(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    form: web::Form<TokenOperationRequest>,
+) -> HttpResponse {
+    match introspect_token(&req, &state.pg_client, &form).await {
+        Ok(response) => HttpResponse::Ok()
+            .insert_header((header::CACHE_CONTROL, "no-store"))
+            .json(response),
+        Err(e) => e.to_response(),
+    }
+}
+
+async fn introspect_token(
+    req: &HttpRequest,
+    db: &DatabaseConnection,
+    form: &TokenOperationRequest,
+) -> Result<IntrospectionResponse, OAuthError> {
+    let client = authenticate_client(req, db, form.client_id.as_deref(), form.client_secret.as_deref()).await?;
+    if client.client_secret_hash.is_none() {
+        return Err(OAuthError::unauthorized_client("Token introspection requires a confidential client"));
+    }
+
+    let Some(token) = form.token.as_deref() else {
+        return Err(OAuthError::invalid_request("token is required"));
+    };
+
+    let db_error = |e: sea_orm::DbErr| OAuthError::server_error(format!("Database error: {}", e));
+    if let Some(claims) = active_claims(db, token).await.map_err(db_error)? {
+        return Ok(claims.into());
+    }
+
+    let refresh_token = find_refresh_token(db, &hash_str(token)).await.map_err(db_error)?;
+    Ok(match refresh_token {
+        Some(t) if is_active_refresh_token(&t, &client) => t.into(),
+        _ => IntrospectionResponse::inactive(),
+    })
+}
+
+fn is_active_refresh_token(token: &oauth_refresh_tokens::Model, client: &oauth_clients::Model) -> bool {
+    token.client_id == client.client_id
+        && token.revoked_at.is_none()
+        && token.expires_at > Utc::now().naive_utc()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_introspection_response_shape() {
+        let inactive = serde_json::to_value(IntrospectionResponse::inactive()).unwrap();
+        assert_eq!(inactive, serde_json::json!({ "active": false }));
+
+        let claims = Claims {
+            user_id: "alice".into(),
+            username: "alice".into(),
+            role: Some(UserRoleType::User),
+            exp: 1_900_000_000,
+            client_id: Some("client-1".into()),
+            scope: Some("openid profile".into()),
+            ..Default::default()
+        };
+        let active = serde_json::to_value(IntrospectionResponse::from(claims)).unwrap();
+        assert_eq!(active["active"], true);
+        assert_eq!(active["sub"], "alice");
+        assert_eq!(active["client_id"], "client-1");
+        assert_eq!(active["scope"], "openid profile");
+        assert_eq!(active["token_type"], "Bearer");
+        assert_eq!(active["exp"], 1_900_000_000);
+    }
+}
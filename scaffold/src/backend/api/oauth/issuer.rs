@@ -0,0 +1,23 @@
+use actix_web::HttpRequest;
+use crate::backend::api::oauth::oauth_error::OAuthError;
+use crate::backend::config::qr_link_config;
+
+/// 对外访问的站点根地址
+///
+/// 优先使用 `QR_LOGIN_BASE_URL`，未配置时根据请求的 scheme 与 Host 推断
+pub fn public_base_url(req: &HttpRequest) -> String {
+    qr_link_config().base_url.clone().unwrap_or_else(|| {
+        let info = req.connection_info();
+        format!("{}://{}", info.scheme(), info.host())
+    })
+}
+
+/// 配置的站点根地址（`QR_LOGIN_BASE_URL`）
+///
+/// 下发给用户打开的地址不能取自请求的 Host 头，否则可被伪造的 Host 引导到其他站点；未配置时返回错误
+pub fn configured_base_url() -> Result<&'static str, OAuthError> {
+    qr_link_config()
+        .base_url
+        .as_deref()
+        .ok_or_else(|| OAuthError::server_error("QR_LOGIN_BASE_URL is not configured"))
+}
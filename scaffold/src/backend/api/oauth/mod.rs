@@ -0,0 +1,88 @@
+mod oauth_error;
+mod issuer;
+mod handle_device_authorizations;
+mod device_authorization;
+mod device_approval;
+mod device_page;
+mod token;
+mod scope;
+mod pkce;
+mod redirect_uri;
+mod handle_oauth_clients;
+mod handle_oauth_grants;
+mod client_auth;
+mod client_assertion;
+mod client_credentials;
+mod code_grant;
+mod clients;
+mod consent_page;
+mod authorize;
+mod oidc;
+mod discovery;
+mod userinfo;
+mod handle_revoked_tokens;
+mod introspect;
+mod revoke;
+
+use actix_web::{Scope, web};
+
+pub use code_grant::generate_secret;
+pub use handle_oauth_clients::purge_client_assertions_before;
+pub use handle_oauth_grants::revoke_refresh_tokens_of_user;
+pub use handle_revoked_tokens::{active_claims, is_token_revoked, purge_revoked_tokens_before};
+pub use issuer::public_base_url;
+pub use pkce::s256_challenge;
+pub use scope::{covers as scope_covers, oauth_route_allowed};
+use crate::backend::api::oauth::authorize::{authorize, authorize_decision};
+use crate::backend::api::oauth::clients::{delete_client, list_clients, register_client};
+use crate::backend::api::oauth::device_authorization::device_authorization;
+use crate::backend::api::oauth::device_approval::{approve_device_authorization, get_device_authorization};
+use crate::backend::api::oauth::device_page::device_verification_page;
+use crate::backend::api::oauth::discovery::{jwks, openid_configuration};
+use crate::backend::api::oauth::introspect::introspect;
+use crate::backend::api::oauth::revoke::revoke;
+use crate::backend::api::oauth::token::token;
+use crate::backend::api::oauth::userinfo::userinfo;
+
+/// OAuth 2.0 / OpenID Connect 公开接口（授权、设备授权、token、自省、吊销、userinfo）
+pub fn oauth_scope() -> Scope {
+    web::scope("/oauth")
+        .route("/authorize", web::get().to(authorize))
+        .route("/device_authorization", web::post().to(device_authorization))
+        .route("/token", web::post().to(token))
+        .route("/introspect", web::post().to(introspect))
+        .route("/revoke", web::post().to(revoke))
+        .route("/userinfo", web::get().to(userinfo))
+        .route("/userinfo", web::post().to(userinfo))
+        .route("/jwks", web::get().to(jwks))
+}
+
+/// 设备授权的批准接口（需要认证）
+pub fn device_approval_scope() -> Scope {
+    web::scope("/oauth/device")
+        .route("/approve", web::post().to(approve_device_authorization))
+        .route("/{user_code}", web::get().to(get_device_authorization))
+}
+
+/// 设备验证页面路由 (挂载在根路径，对应 `https://<base_url>/device`)
+pub fn device_verification_route() -> actix_web::Route {
+    web::get().to(device_verification_page)
+}
+
+/// 授权同意页面提交授权决定的路由（需要认证，对应 `POST /v2/oauth/authorize`）
+pub fn authorize_decision_route() -> actix_web::Route {
+    web::post().to(authorize_decision)
+}
+
+/// OAuth 客户端管理接口（需要认证，仅限管理员）
+pub fn oauth_clients_scope() -> Scope {
+    web::scope("/oauth/clients")
+        .route("", web::post().to(register_client))
+        .route("", web::get().to(list_clients))
+        .route("/{client_id}", web::delete().to(delete_client))
+}
+
+/// OpenID Connect 发现文档路由 (挂载在根路径，对应 `https://<base_url>/.well-known/openid-configuration`)
+pub fn openid_configuration_route() -> actix_web::Route {
+    web::get().to(openid_configuration)
+}
@@ -0,0 +1,107 @@
+use actix_web::{http::header, http::StatusCode, HttpResponse};
+use serde::Serialize;
+
+/// OAuth 2.0 标准错误响应（RFC 6749 §5.2）
+///
+/// OAuth 客户端库按 `error` 字段判断错误类型，因此 OAuth 接口不使用项目统一的 `ErrorResponse`。
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct OAuthError {
+    pub error: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_description: Option<String>,
+}
+
+impl OAuthError {
+    pub fn new(error: &'static str, description: impl Into<String>) -> Self {
+        Self { error, error_description: Some(description.into()) }
+    }
+
+    pub fn invalid_request(description: impl Into<String>) -> Self {
+        Self::new("invalid_request", description)
+    }
+
+    pub fn invalid_grant(description: impl Into<String>) -> Self {
+        Self::new("invalid_grant", description)
+    }
+
+    /// 客户端认证失败（RFC 6749 §5.2），返回 401
+    pub fn invalid_client(description: impl Into<String>) -> Self {
+        Self::new("invalid_client", description)
+    }
+
+    /// 客户端已认证，但无权执行该操作
+    pub fn unauthorized_client(description: impl Into<String>) -> Self {
+        Self::new("unauthorized_client", description)
+    }
+
+    pub fn invalid_scope(description: impl Into<String>) -> Self {
+        Self::new("invalid_scope", description)
+    }
+
+    pub fn unsupported_response_type() -> Self {
+        Self::new("unsupported_response_type", "Only response_type=code is supported")
+    }
+
+    pub fn unsupported_grant_type() -> Self {
+        Self::new("unsupported_grant_type", "Unsupported grant_type")
+    }
+
+    pub fn server_error(description: impl Into<String>) -> Self {
+        Self::new("server_error", description)
+    }
+
+    /// 设备授权：用户尚未批准（RFC 8628 §3.5）
+    pub fn authorization_pending() -> Self {
+        Self { error: "authorization_pending", error_description: None }
+    }
+
+    /// 设备授权：轮询过快，客户端需将轮询间隔增加 5 秒
+    pub fn slow_down() -> Self {
+        Self { error: "slow_down", error_description: None }
+    }
+
+    pub fn access_denied(description: impl Into<String>) -> Self {
+        Self::new("access_denied", description)
+    }
+
+    pub fn expired_token() -> Self {
+        Self::new("expired_token", "The device code has expired")
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self.error {
+            "invalid_client" => StatusCode::UNAUTHORIZED,
+            "server_error" => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    pub fn to_response(&self) -> HttpResponse {
+        let mut builder = HttpResponse::build(self.status());
+        builder.insert_header((header::CACHE_CONTROL, "no-store"));
+        if self.error == "invalid_client" {
+            builder.insert_header((header::WWW_AUTHENTICATE, "Basic realm=\"oauth\""));
+        }
+        builder.json(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_shape() {
+        let value = serde_json::to_value(OAuthError::slow_down()).unwrap();
+        assert_eq!(value, serde_json::json!({ "error": "slow_down" }));
+
+        let error = OAuthError::invalid_grant("bad code");
+        assert_eq!(error.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(error.to_response().headers().get(header::CACHE_CONTROL).unwrap(), "no-store");
+        assert_eq!(OAuthError::server_error("db").status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let response = OAuthError::invalid_client("bad secret").to_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(response.headers().contains_key(header::WWW_AUTHENTICATE));
+    }
+}
@@ -0,0 +1,110 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use crate::backend::api::oauth::scope::parse_scopes;
+use crate::backend::config::oauth;
+use crate::backend::models::users;
+
+/// id_token 负载（OpenID Connect Core §2）
+#[derive(Serialize, Debug)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    pub aud: String,
+    pub exp: i64,
+    pub iat: i64,
+    pub auth_time: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    /// 按 scope 筛选的用户声明
+    #[serde(flatten)]
+    pub user_claims: Map<String, Value>,
+}
+
+/// scope 中是否申请了 OpenID Connect
+pub fn is_openid(scope: &str) -> bool {
+    parse_scopes(scope).contains(&oauth::SCOPE_OPENID)
+}
+
+/// 按 scope 返回用户的标准声明（OpenID Connect Core §5.4），不含 `sub`
+pub fn scoped_user_claims(user: &users::Model, scope: &str) -> Map<String, Value> {
+    let scopes = parse_scopes(scope);
+    let mut claims = Map::new();
+
+    if scopes.contains(&oauth::SCOPE_PROFILE) {
+        claims.insert("preferred_username".into(), user.user_id.clone().into());
+        claims.insert("updated_at".into(), user.updated_at.and_utc().timestamp().into());
+    }
+    if scopes.contains(&oauth::SCOPE_EMAIL) {
+        if let Some(email) = &user.email {
+            claims.insert("email".into(), email.clone().into());
+            claims.insert("email_verified".into(), user.is_verified.unwrap_or(false).into());
+        }
+    }
+    if scopes.contains(&oauth::SCOPE_PHONE) {
+        if let Some(phone) = &user.phone {
+            claims.insert("phone_number".into(), phone.clone().into());
+        }
+    }
+
+    claims
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::backend::models::sea_orm_active_enums::UserRoleType;
+
+    fn user() -> users::Model {
+        users::Model {
+            id: 1,
+            user_id: "alice".to_string(),
+            password_hash: String::new(),
+            email: Some("alice@example.com".to_string()),
+            phone: None,
+            role: UserRoleType::User,
+            is_active: Some(true),
+            is_verified: Some(true),
+            created_at: Utc::now().naive_utc(),
+            updated_at: Utc::now().naive_utc(),
+            display_name: None,
+            avatar_url: None,
+            sessions_revoked_at: None,
+        }
+    }
+
+    #[test]
+    fn test_claims_follow_scope() {
+        let claims = scoped_user_claims(&user(), "openid");
+        assert!(claims.is_empty());
+
+        let claims = scoped_user_claims(&user(), "openid email phone");
+        assert_eq!(claims["email"], "alice@example.com");
+        assert_eq!(claims["email_verified"], true);
+        assert!(!claims.contains_key("phone_number"));
+        assert!(!claims.contains_key("preferred_username"));
+
+        let claims = scoped_user_claims(&user(), "openid profile");
+        assert_eq!(claims["preferred_username"], "alice");
+        assert!(!claims.contains_key("email"));
+    }
+
+    #[test]
+    fn test_id_token_shape() {
+        let id_token = IdTokenClaims {
+            iss: "https://auth.example.com".to_string(),
+            sub: "alice".to_string(),
+            aud: "client".to_string(),
+            exp: 2,
+            iat: 1,
+            auth_time: 1,
+            nonce: None,
+            user_claims: scoped_user_claims(&user(), "openid email"),
+        };
+        let value = serde_json::to_value(id_token).unwrap();
+        assert_eq!(value["email"], "alice@example.com");
+        assert!(value.get("nonce").is_none());
+        assert!(is_openid("profile openid"));
+        assert!(!is_openid("profile"));
+    }
+}
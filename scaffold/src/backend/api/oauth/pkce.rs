@@ -0,0 +1,54 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use sha2::{Digest, Sha256};
+use crate::backend::config::oauth;
+
+/// code_verifier 是否符合 RFC 7636 §4.1：43-128 个非保留字符
+pub fn is_valid_verifier(verifier: &str) -> bool {
+    (oauth::PKCE_VERIFIER_MIN_LEN..=oauth::PKCE_VERIFIER_MAX_LEN).contains(&verifier.len())
+        && verifier
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~'))
+}
+
+/// S256 的 code_challenge 为 32 字节哈希的 Base64URL 编码（43 个字符）
+pub fn is_valid_challenge(challenge: &str) -> bool {
+    URL_SAFE_NO_PAD.decode(challenge).is_ok_and(|bytes| bytes.len() == 32)
+}
+
+/// 计算 S256 code_challenge
+pub fn s256_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// 校验 code_verifier 与授权请求中的 code_challenge 是否匹配
+pub fn verify_pkce(verifier: &str, challenge: &str, method: &str) -> bool {
+    method == oauth::PKCE_METHOD_S256
+        && is_valid_verifier(verifier)
+        && s256_challenge(verifier) == challenge
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 7636 Appendix B
+    const VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+    const CHALLENGE: &str = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM";
+
+    #[test]
+    fn test_rfc_example() {
+        assert_eq!(s256_challenge(VERIFIER), CHALLENGE);
+        assert!(is_valid_challenge(CHALLENGE));
+        assert!(verify_pkce(VERIFIER, CHALLENGE, "S256"));
+        assert!(!verify_pkce(VERIFIER, CHALLENGE, "plain"));
+        assert!(!verify_pkce(&VERIFIER.replace('d', "e"), CHALLENGE, "S256"));
+    }
+
+    #[test]
+    fn test_verifier_format() {
+        assert!(!is_valid_verifier("too-short"));
+        assert!(!is_valid_verifier(&"a".repeat(129)));
+        assert!(!is_valid_verifier(&format!("{}!", "a".repeat(50))));
+        assert!(is_valid_verifier(&"a~._-".repeat(10)));
+    }
+}
@@ -0,0 +1,58 @@
+use url::Url;
+
+/// 校验客户端登记的回调地址
+///
+/// - 必须是不含 fragment 的绝对地址
+/// - `http` 只允许本机回环地址（本地开发、原生应用）
+/// - 原生应用可使用反向域名形式的自定义 scheme，如 `com.example.app:/callback`
+pub fn validate_redirect_uri(uri: &str) -> Result<(), String> {
+    let parsed = Url::parse(uri).map_err(|e| format!("Invalid redirect_uri '{}': {}", uri, e))?;
+
+    if parsed.fragment().is_some() {
+        return Err(format!("redirect_uri '{}' must not contain a fragment", uri));
+    }
+
+    match parsed.scheme() {
+        "https" => Ok(()),
+        "http" if matches!(parsed.host_str(), Some("localhost" | "127.0.0.1" | "[::1]")) => Ok(()),
+        "http" => Err(format!("redirect_uri '{}' must use https", uri)),
+        scheme if scheme.contains('.') => Ok(()),
+        _ => Err(format!("redirect_uri '{}' has an unsupported scheme", uri)),
+    }
+}
+
+/// 在回调地址上追加查询参数，保留原有参数
+pub fn append_query(uri: &str, params: &[(&str, &str)]) -> String {
+    match Url::parse(uri) {
+        Ok(mut url) => {
+            url.query_pairs_mut().extend_pairs(params);
+            url.to_string()
+        }
+        Err(_) => uri.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_redirect_uri() {
+        assert!(validate_redirect_uri("https://app.example.com/callback").is_ok());
+        assert!(validate_redirect_uri("http://localhost:3000/callback").is_ok());
+        assert!(validate_redirect_uri("http://127.0.0.1/cb").is_ok());
+        assert!(validate_redirect_uri("com.example.app:/callback").is_ok());
+        assert!(validate_redirect_uri("http://app.example.com/callback").is_err());
+        assert!(validate_redirect_uri("https://app.example.com/cb#frag").is_err());
+        assert!(validate_redirect_uri("javascript:alert(1)").is_err());
+        assert!(validate_redirect_uri("/relative").is_err());
+    }
+
+    #[test]
+    fn test_append_query() {
+        assert_eq!(
+            append_query("https://app.example.com/cb?x=1", &[("code", "a b"), ("state", "s&t")]),
+            "https://app.example.com/cb?x=1&code=a+b&state=s%26t"
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use sea_orm::DatabaseConnection;
+use tracing::{info, warn};
+use crate::backend::AppState;
+use crate::backend::api::oauth::client_auth::authenticate_client;
+use crate::backend::api::oauth::handle_oauth_grants::{find_refresh_token, revoke_refresh_token};
+use crate::backend::api::oauth::handle_revoked_tokens::{active_claims, revoke_access_token, revoke_grant};
+use crate::backend::api::oauth::introspect::TokenOperationRequest;
+use crate::backend::api::oauth::oauth_error::OAuthError;
+use crate::backend::utils::hash::hash_str;
+
+/// token 吊销
+///
+/// 路由: POST /v1/oauth/revoke（application/x-www-form-urlencoded）
+///
+/// 客户端只能吊销签发给自己的 token。access token 吊销后 `Auth` 中间件与自省接口都会拒绝它；
+/// 吊销 refresh token 时，该授权下已签发的 access token 一并失效（RFC 7009 §2.1）；
+/// 无效或已过期的 token 同样返回 200（RFC 7009 §2.2）。
+pub async fn revoke(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    form: web::Form<TokenOperationRequest>,
+) -> HttpResponse {
+    match revoke_token(&req, &state.pg_client, &form).await {
+        Ok(()) => HttpResponse::Ok()
+            .insert_header((header::CACHE_CONTROL, "no-store"))
+            .finish(),
+        Err(e) => e.to_response(),
+    }
+}
+
+async fn revoke_token(
+    req: &HttpRequest,
+    db: &DatabaseConnection,
+    form: &TokenOperationRequest,
+) -> Result<(), OAuthError> {
+    let client = authenticate_client(req, db, form.client_id.as_deref(), form.client_secret.as_deref()).await?;
+
+    let Some(token) = form.token.as_deref() else {
+        return Err(OAuthError::invalid_request("token is required"));
+    };
+
+    let db_error = |e: sea_orm::DbErr| OAuthError::server_error(format!("Database error: {}", e));
+    let not_owned = || {
+        warn!("Client {} tried to revoke a token issued to another client", client.client_id);
+        OAuthError::unauthorized_client("The token was not issued to this client")
+    };
+
+    if let Some(claims) = active_claims(db, token).await.map_err(db_error)? {
+        if claims.client_id.as_deref() != Some(client.client_id.as_str()) {
+            return Err(not_owned());
+        }
+        revoke_access_token(db, token, &claims).await.map_err(db_error)?;
+        info!("🚫 Client {} revoked an access token of user {}", client.client_id, claims.user_id);
+        return Ok(());
+    }
+
+    if let Some(stored) = find_refresh_token(db, &hash_str(token)).await.map_err(db_error)? {
+        if stored.client_id != client.client_id {
+            return Err(not_owned());
+        }
+        if revoke_refresh_token(db, stored.id).await.map_err(db_error)? {
+            info!("🚫 Client {} revoked a refresh token of user {}", client.client_id, stored.user_id);
+        }
+        // 续期得到的 access token 同样属于这次授权，一并失效
+        revoke_grant(db, &client.client_id, &stored.user_id).await.map_err(db_error)?;
+    }
+
+    Ok(())
+}
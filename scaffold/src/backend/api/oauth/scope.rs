@@ -0,0 +1,70 @@
+use crate::backend::config::oauth;
+
+/// 解析空格分隔的 scope，去重并保持原有顺序
+pub fn parse_scopes(scope: &str) -> Vec<&str> {
+    let mut scopes: Vec<&str> = Vec::new();
+    for s in scope.split_whitespace() {
+        if !scopes.contains(&s) {
+            scopes.push(s);
+        }
+    }
+    scopes
+}
+
+/// `granted` 是否包含 `requested` 中的全部 scope
+pub fn covers(granted: &str, requested: &str) -> bool {
+    let granted = parse_scopes(granted);
+    parse_scopes(requested).iter().all(|s| granted.contains(s))
+}
+
+/// OAuth access token 能否访问该接口，见 `oauth::RESOURCE_SCOPES`
+pub fn oauth_route_allowed(scope: &str, method: &str, path: &str) -> bool {
+    let granted = parse_scopes(scope);
+    oauth::RESOURCE_SCOPES
+        .iter()
+        .any(|(m, p, required)| *m == method && *p == path && granted.contains(required))
+}
+
+/// 确定授予的 scope
+///
+/// 申请的 scope 必须都在客户端允许的范围内；未申请时授予客户端允许的全部 scope
+pub fn resolve_scopes(requested: Option<&str>, allowed: &str) -> Result<String, String> {
+    let Some(requested) = requested.filter(|s| !s.trim().is_empty()) else {
+        return Ok(parse_scopes(allowed).join(" "));
+    };
+
+    let allowed_scopes = parse_scopes(allowed);
+    let requested = parse_scopes(requested);
+    if let Some(invalid) = requested.iter().find(|s| !allowed_scopes.contains(s)) {
+        return Err(format!("Scope '{}' is not allowed for this client", invalid));
+    }
+    Ok(requested.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_scopes() {
+        assert_eq!(resolve_scopes(None, "read  write read"), Ok("read write".to_string()));
+        assert_eq!(resolve_scopes(Some("write"), "read write"), Ok("write".to_string()));
+        assert_eq!(resolve_scopes(Some(" "), "read"), Ok("read".to_string()));
+        assert!(resolve_scopes(Some("read admin"), "read write").is_err());
+    }
+
+    #[test]
+    fn test_oauth_route_allowed() {
+        assert!(oauth_route_allowed("openid profile", "GET", "/v2/user/me"));
+        assert!(!oauth_route_allowed("openid email", "GET", "/v2/user/me"));
+        assert!(!oauth_route_allowed("openid profile", "PATCH", "/v2/user/me"));
+        assert!(!oauth_route_allowed("openid profile", "POST", "/v2/api-keys"));
+    }
+
+    #[test]
+    fn test_covers() {
+        assert!(covers("read write", "write"));
+        assert!(covers("read write", ""));
+        assert!(!covers("read", "read write"));
+    }
+}
@@ -0,0 +1,147 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use chrono::{Duration, Utc};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use crate::backend::AppState;
+use crate::backend::api::oauth::client_credentials::exchange_client_credentials;
+use crate::backend::api::oauth::code_grant::{exchange_authorization_code, exchange_refresh_token};
+use crate::backend::api::oauth::handle_device_authorizations::{find_by_device_code_hash, record_poll};
+use crate::backend::api::oauth::oauth_error::OAuthError;
+use crate::backend::api::qr_login::{find_session_by_id, mark_session_consumed};
+use crate::backend::config::{device_grant, oauth};
+use crate::backend::utils::hash::hash_str;
+use crate::backend::utils::jwt::verify_jwt;
+
+#[derive(Deserialize, Debug)]
+pub struct TokenRequest {
+    pub grant_type: String,
+    pub client_id: Option<String>,
+    /// 机密客户端也可以通过 HTTP Basic 传递凭证
+    pub client_secret: Option<String>,
+    /// 使用 JWT 断言认证客户端（RFC 7523）
+    pub client_assertion_type: Option<String>,
+    pub client_assertion: Option<String>,
+    /// `urn:ietf:params:oauth:grant-type:device_code`
+    pub device_code: Option<String>,
+    /// `authorization_code`
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+    /// `refresh_token`
+    pub refresh_token: Option<String>,
+    pub scope: Option<String>,
+}
+
+/// token 接口的成功响应（RFC 6749 §5.1）
+#[derive(Serialize, Debug)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: &'static str,
+    pub expires_in: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// OpenID Connect：scope 包含 `openid` 时返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+}
+
+/// OAuth token 接口
+///
+/// 路由: POST /v1/oauth/token（application/x-www-form-urlencoded）
+///
+/// 按 `grant_type` 分发，支持授权码（要求 PKCE）、refresh token、客户端凭证（服务账号）
+/// 与设备授权（RFC 8628）。
+pub async fn token(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    form: web::Form<TokenRequest>,
+) -> HttpResponse {
+    let result = match form.grant_type.as_str() {
+        oauth::GRANT_AUTHORIZATION_CODE => exchange_authorization_code(&req, &state.pg_client, &form).await,
+        oauth::GRANT_REFRESH_TOKEN => exchange_refresh_token(&req, &state.pg_client, &form).await,
+        oauth::GRANT_CLIENT_CREDENTIALS => exchange_client_credentials(&req, &state.pg_client, &form).await,
+        device_grant::GRANT_TYPE => exchange_device_code(&state.pg_client, &form).await,
+        _ => Err(OAuthError::unsupported_grant_type()),
+    };
+
+    match result {
+        Ok(token) => HttpResponse::Ok()
+            .insert_header((header::CACHE_CONTROL, "no-store"))
+            .json(token),
+        Err(e) => e.to_response(),
+    }
+}
+
+/// 设备轮询换取 token（RFC 8628 §3.4）
+async fn exchange_device_code(
+    db: &DatabaseConnection,
+    form: &TokenRequest,
+) -> Result<TokenResponse, OAuthError> {
+    let (Some(client_id), Some(device_code)) = (form.client_id.as_deref(), form.device_code.as_deref()) else {
+        return Err(OAuthError::invalid_request("client_id and device_code are required"));
+    };
+
+    let authorization = find_by_device_code_hash(db, &hash_str(device_code))
+        .await
+        .map_err(|e| OAuthError::server_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| OAuthError::invalid_grant("Invalid device_code"))?;
+
+    if authorization.client_id != client_id {
+        return Err(OAuthError::invalid_grant("device_code was issued to another client"));
+    }
+
+    let session = find_session_by_id(db, &authorization.session_id)
+        .await
+        .map_err(|e| OAuthError::server_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| OAuthError::invalid_grant("Invalid device_code"))?;
+
+    // 轮询过快：返回 slow_down，并将之后的最小间隔增加 5 秒
+    let now = Utc::now().naive_utc();
+    let too_fast = authorization
+        .last_polled_at
+        .is_some_and(|last| now - last < Duration::seconds(authorization.interval_seconds as i64));
+    let interval = if too_fast {
+        authorization.interval_seconds + device_grant::SLOW_DOWN_INCREMENT_SECONDS
+    } else {
+        authorization.interval_seconds
+    };
+    record_poll(db, authorization.id, interval)
+        .await
+        .map_err(|e| OAuthError::server_error(format!("Database error: {}", e)))?;
+    if too_fast {
+        return Err(OAuthError::slow_down());
+    }
+
+    match session.status.as_str() {
+        "confirmed" => {
+            // 条件更新保证 device_code 只能换取一次 token
+            let consumed = mark_session_consumed(db, &session.session_id)
+                .await
+                .map_err(|e| OAuthError::server_error(format!("Database error: {}", e)))?;
+            let access_token = session.web_token.filter(|_| consumed)
+                .ok_or_else(|| OAuthError::invalid_grant("device_code has already been used"))?;
+
+            let expires_in = verify_jwt(&access_token)
+                .map(|data| data.claims.exp as i64 - Utc::now().timestamp())
+                .map_err(|_| OAuthError::server_error("Issued token is invalid"))?;
+
+            info!("✅ Device authorization {} exchanged for token", session.session_id);
+            Ok(TokenResponse {
+                access_token,
+                token_type: "Bearer",
+                expires_in,
+                scope: authorization.scope,
+                refresh_token: None,
+                id_token: None,
+            })
+        }
+        "rejected" => Err(OAuthError::access_denied("The user denied the authorization request")),
+        "consumed" => Err(OAuthError::invalid_grant("device_code has already been used")),
+        "expired" => Err(OAuthError::expired_token()),
+        _ if session.expires_at < now => Err(OAuthError::expired_token()),
+        _ => Err(OAuthError::authorization_pending()),
+    }
+}
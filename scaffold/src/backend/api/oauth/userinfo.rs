@@ -0,0 +1,64 @@
+use actix_web::{http::header, http::StatusCode, web, HttpRequest, HttpResponse};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde_json::Value;
+use tracing::warn;
+use crate::backend::AppState;
+use crate::backend::api::oauth::handle_revoked_tokens::active_claims;
+use crate::backend::api::oauth::oauth_error::OAuthError;
+use crate::backend::api::oauth::oidc::{is_openid, scoped_user_claims};
+use crate::backend::models::users;
+use crate::backend::utils::extractors::extract_token_from_request;
+
+/// Bearer token 错误（RFC 6750 §3），错误信息放在 `WWW-Authenticate` 头中
+fn bearer_error(status: StatusCode, error: &'static str, description: &str) -> HttpResponse {
+    HttpResponse::build(status)
+        .insert_header((
+            header::WWW_AUTHENTICATE,
+            format!("Bearer error=\"{}\", error_description=\"{}\"", error, description),
+        ))
+        .json(OAuthError::new(error, description))
+}
+
+/// OpenID Connect 用户信息
+///
+/// 路由: GET/POST /v1/oauth/userinfo
+///
+/// 需要携带通过 OAuth 签发、且 scope 包含 `openid` 的 access token，
+/// 按 token 的 scope 返回 `users` 表中的用户声明。
+pub async fn userinfo(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
+    let Ok(token) = extract_token_from_request(&req) else {
+        return bearer_error(StatusCode::UNAUTHORIZED, "invalid_token", "Missing access token");
+    };
+    let claims = match active_claims(&state.pg_client, &token).await {
+        Ok(Some(claims)) => claims,
+        Ok(None) => {
+            return bearer_error(StatusCode::UNAUTHORIZED, "invalid_token", "Invalid, expired or revoked access token");
+        }
+        Err(e) => return OAuthError::server_error(format!("Database error: {}", e)).to_response(),
+    };
+
+    let scope = claims.scope.as_deref().unwrap_or_default();
+    if claims.client_id.is_none() || !is_openid(scope) {
+        return bearer_error(StatusCode::FORBIDDEN, "insufficient_scope", "The openid scope is required");
+    }
+
+    let user = match users::Entity::find()
+        .filter(users::Column::UserId.eq(&claims.user_id))
+        .one(&state.pg_client)
+        .await
+    {
+        Ok(Some(u)) if u.is_active != Some(false) => u,
+        Ok(_) => {
+            warn!("Userinfo requested for inactive or unknown user {}", claims.user_id);
+            return bearer_error(StatusCode::UNAUTHORIZED, "invalid_token", "User account is disabled");
+        }
+        Err(e) => return OAuthError::server_error(format!("Database error: {}", e)).to_response(),
+    };
+
+    let mut body = scoped_user_claims(&user, scope);
+    body.insert("sub".into(), Value::String(user.user_id));
+
+    HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .json(body)
+}
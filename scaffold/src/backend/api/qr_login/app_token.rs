@@ -0,0 +1,31 @@
+use actix_web::HttpResponse;
+use sea_orm::DatabaseConnection;
+use crate::backend::api::oauth::active_claims;
+use crate::backend::errors::{ErrorCode, error_response};
+use crate::backend::utils::jwt::Claims;
+
+/// 校验 App 端提交的 token：签名、有效期与吊销状态，且必须是用户本人的登录会话
+///
+/// 扫码、确认与拒绝登录都代表用户本人操作，API Key、OAuth 客户端与服务账号的 token 一律拒绝。
+pub async fn verify_app_token(db: &DatabaseConnection, token: &str) -> Result<Claims, HttpResponse> {
+    let claims = match active_claims(db, token).await {
+        Ok(Some(claims)) => claims,
+        Ok(None) => {
+            let error_resp = error_response(ErrorCode::TokenInvalid, "Invalid, expired or revoked app token");
+            return Err(HttpResponse::Unauthorized().json(error_resp));
+        }
+        Err(e) => {
+            let error_resp = error_response(ErrorCode::DatabaseError, format!("Database error: {}", e));
+            return Err(HttpResponse::InternalServerError().json(error_resp));
+        }
+    };
+
+    if !claims.is_first_party() {
+        let error_resp = error_response(
+            ErrorCode::PermissionDenied,
+            "QR login requires a user login session",
+        );
+        return Err(HttpResponse::Forbidden().json(error_resp));
+    }
+    Ok(claims)
+}
@@ -1,17 +1,64 @@
 use actix_web::{web, HttpResponse};
 use chrono::Utc;
+use serde::Deserialize;
+use std::time::Duration;
 use tracing::info;
 use serde_json::json;
 use crate::backend::AppState;
 use crate::backend::api::qr_login::handle_qr_session::find_session_by_id;
+use crate::backend::config::websocket;
 use crate::backend::errors::{ErrorCode, error_response};
+use crate::backend::ws_backplane::events_after;
+use crate::backend::ws_manager::WsManager;
 
+#[derive(Deserialize, Debug)]
+pub struct CheckStatusQuery {
+    /// 长轮询等待时间（秒），最大 `websocket::LONG_POLL_MAX_WAIT_SECONDS`；不传则立即返回
+    pub wait: Option<u64>,
+    /// 客户端最后收到的事件 ID；已有更新的事件时立即返回
+    pub last_event_id: Option<i64>,
+}
+
+/// 查询扫码登录状态
+///
+/// 路由: GET /v1/qr-login/status/{session_id}[?wait=25&last_event_id=N]
+///
+/// 带 `wait` 时为长轮询：会话状态发生变化（收到新的推送事件）或超时后才返回，
+/// 与 WebSocket / SSE 共用 `WsManager` 的推送通道。响应中的 `last_event_id` 用于下一次轮询。
 pub async fn check_login_status(
     state: web::Data<AppState>,
+    ws_manager: web::Data<WsManager>,
     session_id: web::Path<String>,
+    query: web::Query<CheckStatusQuery>,
 ) -> HttpResponse {
     info!("Checking login status for session: {}", session_id);
-    
+
+    let mut last_event_id = query.last_event_id;
+
+    if let Some(wait) = query.wait.filter(|w| *w > 0) {
+        let wait = wait.min(websocket::LONG_POLL_MAX_WAIT_SECONDS);
+
+        // 先订阅再检查，避免漏掉期间的推送
+        if let Some(mut subscription) = ws_manager.subscribe(&session_id).await {
+            let missed = match events_after(&state.pg_client, &session_id, last_event_id).await {
+                Ok(events) => events.last().map(|(id, _)| *id),
+                Err(_) => None,
+            };
+
+            match missed {
+                Some(id) => last_event_id = Some(id),
+                None if !is_finished(&state, &session_id).await => {
+                    if let Ok(Some(event)) =
+                        actix_web::rt::time::timeout(Duration::from_secs(wait), subscription.recv()).await
+                    {
+                        last_event_id = event.event_id.or(last_event_id);
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+
     // 查找会话
     let session = match find_session_by_id(&state.pg_client, &session_id).await {
         Ok(Some(s)) => s,
@@ -37,7 +84,8 @@ pub async fn check_login_status(
         return HttpResponse::Ok().json(json!({
             "status": "expired",
             "web_token": null,
-            "message": "QR code expired"
+            "message": "QR code expired",
+            "last_event_id": last_event_id
         }));
     }
 
@@ -47,14 +95,16 @@ pub async fn check_login_status(
             HttpResponse::Ok().json(json!({
                 "status": "pending",
                 "web_token": null,
-                "message": "Waiting for scan"
+                "message": "Waiting for scan",
+                "last_event_id": last_event_id
             }))
         }
         "scanned" => {
             HttpResponse::Ok().json(json!({
                 "status": "scanned",
                 "web_token": null,
-                "message": "Scanned, waiting for confirmation"
+                "message": "Scanned, waiting for confirmation",
+                "last_event_id": last_event_id
             }))
         }
         "confirmed" => {
@@ -62,22 +112,36 @@ pub async fn check_login_status(
             HttpResponse::Ok().json(json!({
                 "status": "confirmed",
                 "web_token": web_token,
-                "message": "Login successful"
+                "message": "Login successful",
+                "last_event_id": last_event_id
             }))
         }
         "rejected" => {
             HttpResponse::Ok().json(json!({
                 "status": "rejected",
                 "web_token": null,
-                "message": "Login rejected by user"
+                "message": "Login rejected by user",
+                "last_event_id": last_event_id
             }))
         }
         _ => {
             HttpResponse::Ok().json(json!({
                 "status": "expired",
                 "web_token": null,
-                "message": "QR code expired"
+                "message": "QR code expired",
+                "last_event_id": last_event_id
             }))
         }
     }
 }
+
+/// 会话是否已结束（无需再等待）
+async fn is_finished(state: &AppState, session_id: &str) -> bool {
+    match find_session_by_id(&state.pg_client, session_id).await {
+        Ok(Some(session)) => {
+            (session.status != "pending" && session.status != "scanned")
+                || session.expires_at < Utc::now().naive_utc()
+        }
+        _ => true,
+    }
+}
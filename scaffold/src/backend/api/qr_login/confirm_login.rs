@@ -1,55 +1,132 @@
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
 use serde::Deserialize;
 use chrono::Utc;
 use sea_orm::{EntityTrait, QueryFilter, ColumnTrait, ActiveModelTrait, Set};
+use rand::{thread_rng, Rng};
+use rand::distributions::Alphanumeric;
 use tracing::{info, warn};
 use crate::backend::AppState;
-use crate::backend::api::qr_login::handle_qr_session::{find_session_by_id, update_session_confirmed};
+use crate::backend::api::admin::is_active_admin;
+use crate::backend::api::device::{verify_device_signature, DeviceSignature};
+use crate::backend::api::logs::handle_user_logs::insert_user_log;
+use crate::backend::api::qr_login::app_token::verify_app_token;
+use crate::backend::api::qr_login::handle_qr_session::{can_respond, find_session_by_id, update_session_confirmed, update_session_status};
+use crate::backend::api::qr_login::login_context::summarize_user_agent;
+use crate::backend::config::{device_key, jwt, qr_code, qr_login_policy, QrLoginPolicy};
 use crate::backend::models::users;
-use crate::backend::utils::jwt::{Claims, create_jwt, verify_jwt};
+use crate::backend::utils::extractors::{client_ip, user_agent};
+use crate::backend::utils::hash::hash_password;
+use crate::backend::utils::jwt::{Claims, create_jwt};
 use crate::backend::ws_manager::WsManager;
+use crate::backend::ws_protocol::{UserEvent, WsEvent};
 use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
-use bcrypt::{hash, DEFAULT_COST};
-use crate::backend::models::sea_orm_active_enums::UserRoleType;
+use crate::backend::models::sea_orm_active_enums::{LogActionType, UserRoleType};
 
 #[derive(Deserialize, Debug)]
 pub struct ConfirmLoginRequest {
     pub session_id: String,
     pub app_token: String,
+    /// 以指定用户身份登录（仅管理员，且需开启 `allow_admin_impersonation` 策略）
+    #[serde(default)]
+    pub target_user_id: Option<String>,
+    /// 用户在 App 端选择的数字，需与 Web 端页面上显示的数字一致
+    #[serde(default)]
+    pub match_number: Option<i16>,
+    /// 已注册设备对本次确认的签名，防止 app_token 被截获后重放
+    #[serde(default)]
+    pub device_signature: Option<DeviceSignature>,
+}
+
+/// 确定 Web 端最终登录的用户
+///
+/// 默认登录为 App 端已认证的用户本人；只有在策略允许时，管理员才可以指定其他用户。
+/// `is_admin` 须以数据库中的当前角色为准，不能取自 token。
+/// 返回 `(user_id, 是否为代登录)`
+fn resolve_target_user(
+    app_user_id: &str,
+    is_admin: bool,
+    target_user_id: Option<&str>,
+    policy: &QrLoginPolicy,
+) -> Result<(String, bool), (ErrorCode, &'static str)> {
+    let target = match target_user_id {
+        Some(t) if t != app_user_id => t,
+        _ => return Ok((app_user_id.to_string(), false)),
+    };
+
+    if !policy.allow_admin_impersonation {
+        return Err((ErrorCode::PermissionDenied, "Logging in as another user is disabled"));
+    }
+
+    if !is_admin {
+        return Err((ErrorCode::PermissionDenied, "Admin permission required to log in as another user"));
+    }
+
+    Ok((target.to_string(), true))
+}
+
+/// 生成一个不可用于登录的随机密码（扫码注册的用户需自行设置密码）
+fn random_password() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
 }
 
 pub async fn confirm_login(
+    req: HttpRequest,
     state: web::Data<AppState>,
     ws_manager: web::Data<WsManager>,
     request: web::Json<ConfirmLoginRequest>,
 ) -> HttpResponse {
     info!("Received confirm login request for session: {}", request.session_id);
 
-    // 1. 验证App端token并检查admin权限
-    let admin_claims = match verify_jwt(&request.app_token) {
-        Ok(token_data) => token_data.claims,
-        Err(e) => {
-            let error_resp = error_response(
-                ErrorCode::TokenInvalid,
-                format!("Invalid app token: {}", e),
+    // 1. 验证App端token
+    let app_claims = match verify_app_token(&state.pg_client, &request.app_token).await {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    // 2. 确定登录用户（默认为App端用户本人）；代登录时以数据库中的角色判断是否为管理员
+    let policy = qr_login_policy();
+    let impersonating = request.target_user_id.as_deref().is_some_and(|t| t != app_claims.user_id);
+    let is_admin = if impersonating {
+        match is_active_admin(&state.pg_client, &app_claims.user_id).await {
+            Ok(is_admin) => is_admin,
+            Err(e) => {
+                let error_resp = error_response(
+                    ErrorCode::DatabaseError,
+                    format!("Database error: {}", e),
+                );
+                return HttpResponse::InternalServerError().json(error_resp);
+            }
+        }
+    } else {
+        false
+    };
+    let (user_id, impersonated) = match resolve_target_user(
+        &app_claims.user_id,
+        is_admin,
+        request.target_user_id.as_deref(),
+        policy,
+    ) {
+        Ok(resolved) => resolved,
+        Err((code, msg)) => {
+            warn!(
+                "User {} was denied confirming QR login for {:?}: {}",
+                app_claims.user_id, request.target_user_id, msg
             );
-            return HttpResponse::Unauthorized().json(error_resp);
+            return HttpResponse::Forbidden().json(error_response(code, msg));
         }
     };
 
-    // 验证是否为admin权限
-    let is_admin = admin_claims.role.as_ref().map_or(false, |r| r == &UserRoleType::Admin);
-    if !is_admin {
-        let error_resp = error_response(
-            ErrorCode::PermissionDenied,
-            "Admin permission required to confirm QR login",
-        );
-        return HttpResponse::Forbidden().json(error_resp);
+    if impersonated {
+        warn!("Admin {} is confirming QR login as user {}", app_claims.user_id, user_id);
+    } else {
+        info!("User {} is confirming QR login", user_id);
     }
 
-    info!("Admin user {} is confirming QR login", admin_claims.user_id);
-
-    // 2. 查找会话
+    // 3. 查找会话
     let session = match find_session_by_id(&state.pg_client, &request.session_id).await {
         Ok(Some(s)) => s,
         Ok(None) => {
@@ -68,7 +145,20 @@ pub async fn confirm_login(
         }
     };
 
-    // 3. 检查会话状态
+    // 推送审批只能由目标用户本人批准，不允许代登录
+    if !can_respond(&session, &app_claims.user_id) || (impersonated && session.login_method == qr_code::LOGIN_METHOD_PUSH) {
+        warn!(
+            "User {} tried to confirm push login session {} of another user",
+            app_claims.user_id, session.session_id
+        );
+        let error_resp = error_response(
+            ErrorCode::PermissionDenied,
+            "This login request belongs to another user",
+        );
+        return HttpResponse::Forbidden().json(error_resp);
+    }
+
+    // 4. 检查会话状态
     if session.status != "pending" && session.status != "scanned" {
         let error_resp = error_response(
             ErrorCode::ResourceConflict,
@@ -77,7 +167,7 @@ pub async fn confirm_login(
         return HttpResponse::BadRequest().json(error_resp);
     }
 
-    // 4. 检查是否过期
+    // 5. 检查是否过期
     let now = Utc::now().naive_utc();
     if session.expires_at < now {
         let error_resp = error_response(
@@ -87,33 +177,82 @@ pub async fn confirm_login(
         return HttpResponse::BadRequest().json(error_resp);
     }
 
-    // 5. 从二维码数据中提取或生成用户ID
-    // 二维码数据格式: {"session_id":"xxx","action":"login","user_id":"xxx"}（可选）
-    // 如果没有user_id，则基于session_id生成
-    let user_id = session.user_id.clone().unwrap_or_else(|| {
-        // 使用session_id作为基础生成user_id
-        format!("qr_user_{}", &session.session_id[0..8])
-    });
+    // 5.1 设备签名校验
+    if let Err(e) = verify_device_signature(
+        &state.pg_client,
+        &app_claims.user_id,
+        device_key::ACTION_CONFIRM,
+        &session.session_id,
+        request.device_signature.as_ref(),
+        policy,
+    ).await {
+        warn!("Device signature check failed for session {}: {}", session.session_id, e.message());
+        return e.error_response();
+    }
+
+    // 6. 数字匹配校验，选错则直接拒绝本次登录
+    if let Some(expected) = session.match_number {
+        let Some(selected) = request.match_number else {
+            let error_resp = error_response(
+                ErrorCode::MissingRequiredField,
+                "match_number is required",
+            );
+            return HttpResponse::BadRequest().json(error_resp);
+        };
+
+        if selected != expected {
+            warn!(
+                "QR login number mismatch for session {} by user {}: selected {}",
+                request.session_id, app_claims.user_id, selected
+            );
 
-    info!("Target user_id for login: {}", user_id);
+            match update_session_status(&state.pg_client, &session.session_id, "rejected").await {
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    let error_resp = error_response(ErrorCode::ResourceConflict, "Session is no longer pending");
+                    return HttpResponse::Conflict().json(error_resp);
+                }
+                Err(e) => {
+                    let error_resp = error_response(
+                        ErrorCode::DatabaseError,
+                        format!("Failed to update session: {}", e),
+                    );
+                    return HttpResponse::InternalServerError().json(error_resp);
+                }
+            }
 
-    // 6. 查找或创建用户（扫码即注册）
+            if let Err(e) = insert_user_log(
+                &state.pg_client,
+                &app_claims.user_id,
+                LogActionType::QrLoginMismatch,
+                &client_ip(&req),
+                user_agent(&req).as_deref(),
+            ).await {
+                warn!("Failed to record QR login mismatch for {}: {}", app_claims.user_id, e);
+            }
+
+            ws_manager.notify(&request.session_id, WsEvent::rejected("Verification number mismatch")).await;
+
+            let error_resp = error_response(
+                ErrorCode::QRCodeMatchMismatch,
+                ErrorCode::QRCodeMatchMismatch.default_message(),
+            );
+            return HttpResponse::BadRequest().json(error_resp);
+        }
+    }
+
+    // 7. 查找用户；仅当App端用户本人不存在且开启扫码注册时才自动创建
+    let mut auto_registered = false;
     let user = match users::Entity::find()
         .filter(users::Column::UserId.eq(&user_id))
         .one(&state.pg_client)
         .await
     {
-        Ok(Some(u)) => {
-            info!("User {} exists, proceeding with login", user_id);
-            u
-        }
-        Ok(None) => {
-            // 用户不存在，自动创建（扫码即注册）
+        Ok(Some(u)) => u,
+        Ok(None) if !impersonated && policy.scan_to_register => {
             info!("User {} does not exist, creating new user (scan-to-register)", user_id);
 
-            // 生成默认密码：用户名@123456
-            let default_password = format!("{}@123456", user_id);
-            let password_hash = match hash(&default_password, DEFAULT_COST) {
+            let password_hash = match hash_password(&random_password()) {
                 Ok(hash) => hash,
                 Err(e) => {
                     let error_resp = error_response(
@@ -124,22 +263,23 @@ pub async fn confirm_login(
                 }
             };
 
-            // 创建新用户
             let new_user = users::ActiveModel {
                 user_id: Set(user_id.clone()),
                 password_hash: Set(password_hash),
-                email: Set(Some(format!("{}@qr-login.local", user_id))),
                 role: Set(UserRoleType::User),
                 is_active: Set(Some(true)),
                 is_verified: Set(Some(false)),
-                created_at: Set(Utc::now().naive_utc()),
-                updated_at: Set(Utc::now().naive_utc()),
+                // 同一 user_id 可能属于已删除的账户，创建之前签发的 token 一律无效
+                sessions_revoked_at: Set(Some(now)),
+                created_at: Set(now),
+                updated_at: Set(now),
                 ..Default::default()
             };
 
             match new_user.insert(&state.pg_client).await {
                 Ok(user) => {
                     info!("✅ New user created successfully: {}", user_id);
+                    auto_registered = true;
                     user
                 }
                 Err(e) => {
@@ -151,6 +291,13 @@ pub async fn confirm_login(
                 }
             }
         }
+        Ok(None) => {
+            let error_resp = error_response(
+                ErrorCode::NotFound,
+                format!("User {} not found", user_id),
+            );
+            return HttpResponse::NotFound().json(error_resp);
+        }
         Err(e) => {
             let error_resp = error_response(
                 ErrorCode::DatabaseError,
@@ -160,50 +307,104 @@ pub async fn confirm_login(
         }
     };
 
-    // 7. 生成Web端JWT token
+    if user.is_active == Some(false) {
+        let error_resp = error_response(
+            ErrorCode::PermissionDenied,
+            "User account is disabled",
+        );
+        return HttpResponse::Forbidden().json(error_resp);
+    }
+
+    // 8. 生成Web端JWT token
     let web_claims = Claims {
         user_id: user.user_id.clone(),
         username: user.user_id.clone(),
         role: Some(user.role.clone()),
-        exp: (Utc::now().timestamp() as usize + 60 * 60 * 24), // 24小时
+        exp: Utc::now().timestamp() as usize + jwt::DEFAULT_EXPIRATION_SECONDS,
+        ..Default::default()
     };
     let web_token = create_jwt(&web_claims);
 
-    // 8. 更新会话状态
-    if let Err(e) = update_session_confirmed(
+    // 9. 更新会话状态
+    let confirmed_session = match update_session_confirmed(
         &state.pg_client,
         &request.session_id,
         &user.user_id,
         &web_token,
         &request.app_token,
     ).await {
-        let error_resp = error_response(
-            ErrorCode::DatabaseError,
-            format!("Failed to update session: {}", e),
-        );
-        return HttpResponse::InternalServerError().json(error_resp);
-    }
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            warn!("QR login session {} was no longer pending when confirmed", request.session_id);
+            let error_resp = error_response(ErrorCode::ResourceConflict, "Session is no longer pending");
+            return HttpResponse::Conflict().json(error_resp);
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Failed to update session: {}", e),
+            );
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    };
 
-    // 9. 🔔 通过WebSocket推送状态更新
-    ws_manager.notify_status(&request.session_id, "confirmed", Some(&web_token)).await;
+    // 10. 🔔 通过WebSocket推送状态更新（不含 token，Web 端收到后经状态接口获取）
+    ws_manager.notify(&request.session_id, WsEvent::confirmed()).await;
     info!("✅ Login confirmed and WebSocket notified for session: {}", request.session_id);
     info!("✅ User {} logged in via QR code scan", user.user_id);
 
+    // 11. 提醒该用户已登录的其他设备
+    let method = if confirmed_session.login_method == qr_code::LOGIN_METHOD_PUSH { "push" } else { "qr_code" };
+    let browser = confirmed_session.user_agent.as_deref().map(summarize_user_agent);
+    ws_manager.notify_user(
+        &user.user_id,
+        UserEvent::new_login(method, confirmed_session.client_ip.as_deref(), browser.as_deref()),
+    ).await;
+
     #[derive(serde::Serialize)]
     struct ConfirmResponse {
         success: bool,
         message: String,
         user_id: String,
         auto_registered: bool,
+        impersonated: bool,
     }
 
-    // 判断是否为自动注册的用户
-    let was_auto_registered = session.user_id.is_none();
-
     HttpResponse::Ok().json(SuccessResponse::new(ConfirmResponse {
         success: true,
         message: "Login confirmed successfully".to_string(),
         user_id: user.user_id.clone(),
-        auto_registered: was_auto_registered,
+        auto_registered,
+        impersonated,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_defaults_to_app_user() {
+        let policy = QrLoginPolicy::default();
+
+        assert_eq!(resolve_target_user("alice", false, None, &policy), Ok(("alice".to_string(), false)));
+        assert_eq!(resolve_target_user("alice", false, Some("alice"), &policy), Ok(("alice".to_string(), false)));
+    }
+
+    #[test]
+    fn test_resolve_impersonation_requires_policy() {
+        let policy = QrLoginPolicy::default();
+        let result = resolve_target_user("root", true, Some("alice"), &policy);
+        assert_eq!(result.unwrap_err().0, ErrorCode::PermissionDenied);
+    }
+
+    #[test]
+    fn test_resolve_impersonation_requires_admin() {
+        let policy = QrLoginPolicy { allow_admin_impersonation: true, ..Default::default() };
+
+        let result = resolve_target_user("bob", false, Some("alice"), &policy);
+        assert_eq!(result.unwrap_err().0, ErrorCode::PermissionDenied);
+
+        assert_eq!(resolve_target_user("root", true, Some("alice"), &policy), Ok(("alice".to_string(), true)));
+    }
+}
@@ -1,99 +1,106 @@
 use crate::backend::api::qr_login::handle_qr_session::insert_qr_session;
+use crate::backend::api::qr_login::login_context::LoginContext;
+use crate::backend::api::qr_login::qr_payload::QrPayload;
+use crate::backend::api::qr_login::qr_render::{render_qr, QrRenderOptions, QrRenderParams};
 use crate::backend::AppState;
-use crate::backend::config::qr_code;
-use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
-use actix_web::{web, HttpResponse};
-use base64::{engine::general_purpose, Engine as _};
-use image::Luma;
-use qrcode::QrCode;
+use crate::backend::config::{qr_code, qr_link_config};
+use crate::backend::errors::{AppError, ErrorCode, error_response, SuccessResponse};
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
 use serde::Deserialize;
 use tracing::info;
 use uuid::Uuid;
 
 #[derive(Deserialize, Debug)]
 pub struct GenerateQrRequest {
-    #[allow(dead_code)]
+    /// Web 端客户端信息，App 扫码后展示给用户核对
     pub client_info: Option<String>,
+    /// 图片渲染参数（尺寸、容错级别、留白、颜色、格式、Logo）
+    #[serde(flatten)]
+    pub render: QrRenderParams,
 }
 
-// 生成二维码图片并返回base64编码
-fn generate_qr_image(data: &str) -> Result<String, String> {
-    // 生成二维码
-    let code =
-        QrCode::new(data.as_bytes()).map_err(|e| format!("Failed to generate QR code: {}", e))?;
-
-    // 渲染为图像 - 使用配置的尺寸
-    let image = code
-        .render::<Luma<u8>>()
-        .min_dimensions(qr_code::MIN_IMAGE_SIZE, qr_code::MIN_IMAGE_SIZE)
-        .max_dimensions(qr_code::MAX_IMAGE_SIZE, qr_code::MAX_IMAGE_SIZE)
-        .build();
-
-    // 转换为PNG字节
-    let mut png_bytes: Vec<u8> = Vec::new();
-    image::DynamicImage::ImageLuma8(image)
-        .write_to(
-            &mut std::io::Cursor::new(&mut png_bytes),
-            image::ImageFormat::Png,
-        )
-        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
-
-    // Base64编码
-    let base64_image = general_purpose::STANDARD.encode(&png_bytes);
-    Ok(format!("data:image/png;base64,{}", base64_image))
+// 生成二维码图片，按请求的格式返回 data URI；url 格式不生成图片
+fn generate_qr_image(data: &str, options: &QrRenderOptions) -> Result<Option<String>, AppError> {
+    Ok(render_qr(data, options)?.map(|rendered| rendered.to_data_uri()))
 }
 
 pub async fn generate_qr_code(
+    req: HttpRequest,
     state: web::Data<AppState>,
     request: web::Json<GenerateQrRequest>,
 ) -> HttpResponse {
     info!("Received generate QR code request: {:?}", request);
 
+    // 校验图片渲染参数
+    let render_options = match request.render.to_options() {
+        Ok(options) => options,
+        Err(e) => {
+            let error_resp = error_response(ErrorCode::InvalidParams, e);
+            return HttpResponse::BadRequest().json(error_resp);
+        }
+    };
+
     let session_id = Uuid::new_v4().to_string();
     let ttl_seconds = qr_code::TTL_SECONDS as i64; // 转换为 i64 类型
 
-    // 创建登录会话
-    if let Err(e) = insert_qr_session(&state.pg_client, &session_id, ttl_seconds).await {
-        let error_resp = error_response(
-            ErrorCode::DatabaseError,
-            format!("Failed to create QR session: {}", e),
-        );
-        return HttpResponse::InternalServerError().json(error_resp);
-    }
+    // 记录发起登录的Web端上下文，并生成数字匹配用的两位数字
+    let context = LoginContext::from_request(&req, request.client_info.as_deref());
 
-    // 构造二维码数据
-    let qr_data = format!(
-        r#"{{"session_id":"{}","action":"login","expires_at":{}}}"#,
-        session_id,
-        chrono::Utc::now().timestamp() + ttl_seconds
-    );
+    // 创建登录会话
+    let session = match insert_qr_session(&state.pg_client, &session_id, ttl_seconds, &context).await {
+        Ok(session) => session,
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Failed to create QR session: {}", e),
+            );
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    };
 
-    // 生成二维码图片
-    let qr_image = match generate_qr_image(&qr_data) {
-        Ok(img) => img,
+    // 构造带签名的二维码数据
+    let expires_at = session.expires_at.and_utc().timestamp();
+    let qr_data = match QrPayload::login(&session_id, expires_at).encode(qr_link_config()) {
+        Ok(data) => data,
         Err(e) => {
             let error_resp = error_response(
                 ErrorCode::InternalError,
-                format!("Failed to generate QR image: {}", e),
+                format!("Failed to sign QR payload: {}", e.message()),
             );
             return HttpResponse::InternalServerError().json(error_resp);
         }
     };
 
+    // 生成二维码图片
+    let qr_image = match generate_qr_image(&qr_data, &render_options) {
+        Ok(img) => img,
+        // 尺寸过小等参数错误返回 400，其余为 500
+        Err(e) => return e.error_response(),
+    };
+
     // 返回响应（包含base64图片）
     #[derive(serde::Serialize)]
     struct QrResponse {
         session_id: String,
-        qr_image: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        qr_image: Option<String>,
+        /// 二维码图片地址，可直接用于 `<img src>`，避免在 JSON 中携带 base64 图片
+        image_url: String,
         qr_data: String,
         expires_in: i64,
+        expires_at: i64,
+        /// Web 端需展示此数字，用户在 App 端确认时选择相同的数字
+        match_number: i16,
     }
 
     let qr_response = QrResponse {
         session_id: session_id.clone(),
         qr_image,
+        image_url: format!("/v1/qr-login/{}/image.png", session_id),
         qr_data,
-        expires_in: ttl_seconds as i64,
+        expires_in: ttl_seconds,
+        expires_at,
+        match_number: context.match_number,
     };
 
     info!("Generated QR code image for session: {}", session_id);
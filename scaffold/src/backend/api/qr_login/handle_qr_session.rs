@@ -1,30 +1,129 @@
-use chrono::{Duration, Utc};
-use sea_orm::{ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, ColumnTrait, Set};
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter, ColumnTrait, Set};
 use tracing::info;
+use crate::backend::api::qr_login::login_context::LoginContext;
+use crate::backend::config::qr_code;
 use crate::backend::models::qr_login_sessions;
 
 pub async fn insert_qr_session(
     db: &DatabaseConnection,
     session_id: &str,
     ttl_seconds: i64,
+    context: &LoginContext,
+) -> Result<qr_login_sessions::Model, DbErr> {
+    insert_session(db, session_id, ttl_seconds, context, None, qr_code::LOGIN_METHOD_QR).await
+}
+
+/// 创建推送审批会话，会话从创建起即归属于目标用户
+///
+/// `user_id` 为 `None` 时会话不归属任何用户，无法被批准，只会等待过期
+/// （目标用户不存在或推送过于频繁时使用，使响应与正常推送一致）
+pub async fn insert_push_session(
+    db: &DatabaseConnection,
+    session_id: &str,
+    ttl_seconds: i64,
+    context: &LoginContext,
+    user_id: Option<&str>,
+) -> Result<qr_login_sessions::Model, DbErr> {
+    insert_session(db, session_id, ttl_seconds, context, user_id, qr_code::LOGIN_METHOD_PUSH).await
+}
+
+/// 创建设备授权会话，由用户在设备验证页面输入用户码后批准
+pub async fn insert_device_session(
+    db: &DatabaseConnection,
+    session_id: &str,
+    ttl_seconds: i64,
+    context: &LoginContext,
+) -> Result<qr_login_sessions::Model, DbErr> {
+    insert_session(db, session_id, ttl_seconds, context, None, qr_code::LOGIN_METHOD_DEVICE).await
+}
+
+async fn insert_session(
+    db: &DatabaseConnection,
+    session_id: &str,
+    ttl_seconds: i64,
+    context: &LoginContext,
+    user_id: Option<&str>,
+    login_method: &str,
 ) -> Result<qr_login_sessions::Model, DbErr> {
     let new_session = qr_login_sessions::ActiveModel {
         session_id: Set(session_id.to_string()),
-        user_id: Set(None),
+        user_id: Set(user_id.map(str::to_string)),
         status: Set("pending".to_string()),
         web_token: Set(None),
         app_token: Set(None),
         created_at: Set(Utc::now().naive_utc()),
         expires_at: Set(Utc::now().naive_utc() + Duration::seconds(ttl_seconds)),
         updated_at: Set(Utc::now().naive_utc()),
+        client_ip: Set(Some(context.ip.clone())),
+        user_agent: Set(context.user_agent.clone()),
+        client_info: Set(context.client_info.clone()),
+        match_number: Set(Some(context.match_number)),
+        login_method: Set(login_method.to_string()),
         ..Default::default()
     };
 
     let inserted = new_session.insert(db).await?;
-    info!("Inserted QR login session: {}", session_id);
+    info!("Inserted {} login session: {}", login_method, session_id);
     Ok(inserted)
 }
 
+/// `since` 之后该 IP 发起的推送登录次数
+pub async fn count_push_sessions_from_ip(
+    db: &DatabaseConnection,
+    client_ip: &str,
+    since: NaiveDateTime,
+) -> Result<u64, DbErr> {
+    qr_login_sessions::Entity::find()
+        .filter(qr_login_sessions::Column::ClientIp.eq(client_ip))
+        .filter(qr_login_sessions::Column::LoginMethod.eq(qr_code::LOGIN_METHOD_PUSH))
+        .filter(qr_login_sessions::Column::CreatedAt.gt(since))
+        .count(db)
+        .await
+}
+
+/// `since` 之后推送给该用户的审批次数
+pub async fn count_push_sessions_for_user(
+    db: &DatabaseConnection,
+    user_id: &str,
+    since: NaiveDateTime,
+) -> Result<u64, DbErr> {
+    qr_login_sessions::Entity::find()
+        .filter(qr_login_sessions::Column::UserId.eq(user_id))
+        .filter(qr_login_sessions::Column::LoginMethod.eq(qr_code::LOGIN_METHOD_PUSH))
+        .filter(qr_login_sessions::Column::CreatedAt.gt(since))
+        .count(db)
+        .await
+}
+
+/// App 端用户能否通过扫码接口处理该会话
+///
+/// 扫码会话任何已登录用户都可以处理；推送审批会话只能由目标用户本人处理；
+/// 设备授权会话只能在设备验证页面通过用户码批准
+pub fn can_respond(session: &qr_login_sessions::Model, app_user_id: &str) -> bool {
+    match session.login_method.as_str() {
+        qr_code::LOGIN_METHOD_QR => true,
+        qr_code::LOGIN_METHOD_PUSH => session.user_id.as_deref() == Some(app_user_id),
+        _ => false,
+    }
+}
+
+/// 将已确认的会话标记为已领取 token，返回 `false` 表示会话已被领取过
+///
+/// 条件更新保证 token 只会被领取一次
+pub async fn mark_session_consumed(db: &DatabaseConnection, session_id: &str) -> Result<bool, DbErr> {
+    let result = qr_login_sessions::Entity::update_many()
+        .col_expr(qr_login_sessions::Column::Status, Expr::value("consumed"))
+        .col_expr(qr_login_sessions::Column::UpdatedAt, Expr::value(Utc::now().naive_utc()))
+        .filter(qr_login_sessions::Column::SessionId.eq(session_id))
+        .filter(qr_login_sessions::Column::Status.eq("confirmed"))
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected > 0)
+}
+
 pub async fn find_session_by_id(
     db: &DatabaseConnection,
     session_id: &str,
@@ -35,24 +134,124 @@ pub async fn find_session_by_id(
         .await
 }
 
+/// 确认会话并保存签发的 token，返回 `None` 表示会话已不是待处理状态或已过期
+///
+/// 条件更新与 `expire_stale_sessions`、拒绝等操作互斥，同一会话只会被确认一次
 pub async fn update_session_confirmed(
     db: &DatabaseConnection,
     session_id: &str,
     user_id: &str,
     web_token: &str,
     app_token: &str,
-) -> Result<qr_login_sessions::Model, DbErr> {
-    let session = find_session_by_id(db, session_id).await?
-        .ok_or(DbErr::RecordNotFound("Session not found".to_string()))?;
-    
-    let mut session_active: qr_login_sessions::ActiveModel = session.into();
-    session_active.status = Set("confirmed".to_string());
-    session_active.user_id = Set(Some(user_id.to_string()));
-    session_active.web_token = Set(Some(web_token.to_string()));
-    session_active.app_token = Set(Some(app_token.to_string()));
-    session_active.updated_at = Set(Utc::now().naive_utc());
-    
-    let updated = session_active.update(db).await?;
-    info!("Updated QR login session {} to confirmed", session_id);
+) -> Result<Option<qr_login_sessions::Model>, DbErr> {
+    let now = Utc::now().naive_utc();
+    let updated = qr_login_sessions::Entity::update_many()
+        .col_expr(qr_login_sessions::Column::Status, Expr::value("confirmed"))
+        .col_expr(qr_login_sessions::Column::UserId, Expr::value(user_id))
+        .col_expr(qr_login_sessions::Column::WebToken, Expr::value(web_token))
+        .col_expr(qr_login_sessions::Column::AppToken, Expr::value(app_token))
+        .col_expr(qr_login_sessions::Column::UpdatedAt, Expr::value(now))
+        .filter(qr_login_sessions::Column::SessionId.eq(session_id))
+        .filter(qr_login_sessions::Column::Status.is_in(["pending", "scanned"]))
+        .filter(qr_login_sessions::Column::ExpiresAt.gt(now))
+        .exec_with_returning(db)
+        .await?;
+
+    let updated = updated.into_iter().next();
+    if updated.is_some() {
+        info!("Updated QR login session {} to confirmed", session_id);
+    }
     Ok(updated)
 }
+
+/// 更新待处理会话的状态（scanned / rejected），返回 `None` 表示会话已不是待处理状态或已过期
+pub async fn update_session_status(
+    db: &DatabaseConnection,
+    session_id: &str,
+    status: &str,
+) -> Result<Option<qr_login_sessions::Model>, DbErr> {
+    let now = Utc::now().naive_utc();
+    let updated = qr_login_sessions::Entity::update_many()
+        .col_expr(qr_login_sessions::Column::Status, Expr::value(status))
+        .col_expr(qr_login_sessions::Column::UpdatedAt, Expr::value(now))
+        .filter(qr_login_sessions::Column::SessionId.eq(session_id))
+        .filter(qr_login_sessions::Column::Status.is_in(["pending", "scanned"]))
+        .filter(qr_login_sessions::Column::ExpiresAt.gt(now))
+        .exec_with_returning(db)
+        .await?;
+
+    let updated = updated.into_iter().next();
+    if updated.is_some() {
+        info!("Updated QR login session {} to {}", session_id, status);
+    }
+    Ok(updated)
+}
+
+/// 将已过期但仍处于 pending / scanned 的会话标记为 expired，返回受影响的会话
+pub async fn expire_stale_sessions(
+    db: &DatabaseConnection,
+    now: NaiveDateTime,
+) -> Result<Vec<qr_login_sessions::Model>, DbErr> {
+    let expired = qr_login_sessions::Entity::update_many()
+        .col_expr(qr_login_sessions::Column::Status, Expr::value("expired"))
+        .col_expr(qr_login_sessions::Column::UpdatedAt, Expr::value(now))
+        .filter(qr_login_sessions::Column::Status.is_in(["pending", "scanned"]))
+        .filter(qr_login_sessions::Column::ExpiresAt.lt(now))
+        .exec_with_returning(db)
+        .await?;
+
+    if !expired.is_empty() {
+        info!("Expired {} stale QR login sessions", expired.len());
+    }
+    Ok(expired)
+}
+
+/// 删除过期时间早于 `before` 的会话（无论状态），返回删除数量
+pub async fn purge_sessions_before(
+    db: &DatabaseConnection,
+    before: NaiveDateTime,
+) -> Result<u64, DbErr> {
+    let result = qr_login_sessions::Entity::delete_many()
+        .filter(qr_login_sessions::Column::ExpiresAt.lt(before))
+        .exec(db)
+        .await?;
+
+    if result.rows_affected > 0 {
+        info!("Purged {} QR login sessions expired before {}", result.rows_affected, before);
+    }
+    Ok(result.rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(login_method: &str, user_id: Option<&str>) -> qr_login_sessions::Model {
+        let now = Utc::now().naive_utc();
+        qr_login_sessions::Model {
+            id: 1,
+            session_id: "session".to_string(),
+            user_id: user_id.map(str::to_string),
+            status: "pending".to_string(),
+            web_token: None,
+            app_token: None,
+            created_at: now,
+            expires_at: now,
+            updated_at: now,
+            client_ip: None,
+            user_agent: None,
+            client_info: None,
+            match_number: None,
+            login_method: login_method.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_can_respond() {
+        assert!(can_respond(&session(qr_code::LOGIN_METHOD_QR, None), "alice"));
+        assert!(can_respond(&session(qr_code::LOGIN_METHOD_PUSH, Some("alice")), "alice"));
+        assert!(!can_respond(&session(qr_code::LOGIN_METHOD_PUSH, Some("alice")), "bob"));
+        assert!(!can_respond(&session(qr_code::LOGIN_METHOD_PUSH, None), "alice"));
+        assert!(!can_respond(&session(qr_code::LOGIN_METHOD_DEVICE, None), "alice"));
+    }
+}
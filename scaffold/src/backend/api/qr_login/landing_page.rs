@@ -0,0 +1,166 @@
+use actix_web::{http::StatusCode, web, HttpResponse};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use crate::backend::AppState;
+use crate::backend::api::qr_login::handle_qr_session::find_session_by_id;
+use crate::backend::api::qr_login::qr_payload::QrPayload;
+use crate::backend::config::{qr_code, qr_link_config};
+use crate::backend::errors::SuccessResponse;
+use crate::backend::utils::signing::server_public_key_bs58;
+
+#[derive(Deserialize, Debug)]
+pub struct LandingQuery {
+    pub exp: Option<i64>,
+    pub sig: Option<String>,
+}
+
+/// 二维码回退页面
+///
+/// 路由: /qr/{session_id}?exp=..&sig=..
+///
+/// 当 `url` 格式的二维码被通用相机 App 扫描时，浏览器会打开此页面。
+/// 页面校验签名后提示用户使用 App 扫码，若配置了自定义 scheme 则提供唤起 App 的按钮。
+pub async fn qr_landing_page(
+    state: web::Data<AppState>,
+    session_id: web::Path<String>,
+    query: web::Query<LandingQuery>,
+) -> HttpResponse {
+    let session_id = session_id.into_inner();
+    info!("QR landing page requested for session: {}", session_id);
+
+    let (Some(exp), Some(sig)) = (query.exp, query.sig.as_deref()) else {
+        return render_page(StatusCode::BAD_REQUEST, "二维码无效", "二维码链接不完整，请重新扫描登录页面上的二维码。", None);
+    };
+
+    let payload = QrPayload::login(&session_id, exp);
+    if !payload.verify(sig) {
+        warn!("Invalid QR signature on landing page for session: {}", session_id);
+        return render_page(StatusCode::BAD_REQUEST, "二维码无效", "该二维码未通过签名校验，可能是伪造的，请勿继续操作。", None);
+    }
+
+    let session = match find_session_by_id(&state.pg_client, &session_id).await {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            return render_page(StatusCode::NOT_FOUND, "二维码不存在", "未找到对应的登录会话，请刷新登录页面重新生成二维码。", None);
+        }
+        Err(e) => {
+            warn!("Failed to load QR session {}: {}", session_id, e);
+            return render_page(StatusCode::INTERNAL_SERVER_ERROR, "服务暂时不可用", "请稍后重试。", None);
+        }
+    };
+
+    let expired = exp < Utc::now().timestamp() || session.expires_at < Utc::now().naive_utc();
+    if expired || (session.status != "pending" && session.status != "scanned") {
+        return render_page(StatusCode::GONE, "二维码已失效", "该二维码已过期或已被使用，请刷新登录页面重新生成二维码。", None);
+    }
+
+    let deep_link = qr_link_config()
+        .deep_link_scheme
+        .as_deref()
+        .map(|scheme| payload.deep_link(scheme, sig));
+
+    render_page(StatusCode::OK, "请使用 App 扫码登录", "请打开已登录的 App，使用 App 内的扫一扫功能扫描此二维码完成登录。", deep_link.as_deref())
+}
+
+/// 服务端签名公钥
+///
+/// App 端使用此公钥校验二维码签名，签名消息格式见 `QrPayload::signing_message`
+pub async fn get_public_key() -> HttpResponse {
+    #[derive(Serialize)]
+    struct PublicKeyResponse {
+        algorithm: &'static str,
+        public_key: String,
+        encoding: &'static str,
+        payload_version: u32,
+    }
+
+    HttpResponse::Ok().json(SuccessResponse::new(PublicKeyResponse {
+        algorithm: "Ed25519",
+        public_key: server_public_key_bs58(),
+        encoding: "bs58",
+        payload_version: qr_code::PAYLOAD_VERSION,
+    }))
+}
+
+fn render_page(status: StatusCode, title: &str, message: &str, deep_link: Option<&str>) -> HttpResponse {
+    let action = deep_link
+        .map(|link| format!(r#"<a class="button" href="{}">在 App 中打开</a>"#, html_escape(link)))
+        .unwrap_or_default();
+
+    let body = format!(
+        r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>
+body {{ font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; background: #f5f6fa; margin: 0; }}
+.card {{ max-width: 420px; margin: 15vh auto; background: #fff; border-radius: 12px; padding: 32px; text-align: center; box-shadow: 0 4px 20px rgba(0,0,0,.08); }}
+h1 {{ font-size: 20px; color: #333; }}
+p {{ color: #666; line-height: 1.6; }}
+.button {{ display: inline-block; margin-top: 16px; padding: 12px 24px; border-radius: 8px; background: #667eea; color: #fff; text-decoration: none; }}
+</style>
+</head>
+<body>
+<div class="card">
+<h1>{title}</h1>
+<p>{message}</p>
+{action}
+</div>
+</body>
+</html>"#,
+        title = html_escape(title),
+        message = html_escape(message),
+        action = action,
+    );
+
+    HttpResponse::build(status)
+        .content_type("text/html; charset=utf-8")
+        .body(body)
+}
+
+fn html_escape(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_escape() {
+        assert_eq!(
+            html_escape(r#"<a href="x">'&'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;"
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_render_page_escapes_deep_link() {
+        let resp = render_page(StatusCode::OK, "t", "m", Some(r#"app://x?a=1&b="2""#));
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            "text/html; charset=utf-8"
+        );
+
+        let body = actix_web::body::to_bytes(resp.into_body()).await.unwrap();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert!(body.contains("a=1&amp;b=&quot;2&quot;"));
+        assert!(!body.contains(r#""2""#));
+        assert!(!body.contains("&b="));
+    }
+}
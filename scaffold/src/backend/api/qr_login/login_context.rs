@@ -0,0 +1,171 @@
+use actix_web::HttpRequest;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng};
+use crate::backend::config::qr_code;
+use crate::backend::models::qr_login_sessions;
+use crate::backend::utils::extractors::{client_ip, user_agent};
+
+/// 发起扫码登录的 Web 端上下文
+///
+/// 在生成二维码时记录，App 端扫码后展示给用户核对，
+/// 并要求用户选择 Web 端页面上显示的两位数字（数字匹配），防止远程钓鱼。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoginContext {
+    pub ip: String,
+    pub user_agent: Option<String>,
+    pub client_info: Option<String>,
+    pub match_number: i16,
+}
+
+impl LoginContext {
+    pub fn from_request(req: &HttpRequest, client_info: Option<&str>) -> Self {
+        Self {
+            ip: client_ip(req),
+            user_agent: user_agent(req),
+            client_info: client_info
+                .map(|info| info.chars().take(qr_code::CLIENT_INFO_MAX_LEN).collect::<String>())
+                .filter(|info| !info.trim().is_empty()),
+            match_number: generate_match_number(),
+        }
+    }
+}
+
+/// 请求是否来自发起该会话的 Web 端（IP 与 User-Agent 均与生成二维码时记录的一致）
+///
+/// 会话 ID 包含在二维码中，扫码方同样知道；仅凭会话 ID 不能代表发起方。
+pub fn is_initiator(req: &HttpRequest, session: &qr_login_sessions::Model) -> bool {
+    same_client(&client_ip(req), user_agent(req).as_deref(), session)
+}
+
+fn same_client(ip: &str, user_agent: Option<&str>, session: &qr_login_sessions::Model) -> bool {
+    session.client_ip.as_deref() == Some(ip) && session.user_agent.as_deref() == user_agent
+}
+
+/// 生成两位匹配数字 (10-99)
+pub fn generate_match_number() -> i16 {
+    thread_rng().gen_range(10, 100)
+}
+
+/// 生成 App 端展示的候选数字（包含正确数字，顺序随机）
+pub fn match_choices(correct: i16, count: usize) -> Vec<i16> {
+    let mut rng = thread_rng();
+    let mut choices = vec![correct];
+
+    while choices.len() < count.max(1) {
+        let candidate = rng.gen_range(10, 100);
+        if !choices.contains(&candidate) {
+            choices.push(candidate);
+        }
+    }
+
+    choices.shuffle(&mut rng);
+    choices
+}
+
+/// 将 User-Agent 概括为“浏览器 on 系统”，便于在 App 端展示
+pub fn summarize_user_agent(user_agent: &str) -> String {
+    let browser = if user_agent.contains("Edg/") {
+        Some("Edge")
+    } else if user_agent.contains("OPR/") || user_agent.contains("Opera") {
+        Some("Opera")
+    } else if user_agent.contains("Firefox/") {
+        Some("Firefox")
+    } else if user_agent.contains("Chrome/") || user_agent.contains("CriOS/") {
+        Some("Chrome")
+    } else if user_agent.contains("Safari/") {
+        Some("Safari")
+    } else {
+        None
+    };
+
+    let os = if user_agent.contains("Windows") {
+        Some("Windows")
+    } else if user_agent.contains("iPhone") || user_agent.contains("iPad") {
+        Some("iOS")
+    } else if user_agent.contains("Android") {
+        Some("Android")
+    } else if user_agent.contains("Mac OS X") || user_agent.contains("Macintosh") {
+        Some("macOS")
+    } else if user_agent.contains("Linux") {
+        Some("Linux")
+    } else {
+        None
+    };
+
+    match (browser, os) {
+        (Some(b), Some(o)) => format!("{} on {}", b, o),
+        (Some(b), None) => b.to_string(),
+        (None, Some(o)) => format!("Unknown browser on {}", o),
+        (None, None) => "Unknown browser".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_number_is_two_digits() {
+        for _ in 0..100 {
+            let n = generate_match_number();
+            assert!((10..100).contains(&n));
+        }
+    }
+
+    #[test]
+    fn test_match_choices_contain_correct_number() {
+        for _ in 0..50 {
+            let choices = match_choices(42, 3);
+            assert_eq!(choices.len(), 3);
+            assert!(choices.contains(&42));
+
+            let mut unique = choices.clone();
+            unique.sort();
+            unique.dedup();
+            assert_eq!(unique.len(), 3);
+        }
+    }
+
+    #[test]
+    fn test_same_client() {
+        let now = chrono::Utc::now().naive_utc();
+        let session = qr_login_sessions::Model {
+            id: 1,
+            session_id: "s1".to_string(),
+            user_id: None,
+            status: "pending".to_string(),
+            web_token: None,
+            app_token: None,
+            created_at: now,
+            expires_at: now,
+            updated_at: now,
+            client_ip: Some("203.0.113.7".to_string()),
+            user_agent: Some("Firefox".to_string()),
+            client_info: None,
+            match_number: Some(42),
+            login_method: qr_code::LOGIN_METHOD_QR.to_string(),
+        };
+
+        assert!(same_client("203.0.113.7", Some("Firefox"), &session));
+        assert!(!same_client("198.51.100.1", Some("Firefox"), &session));
+        assert!(!same_client("203.0.113.7", Some("Chrome"), &session));
+        assert!(!same_client("203.0.113.7", None, &session));
+    }
+
+    #[test]
+    fn test_summarize_user_agent() {
+        let chrome = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+        assert_eq!(summarize_user_agent(chrome), "Chrome on Windows");
+
+        let edge = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 Edg/120.0.0.0";
+        assert_eq!(summarize_user_agent(edge), "Edge on Windows");
+
+        let safari = "Mozilla/5.0 (Macintosh; Intel Mac OS X 14_0) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Safari/605.1.15";
+        assert_eq!(summarize_user_agent(safari), "Safari on macOS");
+
+        let firefox = "Mozilla/5.0 (X11; Linux x86_64; rv:121.0) Gecko/20100101 Firefox/121.0";
+        assert_eq!(summarize_user_agent(firefox), "Firefox on Linux");
+
+        assert_eq!(summarize_user_agent("curl/8.0"), "Unknown browser");
+    }
+}
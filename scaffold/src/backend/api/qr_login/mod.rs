@@ -3,21 +3,57 @@ mod confirm_login;
 mod check_status;
 mod handle_qr_session;
 mod ws_status;
+mod qr_payload;
+mod qr_render;
+mod qr_image;
+mod landing_page;
+mod login_context;
+mod scan_qr;
+mod sweeper;
+mod status_sync;
+mod sse_status;
+mod push_login;
+mod reject_login;
+mod app_token;
 
 use actix_web::{Scope, web};
 use crate::backend::api::qr_login::generate_qr::generate_qr_code;
 use crate::backend::api::qr_login::confirm_login::confirm_login;
+use crate::backend::api::qr_login::scan_qr::scan_qr_code;
+use crate::backend::api::qr_login::push_login::push_login;
+use crate::backend::api::qr_login::reject_login::reject_login;
+use crate::backend::api::qr_login::qr_image::get_qr_image;
 use crate::backend::api::qr_login::check_status::check_login_status;
 use crate::backend::api::qr_login::ws_status::ws_qr_status;
+use crate::backend::api::qr_login::sse_status::sse_qr_status;
+use crate::backend::api::qr_login::landing_page::{qr_landing_page, get_public_key};
+
+pub use crate::backend::api::qr_login::sweeper::spawn_session_sweeper;
+pub use crate::backend::api::qr_login::handle_qr_session::{
+    find_session_by_id, insert_device_session, mark_session_consumed, update_session_confirmed,
+    update_session_status,
+};
+pub use crate::backend::api::qr_login::login_context::{summarize_user_agent, LoginContext};
 
 pub fn qr_login_scope() -> Scope {
     web::scope("/qr-login")
         .route("/generate", web::post().to(generate_qr_code))
         .route("/status/{session_id}", web::get().to(check_login_status))
+        .route("/events/{session_id}", web::get().to(sse_qr_status))
+        .route("/scan", web::post().to(scan_qr_code))
         .route("/confirm", web::post().to(confirm_login))
+        .route("/reject", web::post().to(reject_login))
+        .route("/push", web::post().to(push_login))
+        .route("/public-key", web::get().to(get_public_key))
+        .route("/{session_id}/image.{ext}", web::get().to(get_qr_image))
 }
 
 /// WebSocket路由 (需要单独注册，因为WebSocket不在scope内)
 pub fn ws_qr_route() -> actix_web::Route {
     web::get().to(ws_qr_status)
 }
+
+/// 二维码回退页面路由 (挂载在根路径，对应 `https://<base_url>/qr/{session_id}`)
+pub fn qr_landing_route() -> actix_web::Route {
+    web::get().to(qr_landing_page)
+}
@@ -0,0 +1,162 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{Duration, Utc};
+use sea_orm::{ColumnTrait, Condition, EntityTrait, QueryFilter};
+use serde::Deserialize;
+use tracing::{info, warn};
+use uuid::Uuid;
+use crate::backend::AppState;
+use crate::backend::api::qr_login::handle_qr_session::{
+    count_push_sessions_for_user, count_push_sessions_from_ip, insert_push_session,
+};
+use crate::backend::api::qr_login::login_context::{match_choices, summarize_user_agent, LoginContext};
+use crate::backend::config::qr_code;
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+use crate::backend::models::users;
+use crate::backend::ws_manager::WsManager;
+use crate::backend::ws_protocol::UserEvent;
+
+#[derive(Deserialize, Debug)]
+pub struct PushLoginRequest {
+    /// 用户 ID 或邮箱
+    pub username: String,
+    /// Web 端客户端信息，推送给 App 展示给用户核对
+    pub client_info: Option<String>,
+}
+
+/// 推送登录审批
+///
+/// 路由: POST /v1/qr-login/push
+///
+/// Web 端输入用户名后，服务器通过用户通知通道（`/v2/ws`）向该用户已登录的 App 推送
+/// `login_approval` 事件。会话与扫码登录共用 `qr_login_sessions`，Web 端同样通过
+/// `/v1/ws/qr/{session_id}`、SSE 或轮询等待结果；App 端调用 `/confirm` 批准、`/reject` 拒绝。
+///
+/// 无论用户是否存在，响应都相同（不存在时创建一个无法被批准的会话），避免被用于枚举用户。
+/// 同一 IP 超过 `qr_code::PUSH_MAX_PER_IP` 次返回 429；同一用户超过
+/// `qr_code::PUSH_MAX_PER_USER` 次后不再推送，防止连续推送骚扰用户（MFA 疲劳攻击）。
+pub async fn push_login(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    ws_manager: web::Data<WsManager>,
+    request: web::Json<PushLoginRequest>,
+) -> HttpResponse {
+    let username = request.username.trim();
+    info!("Received push login request for: {}", username);
+
+    if username.is_empty() {
+        let error_resp = error_response(ErrorCode::MissingRequiredField, "username is required");
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+
+    let context = LoginContext::from_request(&req, request.client_info.as_deref());
+    let since = Utc::now().naive_utc() - Duration::seconds(qr_code::PUSH_RATE_WINDOW_SECONDS);
+
+    // 1. 按发起 IP 限流
+    match count_push_sessions_from_ip(&state.pg_client, &context.ip, since).await {
+        Ok(count) if count >= qr_code::PUSH_MAX_PER_IP => {
+            warn!("Push login from {} rejected: too many requests", context.ip);
+            let error_resp = error_response(
+                ErrorCode::RateLimitExceeded,
+                "Too many login requests, please try again later",
+            );
+            return HttpResponse::TooManyRequests().json(error_resp);
+        }
+        Ok(_) => {}
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Database error: {}", e),
+            );
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    }
+
+    // 2. 查找用户
+    let user = match users::Entity::find()
+        .filter(
+            Condition::any()
+                .add(users::Column::UserId.eq(username))
+                .add(users::Column::Email.eq(username)),
+        )
+        .one(&state.pg_client)
+        .await
+    {
+        Ok(user) => user.filter(|u| u.is_active != Some(false)),
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Database error: {}", e),
+            );
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    };
+
+    // 3. 按目标用户限流，超过后照常返回但不再推送
+    let target = match user {
+        Some(user) => match count_push_sessions_for_user(&state.pg_client, &user.user_id, since).await {
+            Ok(count) if count >= qr_code::PUSH_MAX_PER_USER => {
+                warn!("Push login for {} suppressed: too many approvals requested", user.user_id);
+                None
+            }
+            Ok(_) => Some(user),
+            Err(e) => {
+                let error_resp = error_response(
+                    ErrorCode::DatabaseError,
+                    format!("Database error: {}", e),
+                );
+                return HttpResponse::InternalServerError().json(error_resp);
+            }
+        },
+        None => None,
+    };
+
+    // 4. 创建会话
+    let session_id = Uuid::new_v4().to_string();
+    let ttl_seconds = qr_code::TTL_SECONDS as i64;
+    let target_id = target.as_ref().map(|user| user.user_id.as_str());
+
+    let session = match insert_push_session(
+        &state.pg_client,
+        &session_id,
+        ttl_seconds,
+        &context,
+        target_id,
+    ).await {
+        Ok(session) => session,
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Failed to create push session: {}", e),
+            );
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    };
+
+    // 5. 推送给用户已登录的 App
+    if let Some(user_id) = target_id {
+        let browser = session.user_agent.as_deref().map(summarize_user_agent);
+        let choices = match_choices(context.match_number, qr_code::MATCH_CHOICES);
+        ws_manager
+            .notify_user(user_id, UserEvent::login_approval(&session, browser, choices))
+            .await;
+        info!("📲 Pushed login approval for session {} to user {}", session_id, user_id);
+    } else {
+        info!("Created unroutable push session {}", session_id);
+    }
+
+    #[derive(serde::Serialize)]
+    struct PushLoginResponse {
+        session_id: String,
+        expires_in: i64,
+        expires_at: i64,
+        /// Web 端需展示此数字，用户在 App 端批准时选择相同的数字
+        match_number: i16,
+    }
+
+    HttpResponse::Ok().json(SuccessResponse::new(PushLoginResponse {
+        session_id,
+        expires_in: ttl_seconds,
+        expires_at: session.expires_at.and_utc().timestamp(),
+        match_number: context.match_number,
+    }))
+}
@@ -0,0 +1,144 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse, ResponseError};
+use chrono::Utc;
+use std::time::SystemTime;
+use tracing::info;
+use crate::backend::AppState;
+use crate::backend::api::qr_login::handle_qr_session::find_session_by_id;
+use crate::backend::api::qr_login::qr_payload::QrPayload;
+use crate::backend::api::qr_login::qr_render::{render_qr, QrRenderParams};
+use crate::backend::config::qr_link_config;
+use crate::backend::errors::{ErrorCode, error_response};
+use crate::backend::utils::hash::hash_str;
+
+/// 直接返回二维码图片
+///
+/// 路由: GET /v1/qr-login/{session_id}/image.{png|svg}
+///
+/// 根据已存储的会话按需渲染，支持与 `/generate` 相同的渲染参数（query string，`format` 由扩展名决定）。
+/// 图片在会话过期前可被缓存（`Cache-Control` + `ETag`），会话过期或已结束后返回 410。
+pub async fn get_qr_image(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+    query: web::Query<QrRenderParams>,
+) -> HttpResponse {
+    let (session_id, ext) = path.into_inner();
+
+    let mut params = query.into_inner();
+    params.format = match ext.as_str() {
+        "png" | "svg" => Some(ext.clone()),
+        _ => {
+            let error_resp = error_response(
+                ErrorCode::InvalidParams,
+                format!("Unsupported image type: {}, expected png or svg", ext),
+            );
+            return HttpResponse::NotFound().json(error_resp);
+        }
+    };
+
+    let options = match params.to_options() {
+        Ok(options) => options,
+        Err(e) => {
+            let error_resp = error_response(ErrorCode::InvalidParams, e);
+            return HttpResponse::BadRequest().json(error_resp);
+        }
+    };
+
+    let session = match find_session_by_id(&state.pg_client, &session_id).await {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            let error_resp = error_response(
+                ErrorCode::QRCodeNotFound,
+                "Session not found",
+            );
+            return HttpResponse::NotFound().json(error_resp);
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Database error: {}", e),
+            );
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    };
+
+    let remaining = (session.expires_at - Utc::now().naive_utc()).num_seconds();
+    if remaining <= 0 || (session.status != "pending" && session.status != "scanned") {
+        let error_resp = error_response(
+            ErrorCode::QRCodeExpired,
+            ErrorCode::QRCodeExpired.default_message(),
+        );
+        return HttpResponse::Gone().json(error_resp);
+    }
+
+    // 二维码内容由会话与配置决定（Ed25519 签名是确定性的），因此相同参数总是渲染出相同图片
+    let expires_at = session.expires_at.and_utc().timestamp();
+    let qr_data = match QrPayload::login(&session_id, expires_at).encode(qr_link_config()) {
+        Ok(data) => data,
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::InternalError,
+                format!("Failed to sign QR payload: {}", e.message()),
+            );
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    };
+
+    let etag = format!("\"{}\"", &hash_str(&format!("{}|{:?}", qr_data, options))[..32]);
+    let cache_control = format!("private, max-age={}", remaining);
+
+    let not_modified = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| etag_matches(v, &etag));
+    if not_modified {
+        return HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag))
+            .insert_header((header::CACHE_CONTROL, cache_control))
+            .finish();
+    }
+
+    let rendered = match render_qr(&qr_data, &options) {
+        Ok(Some(rendered)) => rendered,
+        Ok(None) => {
+            let error_resp = error_response(
+                ErrorCode::InvalidParams,
+                "Image format is required",
+            );
+            return HttpResponse::BadRequest().json(error_resp);
+        }
+        // 尺寸过小等参数错误返回 400，其余为 500
+        Err(e) => return e.error_response(),
+    };
+
+    info!("Rendered QR {} image for session: {}", ext, session_id);
+
+    HttpResponse::Ok()
+        .content_type(rendered.content_type())
+        .insert_header((header::ETAG, etag))
+        .insert_header((header::CACHE_CONTROL, cache_control))
+        .insert_header(header::Expires(SystemTime::from(session.expires_at.and_utc()).into()))
+        .body(rendered.into_bytes())
+}
+
+/// 判断 `If-None-Match` 是否命中当前 ETag（支持多个值、弱校验前缀与 `*`）
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match
+        .split(',')
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .any(|tag| tag == etag || tag == "*")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_etag_matches() {
+        assert!(etag_matches(r#""abc""#, r#""abc""#));
+        assert!(etag_matches(r#""x", W/"abc""#, r#""abc""#));
+        assert!(etag_matches("*", r#""abc""#));
+        assert!(!etag_matches(r#""abcd""#, r#""abc""#));
+    }
+}
@@ -0,0 +1,167 @@
+use serde_json::json;
+use tracing::warn;
+use crate::backend::config::{qr_code, QrLinkConfig, QrPayloadFormat};
+use crate::backend::errors::AppError;
+use crate::backend::utils::signing::{sign_message, verify_server_signature};
+
+/// 二维码载荷
+///
+/// 服务端使用 Ed25519 私钥对载荷签名，App 端可通过 `/v1/qr-login/public-key`
+/// 获取公钥离线校验，防止钓鱼页面伪造二维码。
+///
+/// 签名消息格式: `qr-login|v<version>|<action>|<session_id>|<expires_at>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrPayload {
+    pub session_id: String,
+    pub action: String,
+    pub expires_at: i64,
+}
+
+impl QrPayload {
+    pub fn login(session_id: impl Into<String>, expires_at: i64) -> Self {
+        Self {
+            session_id: session_id.into(),
+            action: qr_code::ACTION_LOGIN.to_string(),
+            expires_at,
+        }
+    }
+
+    /// 待签名的消息
+    pub fn signing_message(&self) -> String {
+        format!(
+            "qr-login|v{}|{}|{}|{}",
+            qr_code::PAYLOAD_VERSION,
+            self.action,
+            self.session_id,
+            self.expires_at
+        )
+    }
+
+    pub fn sign(&self) -> Result<String, AppError> {
+        sign_message(self.signing_message().as_bytes())
+    }
+
+    pub fn verify(&self, signature: &str) -> bool {
+        verify_server_signature(self.signing_message().as_bytes(), signature)
+    }
+
+    /// `https://<base_url>/qr/<session_id>?exp=..&sig=..`
+    pub fn url_link(&self, base_url: &str, signature: &str) -> String {
+        format!(
+            "{}/qr/{}?exp={}&sig={}",
+            base_url, self.session_id, self.expires_at, signature
+        )
+    }
+
+    /// `<scheme>://qr-login/<session_id>?exp=..&sig=..`
+    pub fn deep_link(&self, scheme: &str, signature: &str) -> String {
+        format!(
+            "{}://qr-login/{}?exp={}&sig={}",
+            scheme, self.session_id, self.expires_at, signature
+        )
+    }
+
+    /// 签名后的 JSON 字符串
+    pub fn json(&self, signature: &str) -> String {
+        json!({
+            "v": qr_code::PAYLOAD_VERSION,
+            "session_id": self.session_id,
+            "action": self.action,
+            "expires_at": self.expires_at,
+            "sig": signature,
+        })
+        .to_string()
+    }
+
+    /// 按配置的格式签名并编码为二维码内容
+    ///
+    /// 若配置的 url / deeplink 格式缺少对应的地址或 scheme，则回退为 JSON 格式
+    pub fn encode(&self, config: &QrLinkConfig) -> Result<String, AppError> {
+        let signature = self.sign()?;
+
+        let encoded = match config.payload_format {
+            QrPayloadFormat::Json => self.json(&signature),
+            QrPayloadFormat::Url => match config.base_url.as_deref() {
+                Some(base_url) => self.url_link(base_url, &signature),
+                None => {
+                    warn!("QR_LOGIN_BASE_URL is not set, falling back to JSON payload");
+                    self.json(&signature)
+                }
+            },
+            QrPayloadFormat::DeepLink => match config.deep_link_scheme.as_deref() {
+                Some(scheme) => self.deep_link(scheme, &signature),
+                None => {
+                    warn!("QR_LOGIN_DEEP_LINK_SCHEME is not set, falling back to JSON payload");
+                    self.json(&signature)
+                }
+            },
+        };
+
+        Ok(encoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload() -> QrPayload {
+        QrPayload::login("3f2a9c1e-0000-4000-8000-000000000000", 1_700_000_000)
+    }
+
+    #[test]
+    fn test_signing_message_format() {
+        assert_eq!(
+            payload().signing_message(),
+            "qr-login|v1|login|3f2a9c1e-0000-4000-8000-000000000000|1700000000"
+        );
+    }
+
+    #[test]
+    fn test_signature_binds_session_and_expiry() {
+        let payload = payload();
+        let signature = payload.sign().unwrap();
+        assert!(payload.verify(&signature));
+
+        let mut forged = payload.clone();
+        forged.expires_at += 3600;
+        assert!(!forged.verify(&signature));
+
+        let mut forged = payload;
+        forged.session_id = "attacker-session".to_string();
+        assert!(!forged.verify(&signature));
+    }
+
+    #[test]
+    fn test_encode_json() {
+        let encoded = payload().encode(&QrLinkConfig::default()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&encoded).unwrap();
+
+        assert_eq!(value["v"], 1);
+        assert_eq!(value["action"], "login");
+        assert_eq!(value["expires_at"], 1_700_000_000);
+        assert!(payload().verify(value["sig"].as_str().unwrap()));
+    }
+
+    #[test]
+    fn test_encode_links() {
+        let config = QrLinkConfig {
+            payload_format: QrPayloadFormat::Url,
+            base_url: Some("https://example.com".to_string()),
+            deep_link_scheme: Some("rustframe".to_string()),
+        };
+        let url = payload().encode(&config).unwrap();
+        assert!(url.starts_with("https://example.com/qr/3f2a9c1e-0000-4000-8000-000000000000?exp=1700000000&sig="));
+
+        let config = QrLinkConfig { payload_format: QrPayloadFormat::DeepLink, ..config };
+        let link = payload().encode(&config).unwrap();
+        assert!(link.starts_with("rustframe://qr-login/3f2a9c1e-0000-4000-8000-000000000000?exp=1700000000&sig="));
+    }
+
+    #[test]
+    fn test_encode_falls_back_to_json() {
+        let config = QrLinkConfig { payload_format: QrPayloadFormat::Url, ..Default::default() };
+        let encoded = payload().encode(&config).unwrap();
+        assert!(encoded.starts_with('{'));
+    }
+}
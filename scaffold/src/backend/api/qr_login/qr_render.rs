@@ -0,0 +1,451 @@
+use base64::{engine::general_purpose, Engine as _};
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+use once_cell::sync::Lazy;
+use qrcode::{Color, EcLevel, QrCode};
+use serde::Deserialize;
+use std::fmt::Write as _;
+use tracing::{info, warn};
+use crate::backend::config::{qr_code, qr_image_config};
+use crate::backend::errors::AppError;
+
+/// 二维码图片输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QrImageFormat {
+    #[default]
+    Png,
+    Svg,
+    /// 只返回二维码内容，由调用方自行渲染
+    Url,
+}
+
+impl QrImageFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "png" => Some(Self::Png),
+            "svg" => Some(Self::Svg),
+            "url" | "none" => Some(Self::Url),
+            _ => None,
+        }
+    }
+}
+
+/// 二维码图片渲染参数（请求参数，均可选）
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct QrRenderParams {
+    /// 图片尺寸（像素），范围见 `qr_code::MIN_IMAGE_SIZE`..=`qr_code::MAX_IMAGE_SIZE`
+    pub size: Option<u32>,
+    /// 容错级别: L / M / Q / H
+    pub ec_level: Option<String>,
+    /// 留白宽度（模块数）
+    pub margin: Option<u32>,
+    /// 前景色，如 `#000000`
+    pub foreground: Option<String>,
+    /// 背景色，如 `#ffffff`
+    pub background: Option<String>,
+    /// 输出格式: png / svg / url
+    pub format: Option<String>,
+    /// 是否在中心叠加 Logo（需配置 `QR_LOGIN_LOGO_PATH`）
+    pub logo: Option<bool>,
+}
+
+/// 校验后的渲染选项
+#[derive(Debug, Clone, PartialEq)]
+pub struct QrRenderOptions {
+    pub size: u32,
+    pub ec_level: EcLevel,
+    pub margin: u32,
+    pub foreground: Rgba<u8>,
+    pub background: Rgba<u8>,
+    pub format: QrImageFormat,
+    pub logo: bool,
+}
+
+impl Default for QrRenderOptions {
+    fn default() -> Self {
+        Self {
+            size: qr_code::DEFAULT_IMAGE_SIZE,
+            ec_level: qr_code::ERROR_CORRECTION_LEVEL,
+            margin: qr_code::DEFAULT_MARGIN,
+            foreground: Rgba([0, 0, 0, 255]),
+            background: Rgba([255, 255, 255, 255]),
+            format: QrImageFormat::Png,
+            logo: false,
+        }
+    }
+}
+
+impl QrRenderParams {
+    /// 校验参数并转换为渲染选项
+    ///
+    /// 叠加 Logo 时会遮挡部分模块，因此容错级别自动提升为 `EcLevel::H`
+    pub fn to_options(&self) -> Result<QrRenderOptions, String> {
+        let mut options = QrRenderOptions::default();
+
+        if let Some(size) = self.size {
+            if !(qr_code::MIN_IMAGE_SIZE..=qr_code::MAX_IMAGE_SIZE).contains(&size) {
+                return Err(format!(
+                    "size must be between {} and {}",
+                    qr_code::MIN_IMAGE_SIZE,
+                    qr_code::MAX_IMAGE_SIZE
+                ));
+            }
+            options.size = size;
+        }
+
+        if let Some(level) = self.ec_level.as_deref() {
+            options.ec_level = parse_ec_level(level)
+                .ok_or_else(|| format!("Invalid ec_level: {}, expected L/M/Q/H", level))?;
+        }
+
+        if let Some(margin) = self.margin {
+            if margin > qr_code::MAX_MARGIN {
+                return Err(format!("margin must not exceed {}", qr_code::MAX_MARGIN));
+            }
+            options.margin = margin;
+        }
+
+        if let Some(color) = self.foreground.as_deref() {
+            options.foreground = parse_hex_color(color)
+                .ok_or_else(|| format!("Invalid foreground color: {}", color))?;
+        }
+
+        if let Some(color) = self.background.as_deref() {
+            options.background = parse_hex_color(color)
+                .ok_or_else(|| format!("Invalid background color: {}", color))?;
+        }
+
+        if let Some(format) = self.format.as_deref() {
+            options.format = QrImageFormat::parse(format)
+                .ok_or_else(|| format!("Invalid format: {}, expected png/svg/url", format))?;
+        }
+
+        if self.logo.unwrap_or(false) {
+            if LOGO.is_none() {
+                return Err("Logo is not configured on this server".to_string());
+            }
+            options.logo = true;
+            options.ec_level = EcLevel::H;
+        }
+
+        Ok(options)
+    }
+}
+
+/// 渲染结果
+#[derive(Debug, Clone)]
+pub enum RenderedQr {
+    Png(Vec<u8>),
+    Svg(String),
+}
+
+impl RenderedQr {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            RenderedQr::Png(_) => "image/png",
+            RenderedQr::Svg(_) => "image/svg+xml",
+        }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        match self {
+            RenderedQr::Png(bytes) => bytes,
+            RenderedQr::Svg(svg) => svg.into_bytes(),
+        }
+    }
+
+    /// 转换为 `data:` URI，便于直接嵌入 JSON 响应
+    pub fn to_data_uri(&self) -> String {
+        let bytes = match self {
+            RenderedQr::Png(bytes) => bytes.as_slice(),
+            RenderedQr::Svg(svg) => svg.as_bytes(),
+        };
+        format!(
+            "data:{};base64,{}",
+            self.content_type(),
+            general_purpose::STANDARD.encode(bytes)
+        )
+    }
+}
+
+/// 中心 Logo，首次使用时从 `QR_LOGIN_LOGO_PATH` 加载
+static LOGO: Lazy<Option<RgbaImage>> = Lazy::new(|| {
+    let path = qr_image_config().logo_path.as_deref()?;
+    match image::open(path) {
+        Ok(img) => {
+            info!("Loaded QR logo from {}", path);
+            Some(img.to_rgba8())
+        }
+        Err(e) => {
+            warn!("Failed to load QR logo from {}: {}", path, e);
+            None
+        }
+    }
+});
+
+/// 按选项渲染二维码，`QrImageFormat::Url` 时返回 `None`
+///
+/// 尺寸不足以容纳二维码内容时返回参数错误，其余为内部错误。
+pub fn render_qr(data: &str, options: &QrRenderOptions) -> Result<Option<RenderedQr>, AppError> {
+    if options.format == QrImageFormat::Url {
+        return Ok(None);
+    }
+
+    let code = QrCode::with_error_correction_level(data.as_bytes(), options.ec_level)
+        .map_err(|e| AppError::internal(format!("Failed to generate QR code: {}", e)))?;
+    let layout = Layout::new(&code, options).map_err(AppError::validation)?;
+    let logo = if options.logo { LOGO.as_ref() } else { None };
+
+    let rendered = match options.format {
+        QrImageFormat::Png => render_png(&code, &layout, options, logo).map(|png| Some(RenderedQr::Png(png))),
+        QrImageFormat::Svg => render_svg(&code, &layout, options, logo).map(|svg| Some(RenderedQr::Svg(svg))),
+        QrImageFormat::Url => Ok(None),
+    };
+    rendered.map_err(|e| AppError::internal(format!("Failed to generate QR image: {}", e)))
+}
+
+/// 模块像素布局：整张图为 `size` x `size`，二维码（含留白）居中
+struct Layout {
+    module_px: u32,
+    offset: u32,
+    content_px: u32,
+}
+
+impl Layout {
+    fn new(code: &QrCode, options: &QrRenderOptions) -> Result<Self, String> {
+        let total_modules = code.width() as u32 + options.margin * 2;
+        let module_px = options.size / total_modules;
+        if module_px == 0 {
+            return Err(format!(
+                "size {} is too small for this QR code ({} modules)",
+                options.size, total_modules
+            ));
+        }
+
+        let content_px = module_px * total_modules;
+        Ok(Self {
+            module_px,
+            offset: (options.size - content_px) / 2 + module_px * options.margin,
+            content_px,
+        })
+    }
+
+    /// Logo 边长及左上角坐标
+    fn logo_box(&self, size: u32) -> (u32, u32) {
+        let logo_px = ((self.content_px as f32) * qr_code::LOGO_SCALE) as u32;
+        (logo_px.max(1), (size - logo_px) / 2)
+    }
+}
+
+fn render_png(
+    code: &QrCode,
+    layout: &Layout,
+    options: &QrRenderOptions,
+    logo: Option<&RgbaImage>,
+) -> Result<Vec<u8>, String> {
+    let width = code.width();
+    let mut img = RgbaImage::from_pixel(options.size, options.size, options.background);
+
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        if color != Color::Dark {
+            continue;
+        }
+        let x0 = layout.offset + (i % width) as u32 * layout.module_px;
+        let y0 = layout.offset + (i / width) as u32 * layout.module_px;
+        for y in y0..y0 + layout.module_px {
+            for x in x0..x0 + layout.module_px {
+                img.put_pixel(x, y, options.foreground);
+            }
+        }
+    }
+
+    if let Some(logo) = logo {
+        let (logo_px, pos) = layout.logo_box(options.size);
+        // Logo 周围留一圈背景色，避免与模块粘连
+        let pad = (logo_px / 10).max(1);
+        let plate = RgbaImage::from_pixel(logo_px + pad * 2, logo_px + pad * 2, options.background);
+        imageops::overlay(&mut img, &plate, (pos - pad) as i64, (pos - pad) as i64);
+        let resized = imageops::resize(logo, logo_px, logo_px, FilterType::Lanczos3);
+        imageops::overlay(&mut img, &resized, pos as i64, pos as i64);
+    }
+
+    let mut png_bytes: Vec<u8> = Vec::new();
+    image::DynamicImage::ImageRgba8(img)
+        .write_to(
+            &mut std::io::Cursor::new(&mut png_bytes),
+            image::ImageFormat::Png,
+        )
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    Ok(png_bytes)
+}
+
+fn render_svg(
+    code: &QrCode,
+    layout: &Layout,
+    options: &QrRenderOptions,
+    logo: Option<&RgbaImage>,
+) -> Result<String, String> {
+    let width = code.width();
+    let size = options.size;
+    let mut path = String::new();
+
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        if color != Color::Dark {
+            continue;
+        }
+        let x = layout.offset + (i % width) as u32 * layout.module_px;
+        let y = layout.offset + (i / width) as u32 * layout.module_px;
+        let _ = write!(path, "M{} {}h{}v{}h-{}z", x, y, layout.module_px, layout.module_px, layout.module_px);
+    }
+
+    let mut svg = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><svg xmlns="http://www.w3.org/2000/svg" version="1.1" width="{size}" height="{size}" viewBox="0 0 {size} {size}" shape-rendering="crispEdges"><rect width="{size}" height="{size}" fill="{bg}"/><path fill="{fg}" d="{path}"/>"#,
+        size = size,
+        bg = hex_color(options.background),
+        fg = hex_color(options.foreground),
+        path = path,
+    );
+
+    if let Some(logo) = logo {
+        let (logo_px, pos) = layout.logo_box(size);
+        let pad = (logo_px / 10).max(1);
+        let mut logo_png: Vec<u8> = Vec::new();
+        image::DynamicImage::ImageRgba8(logo.clone())
+            .write_to(&mut std::io::Cursor::new(&mut logo_png), image::ImageFormat::Png)
+            .map_err(|e| format!("Failed to encode logo: {}", e))?;
+
+        let _ = write!(
+            svg,
+            r#"<rect x="{px}" y="{px}" width="{pw}" height="{pw}" fill="{bg}"/><image x="{x}" y="{x}" width="{w}" height="{w}" href="data:image/png;base64,{data}"/>"#,
+            px = pos - pad,
+            pw = logo_px + pad * 2,
+            bg = hex_color(options.background),
+            x = pos,
+            w = logo_px,
+            data = general_purpose::STANDARD.encode(&logo_png),
+        );
+    }
+
+    svg.push_str("</svg>");
+    Ok(svg)
+}
+
+fn parse_ec_level(value: &str) -> Option<EcLevel> {
+    match value.trim().to_ascii_uppercase().as_str() {
+        "L" => Some(EcLevel::L),
+        "M" => Some(EcLevel::M),
+        "Q" => Some(EcLevel::Q),
+        "H" => Some(EcLevel::H),
+        _ => None,
+    }
+}
+
+/// 解析 `#RRGGBB` / `RRGGBB` / `#RGB` 形式的颜色
+fn parse_hex_color(value: &str) -> Option<Rgba<u8>> {
+    let hex = value.trim().trim_start_matches('#');
+    let expanded: String = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 => hex.to_string(),
+        _ => return None,
+    };
+
+    let channel = |i: usize| u8::from_str_radix(expanded.get(i..i + 2)?, 16).ok();
+    Some(Rgba([channel(0)?, channel(2)?, channel(4)?, 255]))
+}
+
+fn hex_color(color: Rgba<u8>) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::errors::ErrorCode;
+
+    #[test]
+    fn test_default_params() {
+        let options = QrRenderParams::default().to_options().unwrap();
+        assert_eq!(options, QrRenderOptions::default());
+    }
+
+    #[test]
+    fn test_params_validation() {
+        let params = QrRenderParams { size: Some(qr_code::MAX_IMAGE_SIZE + 1), ..Default::default() };
+        assert!(params.to_options().is_err());
+
+        let params = QrRenderParams { margin: Some(qr_code::MAX_MARGIN + 1), ..Default::default() };
+        assert!(params.to_options().is_err());
+
+        let params = QrRenderParams { ec_level: Some("X".to_string()), ..Default::default() };
+        assert!(params.to_options().is_err());
+
+        let params = QrRenderParams { foreground: Some("#12345".to_string()), ..Default::default() };
+        assert!(params.to_options().is_err());
+
+        let params = QrRenderParams { format: Some("gif".to_string()), ..Default::default() };
+        assert!(params.to_options().is_err());
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#ff0080"), Some(Rgba([255, 0, 128, 255])));
+        assert_eq!(parse_hex_color("00FF00"), Some(Rgba([0, 255, 0, 255])));
+        assert_eq!(parse_hex_color("#fff"), Some(Rgba([255, 255, 255, 255])));
+        assert_eq!(parse_hex_color("#gggggg"), None);
+        assert_eq!(hex_color(Rgba([255, 0, 128, 255])), "#ff0080");
+    }
+
+    #[test]
+    fn test_render_png_with_exact_size() {
+        let options = QrRenderOptions { size: 256, ..Default::default() };
+        let rendered = render_qr("hello", &options).unwrap().unwrap();
+        assert_eq!(rendered.content_type(), "image/png");
+
+        let RenderedQr::Png(png) = rendered else { panic!("expected png") };
+        let img = image::load_from_memory(&png).unwrap();
+        assert_eq!((img.width(), img.height()), (256, 256));
+    }
+
+    #[test]
+    fn test_render_colors_and_margin() {
+        let options = QrRenderOptions {
+            margin: 0,
+            foreground: Rgba([255, 0, 0, 255]),
+            background: Rgba([0, 0, 255, 255]),
+            ..Default::default()
+        };
+        let Some(RenderedQr::Png(png)) = render_qr("hello", &options).unwrap() else { panic!("expected png") };
+        let img = image::load_from_memory(&png).unwrap().to_rgba8();
+
+        // 无留白时，左上角定位图案紧贴内容区域，且为前景色
+        let code = QrCode::with_error_correction_level("hello", options.ec_level).unwrap();
+        let layout = Layout::new(&code, &options).unwrap();
+        assert_eq!(*img.get_pixel(layout.offset, layout.offset), Rgba([255, 0, 0, 255]));
+        assert_eq!(*img.get_pixel(0, 0), Rgba([0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let options = QrRenderOptions { format: QrImageFormat::Svg, ..Default::default() };
+        let rendered = render_qr("hello", &options).unwrap().unwrap();
+        assert_eq!(rendered.content_type(), "image/svg+xml");
+        assert!(rendered.to_data_uri().starts_with("data:image/svg+xml;base64,"));
+
+        let RenderedQr::Svg(svg) = rendered else { panic!("expected svg") };
+        assert!(svg.contains(r#"width="300""#));
+        assert!(svg.ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_render_size_too_small() {
+        let options = QrRenderOptions { size: 16, ..Default::default() };
+        let err = render_qr("hello", &options).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::InvalidParams);
+    }
+
+    #[test]
+    fn test_render_url_only() {
+        let options = QrRenderOptions { format: QrImageFormat::Url, ..Default::default() };
+        assert!(render_qr("hello", &options).unwrap().is_none());
+    }
+}
@@ -0,0 +1,126 @@
+use actix_web::{web, HttpResponse, ResponseError};
+use chrono::Utc;
+use serde::Deserialize;
+use tracing::{info, warn};
+use crate::backend::AppState;
+use crate::backend::api::device::{verify_device_signature, DeviceSignature};
+use crate::backend::api::qr_login::app_token::verify_app_token;
+use crate::backend::api::qr_login::handle_qr_session::{can_respond, find_session_by_id, update_session_status};
+use crate::backend::config::{device_key, qr_login_policy};
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+use crate::backend::ws_manager::WsManager;
+use crate::backend::ws_protocol::WsEvent;
+
+#[derive(Deserialize, Debug)]
+pub struct RejectLoginRequest {
+    pub session_id: String,
+    pub app_token: String,
+    /// 已注册设备对本次拒绝的签名
+    #[serde(default)]
+    pub device_signature: Option<DeviceSignature>,
+}
+
+/// App 端拒绝登录
+///
+/// 路由: POST /v1/qr-login/reject
+///
+/// 适用于扫码登录与推送审批；推送审批会话只能由目标用户本人拒绝。
+pub async fn reject_login(
+    state: web::Data<AppState>,
+    ws_manager: web::Data<WsManager>,
+    request: web::Json<RejectLoginRequest>,
+) -> HttpResponse {
+    info!("Received reject login request for session: {}", request.session_id);
+
+    let app_claims = match verify_app_token(&state.pg_client, &request.app_token).await {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    let session = match find_session_by_id(&state.pg_client, &request.session_id).await {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            let error_resp = error_response(
+                ErrorCode::QRCodeNotFound,
+                "Session not found",
+            );
+            return HttpResponse::NotFound().json(error_resp);
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Database error: {}", e),
+            );
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    };
+
+    if !can_respond(&session, &app_claims.user_id) {
+        warn!(
+            "User {} tried to reject push login session {} of another user",
+            app_claims.user_id, session.session_id
+        );
+        let error_resp = error_response(
+            ErrorCode::PermissionDenied,
+            "This login request belongs to another user",
+        );
+        return HttpResponse::Forbidden().json(error_resp);
+    }
+
+    if session.status != "pending" && session.status != "scanned" {
+        let error_resp = error_response(
+            ErrorCode::ResourceConflict,
+            "Session is not in valid state",
+        );
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+
+    if session.expires_at < Utc::now().naive_utc() {
+        let error_resp = error_response(
+            ErrorCode::QRCodeExpired,
+            "Session expired",
+        );
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+
+    if let Err(e) = verify_device_signature(
+        &state.pg_client,
+        &app_claims.user_id,
+        device_key::ACTION_REJECT,
+        &session.session_id,
+        request.device_signature.as_ref(),
+        qr_login_policy(),
+    ).await {
+        warn!("Device signature check failed for session {}: {}", session.session_id, e.message());
+        return e.error_response();
+    }
+
+    match update_session_status(&state.pg_client, &session.session_id, "rejected").await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            let error_resp = error_response(ErrorCode::ResourceConflict, "Session is no longer pending");
+            return HttpResponse::Conflict().json(error_resp);
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Failed to update session: {}", e),
+            );
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    }
+
+    ws_manager.notify(&request.session_id, WsEvent::rejected("Login rejected by user")).await;
+    info!("🚫 User {} rejected login session {}", app_claims.user_id, request.session_id);
+
+    #[derive(serde::Serialize)]
+    struct RejectResponse {
+        session_id: String,
+        status: String,
+    }
+
+    HttpResponse::Ok().json(SuccessResponse::new(RejectResponse {
+        session_id: request.session_id.clone(),
+        status: "rejected".to_string(),
+    }))
+}
@@ -0,0 +1,144 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use crate::backend::AppState;
+use crate::backend::api::qr_login::app_token::verify_app_token;
+use crate::backend::api::qr_login::handle_qr_session::{can_respond, find_session_by_id, update_session_status};
+use crate::backend::api::qr_login::login_context::{match_choices, summarize_user_agent};
+use crate::backend::config::qr_code;
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+use crate::backend::ws_manager::WsManager;
+use crate::backend::ws_protocol::WsEvent;
+
+#[derive(Deserialize, Debug)]
+pub struct ScanQrRequest {
+    pub session_id: String,
+    pub app_token: String,
+}
+
+/// 扫码后返回给 App 的登录上下文
+#[derive(Serialize, Debug)]
+pub struct ScanQrResponse {
+    pub session_id: String,
+    pub status: String,
+    /// 发起登录的 Web 端 IP
+    pub ip: Option<String>,
+    /// 概括后的浏览器与系统，如 "Chrome on Windows"
+    pub browser: String,
+    pub user_agent: Option<String>,
+    pub client_info: Option<String>,
+    pub created_at: i64,
+    pub expires_at: i64,
+    /// 候选数字，用户需选择 Web 端页面上显示的那一个
+    pub match_choices: Vec<i16>,
+}
+
+/// App 端扫码
+///
+/// 将会话标记为已扫码，通知 Web 端，并返回 Web 端的登录上下文与候选数字，
+/// 用户核对来源并选择与 Web 端一致的数字后再调用 `/confirm`。
+pub async fn scan_qr_code(
+    state: web::Data<AppState>,
+    ws_manager: web::Data<WsManager>,
+    request: web::Json<ScanQrRequest>,
+) -> HttpResponse {
+    info!("Received scan request for session: {}", request.session_id);
+
+    let app_claims = match verify_app_token(&state.pg_client, &request.app_token).await {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    let session = match find_session_by_id(&state.pg_client, &request.session_id).await {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            let error_resp = error_response(
+                ErrorCode::QRCodeNotFound,
+                "Session not found",
+            );
+            return HttpResponse::NotFound().json(error_resp);
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Database error: {}", e),
+            );
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    };
+
+    if !can_respond(&session, &app_claims.user_id) {
+        warn!(
+            "User {} tried to scan push login session {} of another user",
+            app_claims.user_id, session.session_id
+        );
+        let error_resp = error_response(
+            ErrorCode::PermissionDenied,
+            "This login request belongs to another user",
+        );
+        return HttpResponse::Forbidden().json(error_resp);
+    }
+
+    if session.status != "pending" && session.status != "scanned" {
+        let error_resp = error_response(
+            ErrorCode::ResourceConflict,
+            "Session is not in valid state",
+        );
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+
+    if session.expires_at < Utc::now().naive_utc() {
+        let error_resp = error_response(
+            ErrorCode::QRCodeExpired,
+            "Session expired",
+        );
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+
+    let first_scan = session.status == "pending";
+    let session = if first_scan {
+        match update_session_status(&state.pg_client, &session.session_id, "scanned").await {
+            Ok(Some(s)) => s,
+            Ok(None) => {
+                let error_resp = error_response(ErrorCode::ResourceConflict, "Session is no longer pending");
+                return HttpResponse::Conflict().json(error_resp);
+            }
+            Err(e) => {
+                let error_resp = error_response(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to update session: {}", e),
+                );
+                return HttpResponse::InternalServerError().json(error_resp);
+            }
+        }
+    } else {
+        session
+    };
+
+    if first_scan {
+        ws_manager.notify(&session.session_id, WsEvent::scanned()).await;
+    }
+    info!("User {} scanned QR session {}", app_claims.user_id, session.session_id);
+
+    let choices = session
+        .match_number
+        .map(|n| match_choices(n, qr_code::MATCH_CHOICES))
+        .unwrap_or_default();
+
+    HttpResponse::Ok().json(SuccessResponse::new(ScanQrResponse {
+        browser: session
+            .user_agent
+            .as_deref()
+            .map(summarize_user_agent)
+            .unwrap_or_else(|| "Unknown browser".to_string()),
+        session_id: session.session_id,
+        status: session.status,
+        ip: session.client_ip,
+        user_agent: session.user_agent,
+        client_info: session.client_info,
+        created_at: session.created_at.and_utc().timestamp(),
+        expires_at: session.expires_at.and_utc().timestamp(),
+        match_choices: choices,
+    }))
+}
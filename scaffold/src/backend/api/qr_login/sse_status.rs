@@ -0,0 +1,152 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use futures::stream::{self, Stream};
+use std::collections::VecDeque;
+use std::time::Duration;
+use tracing::info;
+use crate::backend::AppState;
+use crate::backend::api::qr_login::handle_qr_session::find_session_by_id;
+use crate::backend::api::qr_login::status_sync::resync_events;
+use crate::backend::config::websocket;
+use crate::backend::errors::{ErrorCode, error_response};
+use crate::backend::ws_manager::{Subscription, WsManager};
+use crate::backend::ws_protocol::WsEvent;
+
+#[derive(serde::Deserialize, Debug)]
+pub struct SseQuery {
+    /// 最后收到的事件 ID；也可通过标准的 `Last-Event-ID` 请求头传递
+    pub last_event_id: Option<i64>,
+}
+
+/// Server-Sent Events：推送扫码登录状态（WebSocket 不可用时的回退方案）
+///
+/// 路由: GET /v1/qr-login/events/{session_id}
+///
+/// 事件内容与 WebSocket 消息一致（`event` 为状态名，`data` 为 JSON 消息，持久化事件带 `id`），
+/// 浏览器 `EventSource` 重连时会自动带上 `Last-Event-ID` 补发错过的事件。
+/// 会话进入终态后服务器结束响应。
+pub async fn sse_qr_status(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    ws_manager: web::Data<WsManager>,
+    session_id: web::Path<String>,
+    query: web::Query<SseQuery>,
+) -> HttpResponse {
+    let session_id = session_id.into_inner();
+    let last_event_id = query.last_event_id.or_else(|| {
+        req.headers()
+            .get("Last-Event-ID")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok())
+    });
+
+    info!("📡 SSE connection request for session: {}", session_id);
+
+    match find_session_by_id(&state.pg_client, &session_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            let error_resp = error_response(
+                ErrorCode::QRCodeNotFound,
+                "Session not found",
+            );
+            return HttpResponse::NotFound().json(error_resp);
+        }
+        Err(e) => {
+            let error_resp = error_response(
+                ErrorCode::DatabaseError,
+                format!("Database error: {}", e),
+            );
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    }
+
+    // 先订阅再补发，避免漏掉期间的推送
+    let Some(subscription) = ws_manager.subscribe(&session_id).await else {
+        let error_resp = error_response(
+            ErrorCode::RateLimitExceeded,
+            "Too many connections for this session",
+        );
+        return HttpResponse::TooManyRequests().json(error_resp);
+    };
+
+    let sync = resync_events(&state.pg_client, &session_id, last_event_id).await;
+
+    let mut initial: VecDeque<(Option<i64>, WsEvent)> = VecDeque::new();
+    initial.push_back((None, WsEvent::connected()));
+    initial.extend(sync.events);
+
+    // 会话已结束时发送完补发事件即结束
+    let subscription = if sync.finished { None } else { Some(subscription) };
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        // 关闭 Nginx 等反向代理的响应缓冲
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(event_stream(initial, subscription))
+}
+
+/// 将事件格式化为 SSE 帧
+fn sse_frame(id: Option<i64>, event: &WsEvent) -> String {
+    let mut frame = String::new();
+    if let Some(id) = id {
+        frame.push_str(&format!("id: {}\n", id));
+    }
+    frame.push_str(&format!("event: {}\n", event.name()));
+    frame.push_str(&format!("data: {}\n\n", event.to_message_with_id(id)));
+    frame
+}
+
+struct StreamState {
+    initial: VecDeque<(Option<i64>, WsEvent)>,
+    subscription: Option<Subscription>,
+    keepalive: actix_web::rt::time::Interval,
+}
+
+fn event_stream(
+    initial: VecDeque<(Option<i64>, WsEvent)>,
+    subscription: Option<Subscription>,
+) -> impl Stream<Item = Result<web::Bytes, actix_web::Error>> {
+    let mut keepalive = actix_web::rt::time::interval(Duration::from_secs(websocket::SSE_KEEPALIVE_SECONDS));
+    keepalive.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    let state = StreamState { initial, subscription, keepalive };
+
+    stream::unfold(state, |mut state| async move {
+        if let Some((id, event)) = state.initial.pop_front() {
+            return Some((Ok(web::Bytes::from(sse_frame(id, &event))), state));
+        }
+
+        let subscription = state.subscription.as_mut()?;
+        tokio::select! {
+            received = subscription.recv() => {
+                let broadcast = received?;
+                if broadcast.event.is_terminal() {
+                    // 终态后结束响应
+                    state.subscription = None;
+                }
+                let frame = sse_frame(broadcast.event_id, &broadcast.event);
+                Some((Ok(web::Bytes::from(frame)), state))
+            }
+            _ = state.keepalive.tick() => {
+                // SSE 注释行，防止代理因空闲断开连接
+                Some((Ok(web::Bytes::from_static(b": keep-alive\n\n")), state))
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sse_frame() {
+        let frame = sse_frame(Some(5), &WsEvent::scanned());
+        assert!(frame.starts_with("id: 5\nevent: scanned\ndata: {"));
+        assert!(frame.ends_with("}\n\n"));
+        assert!(frame.contains(r#""id":5"#));
+
+        let frame = sse_frame(None, &WsEvent::connected());
+        assert!(frame.starts_with("event: connected\ndata: "));
+    }
+}
@@ -0,0 +1,51 @@
+use sea_orm::DatabaseConnection;
+use crate::backend::api::qr_login::handle_qr_session::find_session_by_id;
+use crate::backend::errors::ErrorCode;
+use crate::backend::ws_backplane::events_after;
+use crate::backend::ws_protocol::WsEvent;
+
+/// 客户端重连（WebSocket / SSE）时需要补发的事件
+///
+/// 依次包含 `last_event_id` 之后的持久化事件（带 ID），以及会话当前的权威状态（不带 ID）。
+/// 补发与实时推送可能重复，客户端应忽略 ID 不大于已收到 ID 的事件。
+pub struct StatusSync {
+    pub events: Vec<(Option<i64>, WsEvent)>,
+    /// 会话是否已处于终态，为 `true` 时发送完事件后应关闭连接
+    pub finished: bool,
+}
+
+pub async fn resync_events(
+    db: &DatabaseConnection,
+    session_id: &str,
+    last_event_id: Option<i64>,
+) -> StatusSync {
+    let mut events = Vec::new();
+    let mut terminal_sent = false;
+
+    match events_after(db, session_id, last_event_id).await {
+        Ok(stored) => {
+            for (id, event) in stored {
+                terminal_sent |= event.is_terminal();
+                events.push((Some(id), event));
+            }
+        }
+        Err(e) => {
+            let event = WsEvent::error(ErrorCode::DatabaseError, &format!("Failed to load events: {}", e));
+            events.push((None, event));
+        }
+    }
+
+    let current = match find_session_by_id(db, session_id).await {
+        Ok(Some(qr_session)) => WsEvent::from_session(&qr_session),
+        Ok(None) => WsEvent::error(ErrorCode::QRCodeNotFound, "Session not found"),
+        Err(e) => WsEvent::error(ErrorCode::DatabaseError, &format!("Database error: {}", e)),
+    };
+
+    let finished = terminal_sent || current.is_terminal();
+    // 非终态时同样下发当前状态（如已扫码），便于客户端校准界面
+    if !terminal_sent && current != WsEvent::connected() {
+        events.push((None, current));
+    }
+
+    StatusSync { events, finished }
+}
@@ -0,0 +1,51 @@
+use chrono::{Duration, Utc};
+use sea_orm::DbConn;
+use tracing::{error, info};
+use crate::backend::api::qr_login::handle_qr_session::{expire_stale_sessions, purge_sessions_before};
+use crate::backend::config::qr_code;
+use crate::backend::ws_manager::WsManager;
+use crate::backend::ws_protocol::WsEvent;
+
+/// 启动扫码会话后台清理任务
+///
+/// 每隔 `qr_code::SWEEP_INTERVAL_SECONDS` 秒：
+/// 1. 将已过期的 pending / scanned 会话标记为 expired，并通知仍在等待的 WebSocket 连接
+/// 2. 删除过期时间早于保留期（`qr_code::RETENTION_SECONDS`）的会话
+///
+/// 其他模块的过期数据由 `backend::maintenance` 负责清理。
+pub fn spawn_session_sweeper(db: DbConn, ws_manager: WsManager) {
+    info!(
+        "🧹 QR session sweeper started (interval: {}s, retention: {}s)",
+        qr_code::SWEEP_INTERVAL_SECONDS,
+        qr_code::RETENTION_SECONDS
+    );
+
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(std::time::Duration::from_secs(
+            qr_code::SWEEP_INTERVAL_SECONDS,
+        ));
+
+        loop {
+            interval.tick().await;
+            sweep_once(&db, &ws_manager).await;
+        }
+    });
+}
+
+async fn sweep_once(db: &DbConn, ws_manager: &WsManager) {
+    let now = Utc::now().naive_utc();
+
+    match expire_stale_sessions(db, now).await {
+        Ok(expired) => {
+            for session in expired {
+                ws_manager.notify(&session.session_id, WsEvent::expired()).await;
+            }
+        }
+        Err(e) => error!("Failed to expire stale QR sessions: {}", e),
+    }
+
+    let before = now - Duration::seconds(qr_code::RETENTION_SECONDS);
+    if let Err(e) = purge_sessions_before(db, before).await {
+        error!("Failed to purge old QR sessions: {}", e);
+    }
+}
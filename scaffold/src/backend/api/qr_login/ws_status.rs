@@ -1,15 +1,25 @@
 use actix_web::{web, HttpRequest, HttpResponse, Error};
-use actix_ws::Message;
+use actix_ws::{Message, Session};
 use tracing::info;
-use serde_json::json;
+use crate::backend::api::qr_login::handle_qr_session::{find_session_by_id, update_session_status};
+use crate::backend::api::qr_login::login_context::is_initiator;
+use crate::backend::config::websocket;
+use crate::backend::api::qr_login::status_sync::resync_events;
 use crate::backend::ws_manager::WsManager;
+use crate::backend::ws_protocol::{WsCommand, WsEvent};
 use crate::backend::AppState;
 use crate::backend::errors::{ErrorCode, error_response};
-use sea_orm::{EntityTrait, DatabaseConnection};
+use sea_orm::EntityTrait;
 use crate::backend::models::prelude::QrLoginSessions;
 use sea_orm::ColumnTrait;
 use sea_orm::QueryFilter;
-use chrono::Utc;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct WsStatusQuery {
+    /// 客户端最后收到的事件 ID，重连时用于补发之后的事件
+    pub last_event_id: Option<i64>,
+}
 
 /// WebSocket处理：实时推送扫码登录状态
 /// 
@@ -21,50 +31,86 @@ use chrono::Utc;
 /// 3. 保持连接，等待状态更新
 /// 4. App端确认/拒绝后，服务器主动推送状态
 /// 5. 推送完成后自动关闭连接
+///
+/// 断线重连时可带上 `?last_event_id=N`：握手后会补发 N 之后的状态事件，
+/// 并下发会话当前的权威状态；会话已处于终态时发送终态后立即关闭。
+///
+/// 所有消息均为 `WsEvent`（带 `version` 字段）；客户端可发送 `{"command": "cancel" | "resync" | "ping"}`。
+/// 其中 `cancel` 只接受发起该会话的 Web 端连接（见 `is_initiator`）。
+///
+/// 会话过期由后台清理任务（`sweeper`）统一检测并推送，连接本身不再轮询数据库。
 pub async fn ws_qr_status(
     req: HttpRequest,
     session_id: web::Path<String>,
     stream: web::Payload,
+    query: web::Query<WsStatusQuery>,
     ws_manager: web::Data<WsManager>,
     state: web::Data<AppState>,
 ) -> Result<HttpResponse, Error> {
     let session_id = session_id.into_inner();
+    let last_event_id = query.last_event_id;
     
     info!("🔌 WebSocket connection request for session: {}", session_id);
     
     // 验证session是否存在
-    let session_exists = QrLoginSessions::find()
+    let qr_session = QrLoginSessions::find()
         .filter(crate::backend::models::qr_login_sessions::Column::SessionId.eq(&session_id))
         .one(&state.pg_client)
         .await
         .ok()
-        .flatten()
-        .is_some();
+        .flatten();
     
-    if !session_exists {
+    let Some(qr_session) = qr_session else {
         info!("❌ Session not found: {}", session_id);
         let error_resp = error_response(
             ErrorCode::QRCodeNotFound,
             "Session not found"
         );
         return Ok(HttpResponse::NotFound().json(error_resp));
-    }
+    };
+
+    // 会话已结束（确认/拒绝/过期）时不会再有推送，补发事件后直接关闭
+    let already_finished = WsEvent::from_session(&qr_session).is_terminal();
+    let can_cancel = is_initiator(&req, &qr_session);
     
+    // 同一会话的订阅者数量有上限（如多个标签页同时等待）
+    if ws_manager.subscriber_count(&session_id).await >= websocket::MAX_SUBSCRIBERS_PER_SESSION {
+        info!("❌ Too many subscribers for session: {}", session_id);
+        let error_resp = error_response(
+            ErrorCode::RateLimitExceeded,
+            "Too many connections for this session"
+        );
+        return Ok(HttpResponse::TooManyRequests().json(error_resp));
+    }
+
     // 建立WebSocket连接
     let (response, session, mut msg_stream) = actix_ws::handle(&req, stream)?;
     
     info!("✅ WebSocket connected for session: {}", session_id);
     
-    // 将连接添加到管理器
-    ws_manager.add_connection(session_id.clone(), session.clone()).await;
+    if already_finished {
+        info!("⏰ Session already finished, closing WebSocket: {}", session_id);
+        let mut session = session;
+        sync_session(&mut session, &state, &session_id, last_event_id).await;
+        return Ok(response);
+    }
+
+    // 将连接添加到管理器（并发连接可能在检查后超过上限）
+    let Some(connection_id) = ws_manager.add_connection(session_id.clone(), session.clone()).await else {
+        let mut session = session;
+        let event = WsEvent::error(ErrorCode::RateLimitExceeded, "Too many connections for this session");
+        let _ = session.text(event.to_message()).await;
+        let _ = session.close(None).await;
+        return Ok(response);
+    };
     
-    // 发送连接成功消息 - 使用 serde_json 防止 XSS
+    // 发送连接成功消息，并补发断线期间错过的事件（先注册再补发，避免漏掉期间的推送）
     let mut session_clone = session.clone();
-    let connect_message = json!({
-        "status": "connected",
-        "message": "Waiting for confirmation"
-    });
-    let _ = session_clone.text(connect_message.to_string()).await;
+    let _ = session_clone.text(WsEvent::connected().to_message()).await;
+    if !sync_session(&mut session_clone, &state, &session_id, last_event_id).await {
+        ws_manager.remove_connection(&session_id, connection_id).await;
+        return Ok(response);
+    }
     
     // 启动心跳和消息处理任务
     let ws_manager_clone = ws_manager.clone();
@@ -74,7 +120,6 @@ pub async fn ws_qr_status(
     actix_web::rt::spawn(async move {
         let mut session = session;
         let mut heartbeat_interval = actix_web::rt::time::interval(std::time::Duration::from_secs(30));
-        let mut timeout_check_interval = actix_web::rt::time::interval(std::time::Duration::from_secs(60));
         
         loop {
             tokio::select! {
@@ -92,6 +137,17 @@ pub async fn ws_qr_status(
                         }
                         Message::Text(text) => {
                             info!("📩 Received message from client: {}", text);
+                            let keep_open = handle_command(
+                                &text,
+                                &session_id_clone,
+                                &mut session,
+                                &state_clone,
+                                &ws_manager_clone,
+                                can_cancel,
+                            ).await;
+                            if !keep_open {
+                                break;
+                            }
                         }
                         _ => {}
                     }
@@ -103,42 +159,105 @@ pub async fn ws_qr_status(
                         break;
                     }
                 }
-                // 超时检测（60秒）
-                _ = timeout_check_interval.tick() => {
-                    // 检查session是否过期
-                    let expired = check_session_expired(&state_clone.pg_client, &session_id_clone).await;
-                    if expired {
-                        info!("⏰ Session expired, closing WebSocket: {}", session_id_clone);
-                        let _ = session.text(r#"{"status":"expired","message":"QR code expired"}"#).await;
-                        let _ = session.close(None).await;
-                        break;
-                    }
-                }
             }
         }
         
         // 连接断开，从管理器中移除
-        ws_manager_clone.remove_connection(&session_id_clone).await;
+        ws_manager_clone.remove_connection(&session_id_clone, connection_id).await;
     });
     
     Ok(response)
 }
 
-/// 检查session是否过期
-async fn check_session_expired(db: &DatabaseConnection, session_id: &str) -> bool {
-    match QrLoginSessions::find()
-        .filter(crate::backend::models::qr_login_sessions::Column::SessionId.eq(session_id))
-        .one(db)
-        .await
-    {
-        Ok(Some(session)) => {
-            let now = Utc::now().naive_utc();
-            // session已过期
-            session.expires_at < now
+/// 处理客户端命令，返回是否保持连接
+///
+/// `can_cancel` 表示该连接来自发起会话的 Web 端，只有它可以取消登录
+async fn handle_command(
+    text: &str,
+    session_id: &str,
+    session: &mut Session,
+    state: &AppState,
+    ws_manager: &WsManager,
+    can_cancel: bool,
+) -> bool {
+    let command = match WsCommand::parse(text) {
+        Ok(command) => command,
+        Err(e) => {
+            let _ = session.text(WsEvent::error(ErrorCode::InvalidParams, &e).to_message()).await;
+            return true;
+        }
+    };
+
+    match command {
+        WsCommand::Ping => session.text(WsEvent::ping().to_message()).await.is_ok(),
+        WsCommand::Resync { last_event_id } => {
+            sync_session(session, state, session_id, last_event_id).await
+        }
+        WsCommand::Cancel if !can_cancel => {
+            info!("⛔ Rejected cancel from non-initiator for session: {}", session_id);
+            let event = WsEvent::error(ErrorCode::PermissionDenied, "Only the initiating client can cancel this session");
+            session.text(event.to_message()).await.is_ok()
+        }
+        WsCommand::Cancel => {
+            let qr_session = match find_session_by_id(&state.pg_client, session_id).await {
+                Ok(Some(qr_session)) if qr_session.status == "pending" || qr_session.status == "scanned" => qr_session,
+                Ok(Some(qr_session)) => {
+                    let event = WsEvent::from_session(&qr_session);
+                    let _ = session.text(event.to_message()).await;
+                    return !event.is_terminal();
+                }
+                Ok(None) => {
+                    let _ = session.text(WsEvent::error(ErrorCode::QRCodeNotFound, "Session not found").to_message()).await;
+                    return true;
+                }
+                Err(e) => {
+                    let event = WsEvent::error(ErrorCode::DatabaseError, &format!("Database error: {}", e));
+                    let _ = session.text(event.to_message()).await;
+                    return true;
+                }
+            };
+
+            match update_session_status(&state.pg_client, &qr_session.session_id, "rejected").await {
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    let event = WsEvent::error(ErrorCode::ResourceConflict, "Session is no longer pending");
+                    let _ = session.text(event.to_message()).await;
+                    return true;
+                }
+                Err(e) => {
+                    let event = WsEvent::error(ErrorCode::DatabaseError, &format!("Failed to cancel session: {}", e));
+                    let _ = session.text(event.to_message()).await;
+                    return true;
+                }
+            }
+
+            info!("🚫 QR login cancelled by client for session: {}", session_id);
+            // 通知所有订阅者（包括当前连接）并关闭连接
+            ws_manager.notify(session_id, WsEvent::rejected("Login cancelled")).await;
+            false
         }
-        _ => {
-            // session不存在或查询失败，视为过期
-            true
+    }
+}
+
+/// 补发 `last_event_id` 之后的事件，并下发会话当前的权威状态
+///
+/// 会话已处于终态时发送终态并关闭连接，返回 `false`；否则返回是否发送成功。
+async fn sync_session(
+    session: &mut Session,
+    state: &AppState,
+    session_id: &str,
+    last_event_id: Option<i64>,
+) -> bool {
+    let sync = resync_events(&state.pg_client, session_id, last_event_id).await;
+    for (id, event) in &sync.events {
+        if session.text(event.to_message_with_id(*id)).await.is_err() {
+            return false;
         }
     }
+
+    if sync.finished {
+        let _ = session.clone().close(None).await;
+        return false;
+    }
+    true
 }
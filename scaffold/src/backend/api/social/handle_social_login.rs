@@ -0,0 +1,118 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::sea_query::{Expr, Func};
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set};
+use tracing::info;
+use crate::backend::config::social_login;
+use crate::backend::models::{social_login_states, user_identities, users};
+use crate::backend::models::prelude::{SocialLoginStates, UserIdentities, Users};
+
+pub async fn insert_state(
+    db: &DatabaseConnection,
+    state_hash: &str,
+    provider: &str,
+    code_verifier: &str,
+) -> Result<(), DbErr> {
+    let now = Utc::now().naive_utc();
+    let new_state = social_login_states::ActiveModel {
+        state_hash: Set(state_hash.to_string()),
+        provider: Set(provider.to_string()),
+        code_verifier: Set(code_verifier.to_string()),
+        expires_at: Set(now + Duration::seconds(social_login::STATE_TTL_SECONDS)),
+        created_at: Set(now),
+    };
+
+    SocialLoginStates::insert(new_state).exec_without_returning(db).await?;
+    Ok(())
+}
+
+/// 一次性消费 state，返回 `None` 表示 state 不存在、已使用、已过期或不属于该提供方
+pub async fn consume_state(
+    db: &DatabaseConnection,
+    state_hash: &str,
+    provider: &str,
+) -> Result<Option<social_login_states::Model>, DbErr> {
+    let Some(state) = SocialLoginStates::find_by_id(state_hash.to_string()).one(db).await? else {
+        return Ok(None);
+    };
+
+    // 删除成功的请求才能继续，保证并发回调中只有一个生效
+    let deleted = SocialLoginStates::delete_by_id(state_hash.to_string()).exec(db).await?;
+    let valid = deleted.rows_affected > 0
+        && state.provider == provider
+        && state.expires_at > Utc::now().naive_utc();
+    Ok(valid.then_some(state))
+}
+
+/// 删除已过期的 state
+pub async fn purge_expired_states(db: &DatabaseConnection, now: NaiveDateTime) -> Result<u64, DbErr> {
+    let result = SocialLoginStates::delete_many()
+        .filter(social_login_states::Column::ExpiresAt.lt(now))
+        .exec(db)
+        .await?;
+
+    if result.rows_affected > 0 {
+        info!("Purged {} expired social login states", result.rows_affected);
+    }
+    Ok(result.rows_affected)
+}
+
+pub async fn find_identity(
+    db: &DatabaseConnection,
+    provider: &str,
+    subject: &str,
+) -> Result<Option<user_identities::Model>, DbErr> {
+    UserIdentities::find()
+        .filter(user_identities::Column::Provider.eq(provider))
+        .filter(user_identities::Column::Subject.eq(subject))
+        .one(db)
+        .await
+}
+
+pub async fn insert_identity(
+    db: &DatabaseConnection,
+    user_id: &str,
+    provider: &str,
+    subject: &str,
+    email: Option<&str>,
+) -> Result<user_identities::Model, DbErr> {
+    let now = Utc::now().naive_utc();
+    let identity = user_identities::ActiveModel {
+        user_id: Set(user_id.to_string()),
+        provider: Set(provider.to_string()),
+        subject: Set(subject.to_string()),
+        email: Set(email.map(str::to_string)),
+        created_at: Set(now),
+        last_login_at: Set(Some(now)),
+        ..Default::default()
+    };
+
+    let inserted = identity.insert(db).await?;
+    info!("Linked {} identity {} to user {}", provider, subject, user_id);
+    Ok(inserted)
+}
+
+/// 更新最近登录时间与提供方返回的邮箱
+pub async fn touch_identity(db: &DatabaseConnection, id: i64, email: Option<&str>) -> Result<(), DbErr> {
+    UserIdentities::update_many()
+        .col_expr(user_identities::Column::LastLoginAt, Utc::now().naive_utc().into())
+        .col_expr(user_identities::Column::Email, email.map(str::to_string).into())
+        .filter(user_identities::Column::Id.eq(id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn find_user(db: &DatabaseConnection, user_id: &str) -> Result<Option<users::Model>, DbErr> {
+    Users::find()
+        .filter(users::Column::UserId.eq(user_id))
+        .one(db)
+        .await
+}
+
+/// 按邮箱查找用户，邮箱不区分大小写
+pub async fn find_user_by_email(db: &DatabaseConnection, email: &str) -> Result<Option<users::Model>, DbErr> {
+    Users::find()
+        .filter(Expr::expr(Func::lower(Expr::col(users::Column::Email))).eq(Func::lower(Expr::val(email))))
+        .one(db)
+        .await
+}
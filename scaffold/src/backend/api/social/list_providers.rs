@@ -0,0 +1,29 @@
+use actix_web::HttpResponse;
+use serde::Serialize;
+use crate::backend::config::social_login_config;
+use crate::backend::errors::SuccessResponse;
+
+#[derive(Serialize, Debug)]
+pub struct ProviderInfo {
+    pub name: String,
+    pub display_name: String,
+    /// 登录按钮跳转的地址
+    pub authorize_path: String,
+}
+
+/// 已启用的第三方登录提供方
+///
+/// 路由: GET /v1/auth/social/providers
+pub async fn list_providers() -> HttpResponse {
+    let providers: Vec<ProviderInfo> = social_login_config()
+        .providers
+        .iter()
+        .map(|p| ProviderInfo {
+            name: p.name.clone(),
+            display_name: p.display_name.clone(),
+            authorize_path: format!("/v1/auth/social/{}/authorize", p.name),
+        })
+        .collect();
+
+    HttpResponse::Ok().json(SuccessResponse::new(providers))
+}
@@ -0,0 +1,20 @@
+mod handle_social_login;
+mod provider_client;
+mod list_providers;
+mod social_authorize;
+mod social_callback;
+
+use actix_web::{Scope, web};
+use crate::backend::api::social::list_providers::list_providers;
+use crate::backend::api::social::social_authorize::social_authorize;
+use crate::backend::api::social::social_callback::social_callback;
+
+pub use handle_social_login::purge_expired_states;
+
+/// 第三方登录接口（挂载在 `/v1/auth` 下）
+pub fn social_scope() -> Scope {
+    web::scope("/social")
+        .route("/providers", web::get().to(list_providers))
+        .route("/{provider}/authorize", web::get().to(social_authorize))
+        .route("/{provider}/callback", web::get().to(social_callback))
+}
@@ -0,0 +1,237 @@
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use reqwest::header::ACCEPT;
+use serde_json::Value;
+use url::Url;
+use crate::backend::config::{social_login, SocialProvider};
+use crate::backend::errors::{AppError, ErrorCode};
+
+/// 提供方返回的用户资料
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SocialProfile {
+    pub subject: String,
+    pub email: Option<String>,
+    /// 提供方声明邮箱已验证；只有已验证的邮箱才用于关联已有账号
+    pub email_verified: bool,
+}
+
+static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(social_login::HTTP_TIMEOUT_SECONDS))
+        .user_agent(social_login::USER_AGENT)
+        .build()
+        .expect("Failed to build HTTP client")
+});
+
+fn provider_error(provider: &SocialProvider, msg: impl std::fmt::Display) -> AppError {
+    AppError::custom(ErrorCode::NetworkError, format!("{}: {}", provider.display_name, msg))
+}
+
+/// 构造跳转到提供方的授权地址（授权码模式 + PKCE）
+pub fn authorize_url(
+    provider: &SocialProvider,
+    redirect_uri: &str,
+    state: &str,
+    code_challenge: &str,
+) -> Result<String, AppError> {
+    let mut url = Url::parse(&provider.authorize_url)
+        .map_err(|e| AppError::custom(ErrorCode::ConfigurationError, format!("Invalid authorize_url: {}", e)))?;
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &provider.client_id)
+        .append_pair("redirect_uri", redirect_uri)
+        .append_pair("scope", &provider.scopes)
+        .append_pair("state", state)
+        .append_pair("code_challenge", code_challenge)
+        .append_pair("code_challenge_method", "S256");
+    Ok(url.to_string())
+}
+
+/// 用授权码向提供方换取 access token
+pub async fn exchange_code(
+    provider: &SocialProvider,
+    code: &str,
+    redirect_uri: &str,
+    code_verifier: &str,
+) -> Result<String, AppError> {
+    let response = HTTP_CLIENT
+        .post(&provider.token_url)
+        .header(ACCEPT, "application/json")
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("client_id", provider.client_id.as_str()),
+            ("client_secret", provider.client_secret.as_str()),
+            ("code_verifier", code_verifier),
+        ])
+        .send()
+        .await
+        .map_err(|e| provider_error(provider, format!("token request failed: {}", e)))?;
+
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| provider_error(provider, format!("invalid token response: {}", e)))?;
+
+    // GitHub 出错时也返回 200，只能通过 error 字段判断
+    if let Some(error) = body.get("error").and_then(Value::as_str) {
+        return Err(provider_error(provider, format!("token request rejected: {}", error)));
+    }
+
+    body.get("access_token")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| provider_error(provider, "token response has no access_token"))
+}
+
+/// 获取提供方的用户资料
+pub async fn fetch_profile(provider: &SocialProvider, access_token: &str) -> Result<SocialProfile, AppError> {
+    let response = HTTP_CLIENT
+        .get(&provider.userinfo_url)
+        .header(ACCEPT, "application/json")
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .map_err(|e| provider_error(provider, format!("userinfo request failed: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(provider_error(provider, format!("userinfo request returned {}", response.status())));
+    }
+
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| provider_error(provider, format!("invalid userinfo response: {}", e)))?;
+
+    parse_profile(provider, &body).ok_or_else(|| provider_error(provider, "userinfo has no subject"))
+}
+
+/// 按提供方的字段映射解析用户资料
+pub fn parse_profile(provider: &SocialProvider, body: &Value) -> Option<SocialProfile> {
+    // 标识可能是数字（GitHub 的 id）
+    let subject = match body.get(&provider.subject_field)? {
+        Value::String(s) if !s.is_empty() => s.clone(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+
+    let email = body
+        .get(&provider.email_field)
+        .and_then(Value::as_str)
+        .map(|e| e.trim().to_ascii_lowercase())
+        .filter(|e| !e.is_empty());
+
+    let email_verified = email.is_some()
+        && provider
+            .email_verified_field
+            .as_ref()
+            .and_then(|field| body.get(field))
+            .is_some_and(|v| v.as_bool() == Some(true) || v.as_str() == Some("true"));
+
+    Some(SocialProfile { subject, email, email_verified })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{web, App, HttpResponse, HttpServer};
+
+    fn provider(base: &str) -> SocialProvider {
+        SocialProvider {
+            name: "mock".into(),
+            display_name: "Mock".into(),
+            client_id: "client".into(),
+            client_secret: "secret".into(),
+            authorize_url: format!("{}/authorize", base),
+            token_url: format!("{}/token", base),
+            userinfo_url: format!("{}/userinfo", base),
+            scopes: "openid email".into(),
+            subject_field: "sub".into(),
+            email_field: "email".into(),
+            email_verified_field: Some("email_verified".into()),
+        }
+    }
+
+    async fn mock_token(form: web::Form<std::collections::HashMap<String, String>>) -> HttpResponse {
+        let valid = form.get("code").map(String::as_str) == Some("good-code")
+            && form.get("client_secret").map(String::as_str) == Some("secret")
+            && form.get("code_verifier").is_some();
+        if valid {
+            HttpResponse::Ok().json(serde_json::json!({ "access_token": "mock-token", "token_type": "Bearer" }))
+        } else {
+            HttpResponse::Ok().json(serde_json::json!({ "error": "bad_verification_code" }))
+        }
+    }
+
+    async fn mock_userinfo(req: actix_web::HttpRequest) -> HttpResponse {
+        let authorized = req
+            .headers()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            == Some("Bearer mock-token");
+        if !authorized {
+            return HttpResponse::Unauthorized().finish();
+        }
+        HttpResponse::Ok().json(serde_json::json!({
+            "sub": "mock-user-1",
+            "email": "Alice@Example.com",
+            "email_verified": true,
+        }))
+    }
+
+    /// 在本地随机端口启动模拟提供方
+    fn start_mock_provider() -> String {
+        let server = HttpServer::new(|| {
+            App::new()
+                .route("/token", web::post().to(mock_token))
+                .route("/userinfo", web::get().to(mock_userinfo))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .expect("Failed to bind mock provider");
+        let addr = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+        format!("http://{}", addr)
+    }
+
+    #[actix_web::test]
+    async fn test_login_against_mock_provider() {
+        let provider = provider(&start_mock_provider());
+
+        let access_token = exchange_code(&provider, "good-code", "http://localhost/cb", "verifier")
+            .await
+            .expect("code exchange should succeed");
+        let profile = fetch_profile(&provider, &access_token).await.expect("userinfo should succeed");
+
+        assert_eq!(profile, SocialProfile {
+            subject: "mock-user-1".into(),
+            email: Some("alice@example.com".into()),
+            email_verified: true,
+        });
+
+        assert!(exchange_code(&provider, "bad-code", "http://localhost/cb", "verifier").await.is_err());
+        assert!(fetch_profile(&provider, "wrong-token").await.is_err());
+    }
+
+    #[test]
+    fn test_parse_profile_mapping() {
+        let mut github = provider("https://github.example");
+        github.subject_field = "id".into();
+        github.email_verified_field = None;
+
+        let profile = parse_profile(&github, &serde_json::json!({ "id": 42, "email": "a@b.c" })).unwrap();
+        assert_eq!(profile.subject, "42");
+        assert!(!profile.email_verified, "unmapped verification must not be trusted");
+
+        assert!(parse_profile(&github, &serde_json::json!({ "login": "octocat" })).is_none());
+    }
+
+    #[test]
+    fn test_authorize_url() {
+        let url = authorize_url(&provider("https://idp.example.com"), "https://app/cb", "st", "ch").unwrap();
+        assert!(url.starts_with("https://idp.example.com/authorize?response_type=code&client_id=client"));
+        assert!(url.contains("redirect_uri=https%3A%2F%2Fapp%2Fcb"));
+        assert!(url.contains("code_challenge_method=S256"));
+    }
+}
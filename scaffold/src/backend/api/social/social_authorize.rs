@@ -0,0 +1,74 @@
+use actix_web::cookie::{time, Cookie, SameSite};
+use actix_web::{http::header, web, HttpRequest, HttpResponse, ResponseError};
+use tracing::info;
+use crate::backend::AppState;
+use crate::backend::api::oauth::{generate_secret, public_base_url, s256_challenge};
+use crate::backend::api::social::handle_social_login::insert_state;
+use crate::backend::api::social::provider_client::authorize_url;
+use crate::backend::config::{social_login, social_login_config, SocialProvider};
+use crate::backend::errors::{ErrorCode, error_response};
+use crate::backend::utils::hash::hash_str;
+
+/// 本站在提供方登记的回调地址
+pub fn callback_url(req: &HttpRequest, provider: &SocialProvider) -> String {
+    format!("{}/v1/auth/social/{}/callback", public_base_url(req), provider.name)
+}
+
+/// 把 state 绑定到发起登录的浏览器：cookie 中保存 state 的哈希，回调时比对
+///
+/// 提供方回调是跨站的顶层跳转，需要 `SameSite=Lax` 才会携带 cookie
+pub fn state_cookie(state_hash: &str, secure: bool) -> Cookie<'static> {
+    Cookie::build(social_login::STATE_COOKIE, state_hash.to_string())
+        .path(social_login::STATE_COOKIE_PATH)
+        .http_only(true)
+        .secure(secure)
+        .same_site(SameSite::Lax)
+        .max_age(time::Duration::seconds(social_login::STATE_TTL_SECONDS))
+        .finish()
+}
+
+/// 发起第三方登录
+///
+/// 路由: GET /v1/auth/social/{provider}/authorize
+///
+/// 生成一次性 state 与 PKCE verifier 并保存，state 的哈希同时写入 cookie，
+/// 然后重定向到提供方的授权页面。
+pub async fn social_authorize(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    provider: web::Path<String>,
+) -> HttpResponse {
+    let Some(provider) = social_login_config().provider(&provider) else {
+        let error_resp = error_response(ErrorCode::NotFound, "Unknown login provider");
+        return HttpResponse::NotFound().json(error_resp);
+    };
+
+    let login_state = generate_secret();
+    let code_verifier = generate_secret();
+
+    let state_hash = hash_str(&login_state);
+    if let Err(e) = insert_state(&state.pg_client, &state_hash, &provider.name, &code_verifier).await {
+        let error_resp = error_response(
+            ErrorCode::DatabaseError,
+            format!("Failed to create login state: {}", e),
+        );
+        return HttpResponse::InternalServerError().json(error_resp);
+    }
+
+    let callback = callback_url(&req, provider);
+    let location = match authorize_url(
+        provider,
+        &callback,
+        &login_state,
+        &s256_challenge(&code_verifier),
+    ) {
+        Ok(url) => url,
+        Err(e) => return e.error_response(),
+    };
+
+    info!("🌐 Redirecting to {} for social login", provider.display_name);
+    HttpResponse::Found()
+        .insert_header((header::LOCATION, location))
+        .cookie(state_cookie(&state_hash, callback.starts_with("https://")))
+        .finish()
+}
@@ -0,0 +1,312 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse, ResponseError};
+use chrono::Utc;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, Set};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use crate::backend::AppState;
+use crate::backend::api::logs::handle_user_logs::insert_user_log;
+use crate::backend::api::social::handle_social_login::{
+    consume_state, find_identity, find_user, find_user_by_email, insert_identity, touch_identity,
+};
+use crate::backend::api::social::provider_client::{exchange_code, fetch_profile, SocialProfile};
+use crate::backend::api::social::social_authorize::{callback_url, state_cookie};
+use crate::backend::config::{jwt, social_login, social_login_config, SocialProvider};
+use crate::backend::errors::{AppError, ErrorCode, SuccessResponse};
+use crate::backend::models::sea_orm_active_enums::{LogActionType, UserRoleType};
+use crate::backend::models::users;
+use crate::backend::utils::extractors::{client_ip, user_agent};
+use crate::backend::utils::hash::{constant_time_eq, hash_password, hash_str};
+use crate::backend::utils::jwt::{create_jwt, Claims};
+use crate::backend::ws_manager::WsManager;
+use crate::backend::ws_protocol::UserEvent;
+
+#[derive(Deserialize, Debug)]
+pub struct CallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    /// 用户在提供方拒绝授权等情况
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SocialLoginResponse {
+    pub token: String,
+    pub user_id: String,
+    pub provider: String,
+    /// 本次登录自动注册了新用户
+    pub is_new_user: bool,
+}
+
+/// 第三方登录回调
+///
+/// 路由: GET /v1/auth/social/{provider}/callback
+///
+/// 校验 state（须与发起登录时写入浏览器 cookie 的一致）后用授权码换取提供方的 access token
+/// 并获取用户资料，然后：
+/// 1. 该外部身份已关联用户时直接登录
+/// 2. 否则提供方邮箱已验证、且与本站已验证邮箱一致时，关联到该用户
+/// 3. 否则自动注册新用户并关联
+///
+/// 配置了 `SOCIAL_LOGIN_REDIRECT_URL` 时把结果放在 URL fragment 中跳转到前端，否则返回 JSON。
+pub async fn social_callback(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    ws_manager: web::Data<WsManager>,
+    provider: web::Path<String>,
+    query: web::Query<CallbackQuery>,
+) -> HttpResponse {
+    let result = match social_login_config().provider(&provider) {
+        Some(provider) => complete_login(&req, &state.pg_client, &ws_manager, provider, &query).await,
+        None => Err(AppError::not_found("Unknown login provider")),
+    };
+
+    let redirect_url = social_login_config().redirect_url.as_deref();
+    let mut response = match (result, redirect_url) {
+        (Ok(login), Some(url)) => {
+            let fragment = url::form_urlencoded::Serializer::new(String::new())
+                .append_pair("token", &login.token)
+                .append_pair("provider", &login.provider)
+                .append_pair("is_new_user", &login.is_new_user.to_string())
+                .finish();
+            HttpResponse::Found()
+                .insert_header((header::LOCATION, format!("{}#{}", url, fragment)))
+                .finish()
+        }
+        (Ok(login), None) => HttpResponse::Ok().json(SuccessResponse::new(login)),
+        (Err(e), Some(url)) => {
+            warn!("Social login via {} failed: {}", provider, e);
+            let fragment = url::form_urlencoded::Serializer::new(String::new())
+                .append_pair("error", &e.message())
+                .finish();
+            HttpResponse::Found()
+                .insert_header((header::LOCATION, format!("{}#{}", url, fragment)))
+                .finish()
+        }
+        (Err(e), None) => {
+            warn!("Social login via {} failed: {}", provider, e);
+            e.error_response()
+        }
+    };
+
+    // state 只能使用一次，无论成功与否都清除 cookie
+    if let Err(e) = response.add_removal_cookie(&state_cookie("", false)) {
+        warn!("Failed to clear social login state cookie: {}", e);
+    }
+    response
+}
+
+async fn complete_login(
+    req: &HttpRequest,
+    db: &DatabaseConnection,
+    ws_manager: &WsManager,
+    provider: &SocialProvider,
+    query: &CallbackQuery,
+) -> Result<SocialLoginResponse, AppError> {
+    if let Some(error) = &query.error {
+        return Err(AppError::custom(ErrorCode::LoginFailed, format!("Authorization failed: {}", error)));
+    }
+    let (Some(code), Some(login_state)) = (query.code.as_deref(), query.state.as_deref()) else {
+        return Err(AppError::validation("code and state are required"));
+    };
+
+    // state 必须来自发起登录的同一浏览器，否则可能是攻击者诱导受害者登录攻击者的账号
+    let state_hash = hash_str(login_state);
+    let bound = req
+        .cookie(social_login::STATE_COOKIE)
+        .is_some_and(|cookie| constant_time_eq(cookie.value(), &state_hash));
+    if !bound {
+        return Err(AppError::custom(ErrorCode::InvalidParams, "Login state does not belong to this browser"));
+    }
+
+    let stored = consume_state(db, &state_hash, &provider.name)
+        .await?
+        .ok_or_else(|| AppError::custom(ErrorCode::ResourceExpired, "Login state is invalid or expired"))?;
+
+    let access_token = exchange_code(provider, code, &callback_url(req, provider), &stored.code_verifier).await?;
+    let profile = fetch_profile(provider, &access_token).await?;
+
+    let (user, is_new_user) = resolve_user(db, provider, &profile).await?;
+    if user.is_active == Some(false) {
+        return Err(AppError::custom(ErrorCode::PermissionDenied, "User account is disabled"));
+    }
+
+    let claims = Claims {
+        user_id: user.user_id.clone(),
+        username: user.user_id.clone(),
+        role: Some(user.role.clone()),
+        exp: Utc::now().timestamp() as usize + jwt::DEFAULT_EXPIRATION_SECONDS,
+        ..Default::default()
+    };
+    let token = create_jwt(&claims);
+
+    let ip = client_ip(req);
+    if let Err(e) = insert_user_log(db, &user.user_id, LogActionType::Login, &ip, user_agent(req).as_deref()).await {
+        warn!("Failed to record social login for {}: {}", user.user_id, e);
+    }
+    ws_manager.notify_user(&user.user_id, UserEvent::new_login(social_login::LOGIN_METHOD, Some(&ip), None)).await;
+
+    info!("✅ User {} logged in via {}", user.user_id, provider.display_name);
+    Ok(SocialLoginResponse {
+        token,
+        user_id: user.user_id,
+        provider: provider.name.clone(),
+        is_new_user,
+    })
+}
+
+/// 找到外部身份对应的用户，必要时关联已有用户或自动注册
+async fn resolve_user(
+    db: &DatabaseConnection,
+    provider: &SocialProvider,
+    profile: &SocialProfile,
+) -> Result<(users::Model, bool), AppError> {
+    if let Some(identity) = find_identity(db, &provider.name, &profile.subject).await? {
+        touch_identity(db, identity.id, profile.email.as_deref()).await?;
+        let user = find_user(db, &identity.user_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Linked user no longer exists"))?;
+        return Ok((user, false));
+    }
+
+    let verified_email = profile.email.as_deref().filter(|_| profile.email_verified);
+    let existing = match verified_email {
+        Some(email) => find_user_by_email(db, email).await?,
+        None => None,
+    };
+
+    match link_decision(profile, existing.as_ref()) {
+        LinkDecision::Link(user) => {
+            insert_identity(db, &user.user_id, &provider.name, &profile.subject, profile.email.as_deref()).await?;
+            info!("Linked {} account to existing user {} by verified email", provider.display_name, user.user_id);
+            Ok((user.clone(), false))
+        }
+        LinkDecision::Register { email } => {
+            let user = register_user(db, provider, profile, email).await?;
+            insert_identity(db, &user.user_id, &provider.name, &profile.subject, profile.email.as_deref()).await?;
+            Ok((user, true))
+        }
+    }
+}
+
+/// 外部身份尚未关联用户时的处理方式
+#[derive(Debug, PartialEq)]
+enum LinkDecision<'a> {
+    /// 关联到邮箱一致的已有用户
+    Link(&'a users::Model),
+    /// 注册新用户，`email` 为新用户记录的邮箱
+    Register { email: Option<&'a str> },
+}
+
+/// `existing` 为按提供方邮箱找到的本站用户
+///
+/// 只有双方都验证过的邮箱才能证明是同一个人；邮箱已被其他账号占用（未验证）时，新用户不记录邮箱
+fn link_decision<'a>(profile: &'a SocialProfile, existing: Option<&'a users::Model>) -> LinkDecision<'a> {
+    let Some(email) = profile.email.as_deref().filter(|_| profile.email_verified) else {
+        return LinkDecision::Register { email: None };
+    };
+    match existing {
+        Some(user) if user.is_verified == Some(true) => LinkDecision::Link(user),
+        Some(_) => LinkDecision::Register { email: None },
+        None => LinkDecision::Register { email: Some(email) },
+    }
+}
+
+async fn register_user(
+    db: &DatabaseConnection,
+    provider: &SocialProvider,
+    profile: &SocialProfile,
+    email: Option<&str>,
+) -> Result<users::Model, AppError> {
+    let mut user_id = format!("{}_{}", provider.name, profile.subject);
+    if find_user(db, &user_id).await?.is_some() {
+        let suffix: String = thread_rng().sample_iter(&Alphanumeric).take(6).map(char::from).collect();
+        user_id = format!("{}_{}", user_id, suffix.to_ascii_lowercase());
+    }
+
+    // 第三方登录的用户没有密码，设置无法猜测的随机密码
+    let random_password: String = thread_rng().sample_iter(&Alphanumeric).take(32).map(char::from).collect();
+    let password_hash = hash_password(&random_password)
+        .map_err(|e| AppError::internal(format!("Failed to hash password: {}", e)))?;
+
+    let now = Utc::now().naive_utc();
+    let new_user = users::ActiveModel {
+        user_id: Set(user_id.clone()),
+        password_hash: Set(password_hash),
+        email: Set(email.map(str::to_string)),
+        role: Set(UserRoleType::User),
+        is_active: Set(Some(true)),
+        is_verified: Set(Some(email.is_some())),
+        // 同一 user_id 可能属于已删除的账户，创建之前签发的 token 一律无效
+        sessions_revoked_at: Set(Some(now)),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    };
+
+    let user = new_user.insert(db).await?;
+    info!("✅ Registered user {} via {}", user_id, provider.display_name);
+    Ok(user)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(email: Option<&str>, email_verified: bool) -> SocialProfile {
+        SocialProfile {
+            subject: "12345".to_string(),
+            email: email.map(str::to_string),
+            email_verified,
+        }
+    }
+
+    fn user(is_verified: bool) -> users::Model {
+        let now = Utc::now().naive_utc();
+        users::Model {
+            id: 1,
+            user_id: "alice".to_string(),
+            password_hash: String::new(),
+            email: Some("alice@example.com".to_string()),
+            phone: None,
+            role: UserRoleType::User,
+            is_active: Some(true),
+            is_verified: Some(is_verified),
+            created_at: now,
+            updated_at: now,
+            display_name: None,
+            avatar_url: None,
+            sessions_revoked_at: None,
+        }
+    }
+
+    #[test]
+    fn test_link_verified_email_to_verified_user() {
+        let existing = user(true);
+        let profile = profile(Some("alice@example.com"), true);
+        assert_eq!(link_decision(&profile, Some(&existing)), LinkDecision::Link(&existing));
+    }
+
+    #[test]
+    fn test_no_link_when_email_unverified() {
+        // 提供方未验证邮箱
+        let existing = user(true);
+        let unverified = profile(Some("alice@example.com"), false);
+        assert_eq!(link_decision(&unverified, Some(&existing)), LinkDecision::Register { email: None });
+
+        // 本站用户未验证邮箱，新用户也不记录该邮箱
+        let existing = user(false);
+        let verified = profile(Some("alice@example.com"), true);
+        assert_eq!(link_decision(&verified, Some(&existing)), LinkDecision::Register { email: None });
+    }
+
+    #[test]
+    fn test_register_new_user() {
+        let verified = profile(Some("bob@example.com"), true);
+        assert_eq!(link_decision(&verified, None), LinkDecision::Register { email: Some("bob@example.com") });
+
+        let without_email = profile(None, true);
+        assert_eq!(link_decision(&without_email, None), LinkDecision::Register { email: None });
+    }
+}
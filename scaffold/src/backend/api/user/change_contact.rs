@@ -0,0 +1,259 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{Duration, Utc};
+use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use crate::backend::AppState;
+use crate::backend::api::code::{send_sms_code, send_verification_email};
+use crate::backend::api::logs::handle_user_logs::insert_user_log;
+use crate::backend::api::user::get_me::{load_current_user, require_first_party_session, UserProfileResponse};
+use crate::backend::api::user::handle_account::{
+    apply_contact_change, contact_taken, find_pending_verification, insert_verification, latest_verification,
+    record_failed_attempt, ContactChannel,
+};
+use crate::backend::config::account;
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+use crate::backend::models::sea_orm_active_enums::LogActionType;
+use crate::backend::utils::contact::{is_valid_email, is_valid_phone};
+use crate::backend::utils::extractors::{client_ip, user_agent};
+use crate::backend::utils::hash::hash_str;
+
+#[derive(Deserialize, Debug)]
+pub struct ChangeEmailRequest {
+    pub email: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ChangePhoneRequest {
+    pub phone: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct VerifyContactRequest {
+    pub code: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct VerificationSentResponse {
+    /// 验证码有效期（秒）
+    pub expires_in: i64,
+}
+
+fn generate_numeric_code(length: usize) -> String {
+    let mut rng = thread_rng();
+    (0..length).map(|_| char::from(b'0' + rng.gen_range(0, 10))).collect()
+}
+
+fn validate_target(channel: ContactChannel, target: &str) -> Result<(), String> {
+    let valid = match channel {
+        ContactChannel::Email => is_valid_email(target),
+        ContactChannel::Phone => is_valid_phone(target),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("invalid {}", channel.as_str()))
+    }
+}
+
+/// 验证码错误时的错误码，邮箱沿用邮箱验证码的错误码
+fn invalid_code_error(channel: ContactChannel) -> ErrorCode {
+    match channel {
+        ContactChannel::Email => ErrorCode::EmailCodeInvalid,
+        ContactChannel::Phone => ErrorCode::InvalidParams,
+    }
+}
+
+fn database_error(e: impl std::fmt::Display) -> HttpResponse {
+    let error_resp = error_response(ErrorCode::DatabaseError, format!("Database error: {}", e));
+    HttpResponse::InternalServerError().json(error_resp)
+}
+
+fn contact_conflict(channel: ContactChannel) -> HttpResponse {
+    let error_resp = error_response(
+        ErrorCode::ResourceAlreadyExists,
+        format!("This {} is already used by another account", channel.as_str()),
+    );
+    HttpResponse::Conflict().json(error_resp)
+}
+
+/// 向新的邮箱 / 手机号发送验证码
+async fn request_change(req: HttpRequest, state: web::Data<AppState>, channel: ContactChannel, target: &str) -> HttpResponse {
+    let claims = match require_first_party_session(&req) {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    let target = target.trim();
+    if let Err(message) = validate_target(channel, target) {
+        return HttpResponse::BadRequest().json(error_response(ErrorCode::InvalidFormat, message));
+    }
+
+    let user = match load_current_user(&state, &claims.user_id).await {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+    if channel.current(&user) == Some(target) {
+        let error_resp = error_response(
+            ErrorCode::InvalidParams,
+            format!("The new {} is the same as the current one", channel.as_str()),
+        );
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+
+    let db = &state.pg_client;
+    match contact_taken(db, channel, target, &user.user_id).await {
+        Ok(false) => {}
+        Ok(true) => return contact_conflict(channel),
+        Err(e) => return database_error(e),
+    }
+
+    match latest_verification(db, &user.user_id, channel).await {
+        Ok(Some(latest))
+            if Utc::now().naive_utc() - latest.created_at < Duration::seconds(account::VERIFICATION_RESEND_SECONDS) =>
+        {
+            let error_resp = error_response(
+                ErrorCode::RateLimitExceeded,
+                format!("Please wait {} seconds before requesting another code", account::VERIFICATION_RESEND_SECONDS),
+            );
+            return HttpResponse::TooManyRequests().json(error_resp);
+        }
+        Ok(_) => {}
+        Err(e) => return database_error(e),
+    }
+
+    let code = generate_numeric_code(account::VERIFICATION_CODE_LENGTH);
+    if let Err(e) = insert_verification(db, &user.user_id, channel, target, &hash_str(&code)).await {
+        return database_error(e);
+    }
+
+    let sent = match channel {
+        ContactChannel::Email => send_verification_email(target, &code).await,
+        ContactChannel::Phone => send_sms_code(target, &code).await,
+    };
+    if let Err(e) = sent {
+        let code = match channel {
+            ContactChannel::Email => ErrorCode::EmailSendFailed,
+            ContactChannel::Phone => ErrorCode::ServiceUnavailable,
+        };
+        let error_resp = error_response(code, format!("Failed to send verification code: {}", e));
+        return HttpResponse::InternalServerError().json(error_resp);
+    }
+
+    info!("📨 Sent {} change code to user {}", channel.as_str(), user.user_id);
+    HttpResponse::Ok().json(SuccessResponse::new(VerificationSentResponse {
+        expires_in: account::VERIFICATION_CODE_TTL_SECONDS,
+    }))
+}
+
+/// 校验验证码并写入新的邮箱 / 手机号
+async fn verify_change(req: HttpRequest, state: web::Data<AppState>, channel: ContactChannel, code: &str) -> HttpResponse {
+    let claims = match require_first_party_session(&req) {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    let user = match load_current_user(&state, &claims.user_id).await {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+
+    let db = &state.pg_client;
+    let verification = match find_pending_verification(db, &user.user_id, channel).await {
+        Ok(Some(verification)) if verification.attempts < account::VERIFICATION_MAX_ATTEMPTS => verification,
+        Ok(_) => {
+            let error_resp = error_response(
+                ErrorCode::ResourceExpired,
+                "Verification code expired or not requested, please request a new one",
+            );
+            return HttpResponse::BadRequest().json(error_resp);
+        }
+        Err(e) => return database_error(e),
+    };
+
+    if hash_str(code.trim()) != verification.code_hash {
+        if let Err(e) = record_failed_attempt(db, verification.id).await {
+            warn!("Failed to record verification attempt for {}: {}", user.user_id, e);
+        }
+        let error_resp = error_response(invalid_code_error(channel), "Invalid verification code");
+        return HttpResponse::BadRequest().json(error_resp);
+    }
+
+    let user_id = user.user_id.clone();
+    let updated = match apply_contact_change(db, user, channel, &verification).await {
+        Ok(Some(updated)) => updated,
+        Ok(None) => return contact_conflict(channel),
+        Err(e) => return database_error(e),
+    };
+
+    if let Err(e) = insert_user_log(db, &user_id, LogActionType::UpdateProfile, &client_ip(&req), user_agent(&req).as_deref()).await {
+        warn!("Failed to record {} change for {}: {}", channel.as_str(), user_id, e);
+    }
+
+    info!("✅ User {} verified new {}", user_id, channel.as_str());
+    HttpResponse::Ok().json(SuccessResponse::new(UserProfileResponse::from(updated)))
+}
+
+/// 申请更换邮箱，验证码发送到新邮箱
+///
+/// 路由: POST /v2/user/me/email
+pub async fn request_email_change(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    request: web::Json<ChangeEmailRequest>,
+) -> HttpResponse {
+    request_change(req, state, ContactChannel::Email, &request.email).await
+}
+
+/// 提交邮箱验证码，完成更换
+///
+/// 路由: POST /v2/user/me/email/verify
+pub async fn verify_email_change(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    request: web::Json<VerifyContactRequest>,
+) -> HttpResponse {
+    verify_change(req, state, ContactChannel::Email, &request.code).await
+}
+
+/// 申请更换手机号，验证码以短信发送到新手机号
+///
+/// 路由: POST /v2/user/me/phone
+pub async fn request_phone_change(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    request: web::Json<ChangePhoneRequest>,
+) -> HttpResponse {
+    request_change(req, state, ContactChannel::Phone, &request.phone).await
+}
+
+/// 提交短信验证码，完成更换
+///
+/// 路由: POST /v2/user/me/phone/verify
+pub async fn verify_phone_change(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    request: web::Json<VerifyContactRequest>,
+) -> HttpResponse {
+    verify_change(req, state, ContactChannel::Phone, &request.code).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_numeric_code() {
+        let code = generate_numeric_code(account::VERIFICATION_CODE_LENGTH);
+        assert_eq!(code.len(), account::VERIFICATION_CODE_LENGTH);
+        assert!(code.chars().all(|c| c.is_ascii_digit()));
+    }
+
+    #[test]
+    fn test_validate_target() {
+        assert!(validate_target(ContactChannel::Email, "alice@example.com").is_ok());
+        assert!(validate_target(ContactChannel::Email, "13800138000").is_err());
+        assert!(validate_target(ContactChannel::Phone, "+86 138-0013-8000").is_ok());
+        assert!(validate_target(ContactChannel::Phone, "alice@example.com").is_err());
+    }
+}
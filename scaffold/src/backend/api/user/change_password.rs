@@ -0,0 +1,145 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use chrono::Utc;
+use sea_orm::{DatabaseConnection, DbErr, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use crate::backend::AppState;
+use crate::backend::api::api_keys::revoke_api_keys_of_user;
+use crate::backend::api::device::revoke_devices_of_user;
+use crate::backend::api::logs::handle_user_logs::insert_user_log;
+use crate::backend::api::oauth::revoke_refresh_tokens_of_user;
+use crate::backend::api::user::get_me::{load_current_user, require_first_party_session};
+use crate::backend::api::user::handle_account::update_password;
+use crate::backend::config::jwt;
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+use crate::backend::models::sea_orm_active_enums::LogActionType;
+use crate::backend::utils::contact::validate_password;
+use crate::backend::utils::extractors::{client_ip, user_agent};
+use crate::backend::utils::hash::{hash_password, verify_password};
+use crate::backend::utils::jwt::{create_jwt, Claims};
+
+#[derive(Deserialize, Debug)]
+pub struct ChangePasswordRequest {
+    pub old_password: String,
+    pub new_password: String,
+}
+
+/// 修改成功后为当前会话签发的新 token
+#[derive(Serialize, Debug)]
+pub struct ChangePasswordResponse {
+    pub token: String,
+    pub exp: usize,
+}
+
+/// 写入新密码哈希，并吊销 OAuth refresh token、API Key 与已注册设备；在一个事务中完成，任一步失败则全部回滚
+async fn apply_password_change(db: &DatabaseConnection, user_id: &str, password_hash: &str) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+    update_password(&txn, user_id, password_hash).await?;
+    revoke_refresh_tokens_of_user(&txn, user_id).await?;
+    revoke_api_keys_of_user(&txn, user_id).await?;
+    revoke_devices_of_user(&txn, user_id).await?;
+    txn.commit().await
+}
+
+fn validate_request(request: &ChangePasswordRequest) -> Result<(), String> {
+    validate_password(&request.new_password)?;
+    if request.new_password == request.old_password {
+        return Err("new_password must differ from old_password".to_string());
+    }
+    Ok(())
+}
+
+/// 修改当前用户的密码
+///
+/// 路由: POST /v2/user/me/password
+///
+/// 校验旧密码后写入新密码哈希；此前签发的 access token、OAuth refresh token、API Key 与已注册设备全部失效，
+/// 当前会话通过响应中的新 token（同时写入 `Authorization` 响应头）继续使用。
+pub async fn change_password(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    request: web::Json<ChangePasswordRequest>,
+) -> HttpResponse {
+    let claims = match require_first_party_session(&req) {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    if let Err(message) = validate_request(&request) {
+        return HttpResponse::BadRequest().json(error_response(ErrorCode::InvalidParams, message));
+    }
+
+    let user = match load_current_user(&state, &claims.user_id).await {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+
+    // bcrypt 计算较慢，放到阻塞线程池中执行
+    let request = request.into_inner();
+    let password_hash = user.password_hash.clone();
+    let hashed = web::block(move || {
+        if !verify_password(&request.old_password, &password_hash) {
+            return Ok(None);
+        }
+        hash_password(&request.new_password).map(Some)
+    })
+    .await;
+    let new_hash = match hashed {
+        Ok(Ok(Some(new_hash))) => new_hash,
+        Ok(Ok(None)) => {
+            warn!("❌ User {} failed to change password: wrong old password", user.user_id);
+            let error_resp = error_response(ErrorCode::InvalidParams, "old_password is incorrect");
+            return HttpResponse::BadRequest().json(error_resp);
+        }
+        Ok(Err(e)) => {
+            let error_resp = error_response(ErrorCode::InternalError, format!("Failed to hash password: {}", e));
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+        Err(e) => {
+            let error_resp = error_response(ErrorCode::InternalError, format!("Failed to hash password: {}", e));
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    };
+
+    let db = &state.pg_client;
+    if let Err(e) = apply_password_change(db, &user.user_id, &new_hash).await {
+        let error_resp = error_response(ErrorCode::DatabaseError, format!("Failed to update password: {}", e));
+        return HttpResponse::InternalServerError().json(error_resp);
+    }
+    if let Err(e) = insert_user_log(db, &user.user_id, LogActionType::ChangePassword, &client_ip(&req), user_agent(&req).as_deref()).await {
+        warn!("Failed to record password change for {}: {}", user.user_id, e);
+    }
+
+    let new_claims = Claims {
+        user_id: user.user_id.clone(),
+        username: claims.username,
+        role: Some(user.role),
+        exp: Utc::now().timestamp() as usize + jwt::DEFAULT_EXPIRATION_SECONDS,
+        ..Default::default()
+    };
+    let token = create_jwt(&new_claims);
+
+    info!("🔑 User {} changed password", user.user_id);
+    HttpResponse::Ok()
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+        .json(SuccessResponse::new(ChangePasswordResponse { token, exp: new_claims.exp }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(old_password: &str, new_password: &str) -> ChangePasswordRequest {
+        ChangePasswordRequest {
+            old_password: old_password.to_string(),
+            new_password: new_password.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validate_request() {
+        assert!(validate_request(&request("old password", "new password")).is_ok());
+        assert!(validate_request(&request("old password", "short")).is_err());
+        assert!(validate_request(&request("same password", "same password")).is_err());
+    }
+}
@@ -1,10 +1,10 @@
-use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse};
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::backend::utils::jwt::{create_jwt, Claims};
 use crate::backend::models::sea_orm_active_enums::UserRoleType;
-use crate::backend::errors::SuccessResponse;
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
 use chrono::Utc;
 
 /// 生成测试 Token 的请求参数
@@ -26,13 +26,41 @@ pub struct TokenResponse {
     pub expires_at: String,
 }
 
+/// 经过代理转发的请求头，出现任意一个都说明请求不是本机直连
+const FORWARDED_HEADERS: [&str; 3] = ["forwarded", "x-forwarded-for", "x-real-ip"];
+
+/// 测试接口只接受本机直连的请求
+///
+/// 只看 TCP 对端地址，不信任任何转发头：反向代理与服务同机部署时对端地址同样是回环地址，
+/// 因此带有转发头的请求一律拒绝。
+fn reject_non_loopback(req: &HttpRequest) -> Option<HttpResponse> {
+    let peer = req.peer_addr();
+    let proxied = FORWARDED_HEADERS.iter().any(|h| req.headers().contains_key(*h));
+    if peer.is_some_and(|addr| addr.ip().is_loopback()) && !proxied {
+        return None;
+    }
+
+    warn!(
+        "🚫 拒绝非本机的测试 Token 请求: path={}, peer={:?}, proxied={}",
+        req.path(),
+        peer,
+        proxied
+    );
+    let error_resp = error_response(
+        ErrorCode::PermissionDenied,
+        "Test token endpoints only accept requests from localhost",
+    );
+    Some(HttpResponse::Forbidden().json(error_resp))
+}
+
 /// 生成测试 JWT Token
 ///
 /// # 测试接口
 ///
 /// 此接口用于生成测试用的 JWT token，方便开发和测试。
 ///
-/// **注意**: 此接口仅用于开发测试环境，生产环境中应该禁用。
+/// **注意**: 此接口仅在启用 `test-tokens` feature 的 debug 构建中挂载，且只接受本机直连请求。
+/// `user_id` 必须是 `users` 表中已存在的用户，否则 `Auth` 中间件会将 token 视为已吊销。
 ///
 /// ## 请求示例
 /// ```bash
@@ -62,7 +90,11 @@ pub struct TokenResponse {
 pub async fn generate_test_token(
     req: HttpRequest,
     params: web::Json<GenerateTokenRequest>,
-) -> impl Responder {
+) -> HttpResponse {
+    if let Some(resp) = reject_non_loopback(&req) {
+        return resp;
+    }
+
     info!("🧪 生成测试 Token: user_id={}, username={}", params.user_id, params.username);
 
     // 解析角色
@@ -82,6 +114,7 @@ pub async fn generate_test_token(
         username: params.username.clone(),
         role: role.clone(),
         exp: exp_timestamp,
+        ..Default::default()
     };
 
     // 生成 JWT token
@@ -111,7 +144,11 @@ pub async fn generate_test_token(
 ///
 /// ## 响应
 /// 与 `generate_test_token` 相同
-pub async fn generate_default_test_token() -> impl Responder {
+pub async fn generate_default_test_token(req: HttpRequest) -> HttpResponse {
+    if let Some(resp) = reject_non_loopback(&req) {
+        return resp;
+    }
+
     info!("🧪 生成默认测试 Token");
 
     let claims = Claims {
@@ -119,6 +156,7 @@ pub async fn generate_default_test_token() -> impl Responder {
         username: "alice".to_string(),
         role: Some(UserRoleType::Admin),
         exp: (Utc::now().timestamp() as usize + 86400), // 24小时后过期
+        ..Default::default()
     };
 
     let token = create_jwt(&claims);
@@ -139,19 +177,33 @@ pub async fn generate_default_test_token() -> impl Responder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use actix_web::test;
+    use actix_web::http::StatusCode;
+    use actix_web::test as atest;
 
     #[actix_web::test]
     async fn test_generate_default_token() {
-        let req = test::TestRequest::default().to_http_request();
-        let response = generate_default_test_token().await;
-
-        // 基本检查
-        match response.respond_to(&req) {
-            Ok(resp) => {
-                assert_eq!(resp.status(), actix_web::http::StatusCode::OK);
-            }
-            Err(e) => panic!("Response error: {:?}", e),
-        }
+        let req = atest::TestRequest::default()
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .to_http_request();
+        let response = generate_default_test_token(req).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn test_generate_default_token_rejects_non_loopback() {
+        let remote = atest::TestRequest::default()
+            .peer_addr("203.0.113.7:40000".parse().unwrap())
+            .to_http_request();
+        assert_eq!(generate_default_test_token(remote).await.status(), StatusCode::FORBIDDEN);
+
+        let proxied = atest::TestRequest::default()
+            .peer_addr("127.0.0.1:40000".parse().unwrap())
+            .insert_header(("X-Forwarded-For", "203.0.113.7"))
+            .to_http_request();
+        assert_eq!(generate_default_test_token(proxied).await.status(), StatusCode::FORBIDDEN);
+
+        let unknown_peer = atest::TestRequest::default().to_http_request();
+        assert_eq!(generate_default_test_token(unknown_peer).await.status(), StatusCode::FORBIDDEN);
     }
 }
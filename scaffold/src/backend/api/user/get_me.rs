@@ -1,43 +1,112 @@
-use actix_web::{HttpRequest, HttpResponse, Responder};
-use serde_json::json;
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::Serialize;
 use tracing::info;
 
-use crate::backend::utils::jwt::{verify_jwt, Claims};
-use crate::backend::utils::extractors::extract_token_from_request;
-use crate::backend::errors::{ErrorCode, error_response};
+use crate::backend::AppState;
+use crate::backend::api::oauth::scope_covers;
+use crate::backend::api::user::handle_account::find_user;
+use crate::backend::config::oauth;
+use crate::backend::utils::jwt::Claims;
+use crate::backend::utils::extractors::extract_claims_from_request;
+use crate::backend::errors::{AppError, ErrorCode, error_response, SuccessResponse};
+use crate::backend::models::sea_orm_active_enums::UserRoleType;
+use crate::backend::models::users;
 
 /// 从请求中提取并验证用户信息
 ///
-/// 这是一个辅助函数，用于从请求中提取 JWT token 并验证
+/// 这是一个辅助函数，用于从请求中提取 JWT token（或 API Key 认证后的 Claims）并验证
 /// 返回包含用户信息的 Claims
-fn extract_user_from_request(req: &HttpRequest) -> Result<Claims, HttpResponse> {
-    // 使用共享的 token 提取函数
-    let token = extract_token_from_request(req).map_err(|err| {
-        HttpResponse::Unauthorized().json(error_response(
-            ErrorCode::TokenMissing,
-            err.message(),
-        ))
-    })?;
-
-    // 验证 token 并解析用户信息
-    verify_jwt(&token)
-        .map(|data| data.claims)
-        .map_err(|_| {
-            HttpResponse::Unauthorized().json(error_response(
-                ErrorCode::TokenInvalid,
-                "Invalid or expired token",
-            ))
-        })
+pub fn extract_user_from_request(req: &HttpRequest) -> Result<Claims, HttpResponse> {
+    extract_claims_from_request(req).map_err(|err| {
+        let code = match err {
+            AppError::Auth(_) => ErrorCode::TokenMissing,
+            _ => err.code(),
+        };
+        HttpResponse::Unauthorized().json(error_response(code, err.message()))
+    })
 }
 
-/// 获取当前用户信息
+/// 修改密码、联系方式等账户操作只允许用户本人登录的会话调用
 ///
-/// # JWT 认证测试接口
+/// API Key、OAuth 第三方客户端与服务账号的 token 均会被拒绝。
+pub fn require_first_party_session(req: &HttpRequest) -> Result<Claims, HttpResponse> {
+    let claims = extract_user_from_request(req)?;
+    if !claims.is_first_party() {
+        let error_resp = error_response(
+            ErrorCode::PermissionDenied,
+            "This operation requires a user login session",
+        );
+        return Err(HttpResponse::Forbidden().json(error_resp));
+    }
+    Ok(claims)
+}
+
+/// 当前用户资料，不包含密码哈希
+#[derive(Serialize, Debug)]
+pub struct UserProfileResponse {
+    pub user_id: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub display_name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub role: UserRoleType,
+    pub is_active: bool,
+    pub is_verified: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<users::Model> for UserProfileResponse {
+    fn from(user: users::Model) -> Self {
+        Self {
+            user_id: user.user_id,
+            email: user.email,
+            phone: user.phone,
+            display_name: user.display_name,
+            avatar_url: user.avatar_url,
+            role: user.role,
+            is_active: user.is_active.unwrap_or(true),
+            is_verified: user.is_verified.unwrap_or(false),
+            created_at: user.created_at.and_utc().timestamp(),
+            updated_at: user.updated_at.and_utc().timestamp(),
+        }
+    }
+}
+
+impl UserProfileResponse {
+    /// OAuth token 只能看到所授予 scope 覆盖的联系方式（`email`、`phone`）
+    fn visible_to(mut self, claims: &Claims) -> Self {
+        if claims.is_oauth_token() {
+            let granted = claims.scope.as_deref().unwrap_or_default();
+            if !scope_covers(granted, oauth::SCOPE_EMAIL) {
+                self.email = None;
+            }
+            if !scope_covers(granted, oauth::SCOPE_PHONE) {
+                self.phone = None;
+            }
+        }
+        self
+    }
+}
+
+/// 加载当前用户，不存在时返回 404
+pub async fn load_current_user(state: &AppState, user_id: &str) -> Result<users::Model, HttpResponse> {
+    match find_user(&state.pg_client, user_id).await {
+        Ok(Some(user)) => Ok(user),
+        Ok(None) => {
+            let error_resp = error_response(ErrorCode::NotFound, "User not found");
+            Err(HttpResponse::NotFound().json(error_resp))
+        }
+        Err(e) => {
+            let error_resp = error_response(ErrorCode::DatabaseError, format!("Failed to load user: {}", e));
+            Err(HttpResponse::InternalServerError().json(error_resp))
+        }
+    }
+}
+
+/// 获取当前用户信息
 ///
-/// 此接口用于测试 JWT 认证是否正常工作。它会：
-/// 1. 验证请求中的 JWT token
-/// 2. 解析 token 中的用户信息
-/// 3. 返回完整的用户数据
+/// 根据 token 中的 user_id 从 `users` 表加载最新资料；OAuth token 未授予 `email` / `phone` scope 时不返回对应字段。
 ///
 /// ## 请求示例
 /// ```bash
@@ -50,46 +119,44 @@ fn extract_user_from_request(req: &HttpRequest) -> Result<Claims, HttpResponse>
 /// {
 ///   "code": 0,
 ///   "message": "success",
-///   "path": "/v2/user/me",
 ///   "data": {
 ///     "user_id": "user123",
-///     "username": "test_user",
-///     "role": "Admin",
-///     "exp": 1735689600
+///     "email": "alice@example.com",
+///     "phone": null,
+///     "display_name": "Alice",
+///     "avatar_url": null,
+///     "role": "User",
+///     "is_active": true,
+///     "is_verified": true,
+///     "created_at": 1735689600,
+///     "updated_at": 1735689600
 ///   }
 /// }
 /// ```
-pub async fn get_current_user(req: HttpRequest) -> impl Responder {
+pub async fn get_current_user(req: HttpRequest, state: web::Data<AppState>) -> HttpResponse {
     info!("🔍 GET /v2/user/me - Fetching current user info");
 
-    // 提取并验证用户信息
-    match extract_user_from_request(&req) {
-        Ok(claims) => {
-            info!("✅ User authenticated: {} ({})", claims.username, claims.user_id);
-
-            HttpResponse::Ok().json(json!({
-                "code": 0,
-                "message": "success",
-                "path": "/v2/user/me",
-                "data": {
-                    "user_id": claims.user_id,
-                    "username": claims.username,
-                    "role": claims.role,
-                    "exp": claims.exp
-                }
-            }))
-        }
+    let claims = match extract_user_from_request(&req) {
+        Ok(claims) => claims,
         Err(error_resp) => {
             info!("❌ Authentication failed");
-            error_resp
+            return error_resp;
         }
+    };
+
+    match load_current_user(&state, &claims.user_id).await {
+        Ok(user) => {
+            info!("✅ User authenticated: {}", user.user_id);
+            HttpResponse::Ok().json(SuccessResponse::new(UserProfileResponse::from(user).visible_to(&claims)))
+        }
+        Err(resp) => resp,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use actix_web::test;
+    use actix_web::test as atest;
     use crate::backend::utils::jwt::{create_jwt, Claims};
     use crate::backend::models::sea_orm_active_enums::UserRoleType;
     use chrono::Utc;
@@ -102,13 +169,14 @@ mod tests {
             username: "alice".to_string(),
             role: Some(UserRoleType::Admin),
             exp: (Utc::now().timestamp() as usize + 3600),
+            ..Default::default()
         };
 
         // 生成 token
         let token = create_jwt(&claims);
 
         // 创建测试请求
-        let req = test::TestRequest::default()
+        let req = atest::TestRequest::default()
             .insert_header(("Authorization", format!("Bearer {}", token)))
             .to_http_request();
 
@@ -125,7 +193,7 @@ mod tests {
     #[test]
     fn test_extract_user_from_missing_token() {
         // 创建没有 token 的请求
-        let req = test::TestRequest::default()
+        let req = atest::TestRequest::default()
             .to_http_request();
 
         let result = extract_user_from_request(&req);
@@ -135,11 +203,68 @@ mod tests {
     #[test]
     fn test_extract_user_from_invalid_token() {
         // 创建无效 token 的请求
-        let req = test::TestRequest::default()
+        let req = atest::TestRequest::default()
             .insert_header(("Authorization", "Bearer invalid_token_12345"))
             .to_http_request();
 
         let result = extract_user_from_request(&req);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_require_first_party_session() {
+        let claims = Claims {
+            user_id: "test_user_123".to_string(),
+            username: "alice".to_string(),
+            role: Some(UserRoleType::User),
+            exp: (Utc::now().timestamp() as usize + 3600),
+            ..Default::default()
+        };
+        let request_with = |claims: &Claims| {
+            atest::TestRequest::default()
+                .insert_header(("Authorization", format!("Bearer {}", create_jwt(claims))))
+                .to_http_request()
+        };
+
+        assert!(require_first_party_session(&request_with(&claims)).is_ok());
+
+        // OAuth 客户端代表用户获取的 token 不能修改账户
+        let oauth_claims = Claims { client_id: Some("client_abc".to_string()), ..claims.clone() };
+        let resp = require_first_party_session(&request_with(&oauth_claims)).unwrap_err();
+        assert_eq!(resp.status(), actix_web::http::StatusCode::FORBIDDEN);
+
+        let service_claims = Claims { service_account: true, ..claims };
+        assert!(require_first_party_session(&request_with(&service_claims)).is_err());
+    }
+
+    #[test]
+    fn test_profile_visible_to_oauth_scope() {
+        let profile = || UserProfileResponse {
+            user_id: "test_user_123".to_string(),
+            email: Some("alice@example.com".to_string()),
+            phone: Some("+86 13800138000".to_string()),
+            display_name: None,
+            avatar_url: None,
+            role: UserRoleType::User,
+            is_active: true,
+            is_verified: true,
+            created_at: 0,
+            updated_at: 0,
+        };
+        let oauth_claims = |scope: &str| Claims {
+            client_id: Some("client_abc".to_string()),
+            scope: Some(scope.to_string()),
+            ..Default::default()
+        };
+
+        // 用户本人的会话不受 scope 限制
+        let own = profile().visible_to(&Claims::default());
+        assert!(own.email.is_some() && own.phone.is_some());
+
+        let basic = profile().visible_to(&oauth_claims("openid profile"));
+        assert!(basic.email.is_none() && basic.phone.is_none());
+
+        let with_email = profile().visible_to(&oauth_claims("openid profile email"));
+        assert!(with_email.email.is_some() && with_email.phone.is_none());
+    }
 }
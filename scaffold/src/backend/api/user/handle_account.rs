@@ -0,0 +1,228 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
+};
+use tracing::info;
+use crate::backend::config::account;
+use crate::backend::models::{contact_verifications, users};
+use crate::backend::models::prelude::{ContactVerifications, Users};
+
+/// 可更换的联系方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContactChannel {
+    Email,
+    Phone,
+}
+
+impl ContactChannel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::Phone => "phone",
+        }
+    }
+
+    fn column(self) -> users::Column {
+        match self {
+            Self::Email => users::Column::Email,
+            Self::Phone => users::Column::Phone,
+        }
+    }
+
+    /// 用户当前的联系方式
+    pub fn current(self, user: &users::Model) -> Option<&str> {
+        match self {
+            Self::Email => user.email.as_deref(),
+            Self::Phone => user.phone.as_deref(),
+        }
+    }
+}
+
+pub async fn find_user(db: &DatabaseConnection, user_id: &str) -> Result<Option<users::Model>, DbErr> {
+    Users::find()
+        .filter(users::Column::UserId.eq(user_id))
+        .one(db)
+        .await
+}
+
+/// 更新昵称与头像，`None` 表示不修改，空字符串表示清除
+pub async fn update_profile(
+    db: &DatabaseConnection,
+    user: users::Model,
+    display_name: Option<&str>,
+    avatar_url: Option<&str>,
+) -> Result<users::Model, DbErr> {
+    let clear_empty = |value: &str| Some(value.to_string()).filter(|v| !v.is_empty());
+    let mut active: users::ActiveModel = user.into();
+    if let Some(display_name) = display_name {
+        active.display_name = Set(clear_empty(display_name));
+    }
+    if let Some(avatar_url) = avatar_url {
+        active.avatar_url = Set(clear_empty(avatar_url));
+    }
+    active.updated_at = Set(Utc::now().naive_utc());
+    active.update(db).await
+}
+
+/// 更新密码哈希，并使此前签发的所有 access token 失效
+pub async fn update_password(db: &impl sea_orm::ConnectionTrait, user_id: &str, password_hash: &str) -> Result<(), DbErr> {
+    let now = Utc::now().naive_utc();
+    Users::update_many()
+        .col_expr(users::Column::PasswordHash, password_hash.into())
+        .col_expr(users::Column::SessionsRevokedAt, now.into())
+        .col_expr(users::Column::UpdatedAt, now.into())
+        .filter(users::Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+
+    info!("Password of user {} changed, earlier sessions revoked", user_id);
+    Ok(())
+}
+
+/// 联系方式是否已被其他用户使用
+pub async fn contact_taken(
+    db: &impl sea_orm::ConnectionTrait,
+    channel: ContactChannel,
+    target: &str,
+    user_id: &str,
+) -> Result<bool, DbErr> {
+    let existing = Users::find()
+        .filter(channel.column().eq(target))
+        .filter(users::Column::UserId.ne(user_id))
+        .one(db)
+        .await?;
+    Ok(existing.is_some())
+}
+
+/// 用户在该渠道最近一次申请的验证码（无论是否已使用），用于限制重发频率
+pub async fn latest_verification(
+    db: &DatabaseConnection,
+    user_id: &str,
+    channel: ContactChannel,
+) -> Result<Option<contact_verifications::Model>, DbErr> {
+    ContactVerifications::find()
+        .filter(contact_verifications::Column::UserId.eq(user_id))
+        .filter(contact_verifications::Column::Channel.eq(channel.as_str()))
+        .order_by_desc(contact_verifications::Column::CreatedAt)
+        .one(db)
+        .await
+}
+
+/// 保存新的验证码，同一渠道尚未使用的旧验证码随之作废
+pub async fn insert_verification(
+    db: &DatabaseConnection,
+    user_id: &str,
+    channel: ContactChannel,
+    target: &str,
+    code_hash: &str,
+) -> Result<contact_verifications::Model, DbErr> {
+    let now = Utc::now().naive_utc();
+    let txn = db.begin().await?;
+
+    ContactVerifications::update_many()
+        .col_expr(contact_verifications::Column::ConsumedAt, now.into())
+        .filter(contact_verifications::Column::UserId.eq(user_id))
+        .filter(contact_verifications::Column::Channel.eq(channel.as_str()))
+        .filter(contact_verifications::Column::ConsumedAt.is_null())
+        .exec(&txn)
+        .await?;
+
+    let inserted = contact_verifications::ActiveModel {
+        user_id: Set(user_id.to_string()),
+        channel: Set(channel.as_str().to_string()),
+        target: Set(target.to_string()),
+        code_hash: Set(code_hash.to_string()),
+        attempts: Set(0),
+        expires_at: Set(now + Duration::seconds(account::VERIFICATION_CODE_TTL_SECONDS)),
+        created_at: Set(now),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+
+    txn.commit().await?;
+    info!("Created {} verification for user {}", channel.as_str(), user_id);
+    Ok(inserted)
+}
+
+/// 未使用且未过期的验证码
+pub async fn find_pending_verification(
+    db: &DatabaseConnection,
+    user_id: &str,
+    channel: ContactChannel,
+) -> Result<Option<contact_verifications::Model>, DbErr> {
+    ContactVerifications::find()
+        .filter(contact_verifications::Column::UserId.eq(user_id))
+        .filter(contact_verifications::Column::Channel.eq(channel.as_str()))
+        .filter(contact_verifications::Column::ConsumedAt.is_null())
+        .filter(contact_verifications::Column::ExpiresAt.gt(Utc::now().naive_utc()))
+        .order_by_desc(contact_verifications::Column::CreatedAt)
+        .one(db)
+        .await
+}
+
+pub async fn record_failed_attempt(db: &DatabaseConnection, id: i64) -> Result<(), DbErr> {
+    ContactVerifications::update_many()
+        .col_expr(
+            contact_verifications::Column::Attempts,
+            Expr::col(contact_verifications::Column::Attempts).add(1),
+        )
+        .filter(contact_verifications::Column::Id.eq(id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// 验证通过：写入新的联系方式并作废验证码；返回 `None` 表示联系方式已被其他用户占用
+///
+/// 更换邮箱同时将用户标记为已验证。
+pub async fn apply_contact_change(
+    db: &DatabaseConnection,
+    user: users::Model,
+    channel: ContactChannel,
+    verification: &contact_verifications::Model,
+) -> Result<Option<users::Model>, DbErr> {
+    let now = Utc::now().naive_utc();
+    let txn = db.begin().await?;
+
+    if contact_taken(&txn, channel, &verification.target, &user.user_id).await? {
+        txn.rollback().await?;
+        return Ok(None);
+    }
+
+    let mut active: users::ActiveModel = user.into();
+    match channel {
+        ContactChannel::Email => {
+            active.email = Set(Some(verification.target.clone()));
+            active.is_verified = Set(Some(true));
+        }
+        ContactChannel::Phone => active.phone = Set(Some(verification.target.clone())),
+    }
+    active.updated_at = Set(now);
+    let updated = active.update(&txn).await?;
+
+    ContactVerifications::update_many()
+        .col_expr(contact_verifications::Column::ConsumedAt, now.into())
+        .filter(contact_verifications::Column::Id.eq(verification.id))
+        .exec(&txn)
+        .await?;
+
+    txn.commit().await?;
+    info!("User {} changed {}", updated.user_id, channel.as_str());
+    Ok(Some(updated))
+}
+
+/// 删除已过期的验证码
+pub async fn purge_contact_verifications_before(db: &DatabaseConnection, now: NaiveDateTime) -> Result<u64, DbErr> {
+    let result = ContactVerifications::delete_many()
+        .filter(contact_verifications::Column::ExpiresAt.lt(now))
+        .exec(db)
+        .await?;
+
+    if result.rows_affected > 0 {
+        info!("Purged {} expired contact verifications", result.rows_affected);
+    }
+    Ok(result.rows_affected)
+}
@@ -1,18 +1,48 @@
 mod get_me;
+mod handle_account;
+mod update_profile;
+mod change_password;
+mod change_contact;
+#[cfg(feature = "test-tokens")]
 mod generate_test_token;
 
+#[cfg(all(feature = "test-tokens", not(debug_assertions)))]
+compile_error!("The `test-tokens` feature mints arbitrary Admin tokens and must not be enabled in release builds");
+
 use actix_web::{Scope, web};
 
+pub use get_me::require_first_party_session;
+pub use handle_account::purge_contact_verifications_before;
+
+use crate::backend::api::user::change_contact::{
+    request_email_change, request_phone_change, verify_email_change, verify_phone_change,
+};
+use crate::backend::api::user::change_password::change_password;
 use crate::backend::api::user::get_me::get_current_user;
+use crate::backend::api::user::update_profile::update_profile;
+#[cfg(feature = "test-tokens")]
 use crate::backend::api::user::generate_test_token::{generate_test_token, generate_default_test_token};
 
 pub fn user_scope() -> Scope {
     web::scope("/user")
         .route("/me", web::get().to(get_current_user))
+        .route("/me", web::patch().to(update_profile))
+        .route("/me/password", web::post().to(change_password))
+        .route("/me/email", web::post().to(request_email_change))
+        .route("/me/email/verify", web::post().to(verify_email_change))
+        .route("/me/phone", web::post().to(request_phone_change))
+        .route("/me/phone/verify", web::post().to(verify_phone_change))
 }
 
-pub fn test_scope() -> Scope {
-    web::scope("/test")
-        .route("/generate-token", web::post().to(generate_test_token))
-        .route("/generate-token/default", web::post().to(generate_default_test_token))
+/// 测试接口（生成 token），仅在启用 `test-tokens` feature 时挂载
+#[cfg(feature = "test-tokens")]
+pub fn configure_test_scope(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/test")
+            .route("/generate-token", web::post().to(generate_test_token))
+            .route("/generate-token/default", web::post().to(generate_default_test_token)),
+    );
 }
+
+#[cfg(not(feature = "test-tokens"))]
+pub fn configure_test_scope(_cfg: &mut web::ServiceConfig) {}
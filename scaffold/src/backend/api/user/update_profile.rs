@@ -0,0 +1,111 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+use tracing::{info, warn};
+use url::Url;
+use crate::backend::AppState;
+use crate::backend::api::logs::handle_user_logs::insert_user_log;
+use crate::backend::api::user::get_me::{load_current_user, require_first_party_session, UserProfileResponse};
+use crate::backend::api::user::handle_account::update_profile as save_profile;
+use crate::backend::config::account;
+use crate::backend::errors::{ErrorCode, error_response, SuccessResponse};
+use crate::backend::models::sea_orm_active_enums::LogActionType;
+use crate::backend::utils::extractors::{client_ip, user_agent};
+
+/// 未提供的字段保持不变，空字符串表示清除
+#[derive(Deserialize, Debug)]
+pub struct UpdateProfileRequest {
+    pub display_name: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+fn validate_profile(request: &UpdateProfileRequest) -> Result<(), String> {
+    if request.display_name.is_none() && request.avatar_url.is_none() {
+        return Err("at least one of display_name, avatar_url is required".to_string());
+    }
+    if let Some(display_name) = &request.display_name {
+        if display_name.chars().count() > account::DISPLAY_NAME_MAX_LEN {
+            return Err(format!("display_name must be at most {} characters", account::DISPLAY_NAME_MAX_LEN));
+        }
+        if display_name.chars().any(char::is_control) {
+            return Err("display_name must not contain control characters".to_string());
+        }
+    }
+    if let Some(avatar_url) = request.avatar_url.as_deref().filter(|url| !url.is_empty()) {
+        if avatar_url.len() > account::AVATAR_URL_MAX_LEN {
+            return Err(format!("avatar_url must be at most {} characters", account::AVATAR_URL_MAX_LEN));
+        }
+        match Url::parse(avatar_url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") && url.has_host() => {}
+            _ => return Err("avatar_url must be an http(s) URL".to_string()),
+        }
+    }
+    Ok(())
+}
+
+/// 修改当前用户的昵称与头像
+///
+/// 路由: PATCH /v2/user/me
+pub async fn update_profile(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    request: web::Json<UpdateProfileRequest>,
+) -> HttpResponse {
+    let claims = match require_first_party_session(&req) {
+        Ok(claims) => claims,
+        Err(resp) => return resp,
+    };
+
+    let mut request = request.into_inner();
+    request.display_name = request.display_name.map(|name| name.trim().to_string());
+    request.avatar_url = request.avatar_url.map(|url| url.trim().to_string());
+    if let Err(message) = validate_profile(&request) {
+        return HttpResponse::BadRequest().json(error_response(ErrorCode::InvalidParams, message));
+    }
+
+    let user = match load_current_user(&state, &claims.user_id).await {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+
+    let db = &state.pg_client;
+    let updated = match save_profile(db, user, request.display_name.as_deref(), request.avatar_url.as_deref()).await {
+        Ok(updated) => updated,
+        Err(e) => {
+            let error_resp = error_response(ErrorCode::DatabaseError, format!("Failed to update profile: {}", e));
+            return HttpResponse::InternalServerError().json(error_resp);
+        }
+    };
+
+    if let Err(e) = insert_user_log(db, &updated.user_id, LogActionType::UpdateProfile, &client_ip(&req), user_agent(&req).as_deref()).await {
+        warn!("Failed to record profile update for {}: {}", updated.user_id, e);
+    }
+
+    info!("✏️ User {} updated profile", updated.user_id);
+    HttpResponse::Ok().json(SuccessResponse::new(UserProfileResponse::from(updated)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(display_name: Option<&str>, avatar_url: Option<&str>) -> UpdateProfileRequest {
+        UpdateProfileRequest {
+            display_name: display_name.map(str::to_string),
+            avatar_url: avatar_url.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_validate_profile() {
+        assert!(validate_profile(&request(Some("Alice"), None)).is_ok());
+        assert!(validate_profile(&request(None, Some("https://cdn.example.com/a.png"))).is_ok());
+        // 空字符串表示清除
+        assert!(validate_profile(&request(Some(""), Some(""))).is_ok());
+
+        assert!(validate_profile(&request(None, None)).is_err());
+        assert!(validate_profile(&request(Some(&"x".repeat(account::DISPLAY_NAME_MAX_LEN + 1)), None)).is_err());
+        assert!(validate_profile(&request(Some("a\nb"), None)).is_err());
+        assert!(validate_profile(&request(None, Some("javascript:alert(1)"))).is_err());
+        assert!(validate_profile(&request(None, Some("not a url"))).is_err());
+    }
+}
@@ -1,18 +1,27 @@
 use actix_web::{App, HttpServer, web, middleware, http, Responder, HttpResponse, Scope};
 use actix_cors::Cors;
 use sea_orm::DbConn;
-use tracing::info;
+use tracing::{info, warn};
 use crate::backend::AppState;
 use crate::backend::middleware::auth_middleware::Auth;
 use crate::backend::middleware::time::Timed;
 use crate::backend::api::auth::auth_scope;
 // use crate::backend::api::password::password_scope;
-// use crate::backend::api::admin::admin_scope;
+use crate::backend::api::admin::admin_scope;
 // use crate::backend::api::logs::logs_scope;
 use crate::backend::api::code::code_scope;
-use crate::backend::api::qr_login::{qr_login_scope, ws_qr_route};
-use crate::backend::api::user::{user_scope, test_scope};
+use crate::backend::api::qr_login::{qr_login_scope, qr_landing_route, spawn_session_sweeper, ws_qr_route};
+use crate::backend::api::user::{configure_test_scope, user_scope};
+use crate::backend::api::notify::user_ws_route;
+use crate::backend::api::device::device_scope;
+use crate::backend::api::api_keys::api_keys_scope;
+use crate::backend::api::oauth::{
+    authorize_decision_route, device_approval_scope, device_verification_route, oauth_clients_scope, oauth_scope,
+    openid_configuration_route,
+};
 use crate::backend::ws_manager::WsManager;
+use crate::backend::ws_backplane::spawn_backplane_listener;
+use crate::backend::maintenance::spawn_maintenance;
 
 pub async fn run_backend_server(
     pg_client: DbConn,
@@ -20,8 +29,15 @@ pub async fn run_backend_server(
 ) -> std::io::Result<()> {
     info!("🌐 Starting HTTP server on 0.0.0.0:{}", backend_port);
     
-    // 创建WebSocket管理器
-    let ws_manager = WsManager::new();
+    // 创建WebSocket管理器，并通过 Postgres LISTEN/NOTIFY 接收其他实例的推送
+    let ws_manager = WsManager::with_backplane(pg_client.clone());
+    spawn_backplane_listener(pg_client.clone(), ws_manager.clone());
+
+    // 启动扫码会话后台清理任务（过期推送 + 历史数据清理）
+    spawn_session_sweeper(pg_client.clone(), ws_manager.clone());
+
+    // 启动过期数据清理任务（设备 nonce、第三方登录 state、吊销记录等）
+    spawn_maintenance(pg_client.clone());
     
     let server = HttpServer::new(move || {
         App::new()
@@ -34,9 +50,16 @@ pub async fn run_backend_server(
                       .allowed_header(http::header::CONTENT_TYPE)
                       .max_age(3600),
             )
-            .wrap(middleware::Logger::default())
+            // 与默认格式相同，但只记录路径，避免 WebSocket 握手的 access_token 查询参数写入日志
+            .wrap(middleware::Logger::new(r#"%a "%m %U" %s %b "%{Referer}i" "%{User-Agent}i" %T"#))
             .app_data(web::Data::new(AppState { pg_client: pg_client.clone() }))
             .app_data(web::Data::new(ws_manager.clone()))
+            // 二维码回退页面（通用相机扫码后在浏览器中打开）
+            .route("/qr/{session_id}", qr_landing_route())
+            // OAuth 设备授权验证页面
+            .route("/device", device_verification_route())
+            // OpenID Connect 发现文档
+            .route("/.well-known/openid-configuration", openid_configuration_route())
             // ==================== v1 API: 公开接口（不需要认证）====================
             .service(
                 web::scope("/v1")
@@ -45,17 +68,25 @@ pub async fn run_backend_server(
                     .service(auth_scope())     // 用户注册/登录
                     .service(code_scope())     // 验证码
                     .service(qr_login_scope()) // 扫码登录（生成二维码、查询状态）
-                    .service(test_scope())     // 测试接口（生成 token）
+                    .service(oauth_scope())    // OAuth / OIDC 授权、设备授权、token、自省、吊销、userinfo
+                    .configure(configure_test_scope) // 测试接口（生成 token），仅 `test-tokens` feature
                     // WebSocket路由
                     .route("/ws/qr/{session_id}", ws_qr_route())
             )
-            // ==================== v2 API: 需要认证的接口 ====================
+            // ==================== v2 API: 需要认证的接口（JWT 或 API Key）====================
             .service(
                 web::scope("/v2")
                     .wrap(Timed)
                     .wrap(Auth)
                     .service(user_scope())     // 用户信息管理
-                    // .service(admin_scope())    // 管理员接口
+                    .service(device_scope())   // App 设备密钥
+                    .service(api_keys_scope()) // API Key 管理
+                    .service(device_approval_scope()) // 批准 OAuth 设备授权
+                    .service(oauth_clients_scope())   // OAuth 客户端管理（管理员）
+                    .route("/oauth/authorize", authorize_decision_route()) // OAuth 授权同意
+                    // 已登录用户的实时通知
+                    .route("/ws", user_ws_route())
+                    .service(admin_scope())    // 管理员接口
             )
     })
         .bind(("0.0.0.0", backend_port))?;
@@ -66,14 +97,40 @@ pub async fn run_backend_server(
     info!("  ├─ v1 (公开接口，无需认证):");
     info!("  │  ├─ 🏓 Health: http://localhost:{}/v1/ping", backend_port);
     info!("  │  ├─ �📡 QR Login: http://localhost:{}/v1/qr-login/generate", backend_port);
+    info!("  │  ├─ 🔑 QR Public Key: http://localhost:{}/v1/qr-login/public-key", backend_port);
     info!("  │  ├─ 🔌 WebSocket: ws://localhost:{}/v1/ws/qr/{{session_id}}", backend_port);
+    info!("  │  ├─ 📡 QR Events (SSE): http://localhost:{}/v1/qr-login/events/{{session_id}}", backend_port);
+    info!("  │  ├─ 📲 Push Login: http://localhost:{}/v1/qr-login/push", backend_port);
+    info!("  │  ├─ 🪪 OAuth Authorize: http://localhost:{}/v1/oauth/authorize", backend_port);
+    info!("  │  ├─ 📺 Device Authorization: http://localhost:{}/v1/oauth/device_authorization", backend_port);
+    info!("  │  ├─ 🎫 OAuth Token: http://localhost:{}/v1/oauth/token", backend_port);
+    info!("  │  ├─ 🔍 Token Introspection: http://localhost:{}/v1/oauth/introspect", backend_port);
+    info!("  │  ├─ 🚫 Token Revocation: http://localhost:{}/v1/oauth/revoke", backend_port);
+    info!("  │  ├─ 🆔 OIDC Discovery: http://localhost:{}/.well-known/openid-configuration", backend_port);
+    info!("  │  ├─ 👤 OIDC Userinfo: http://localhost:{}/v1/oauth/userinfo", backend_port);
     info!("  │  ├─ 🔐 Auth: http://localhost:{}/v1/auth/*", backend_port);
-    info!("  │  ├─ 📧 Code: http://localhost:{}/v1/code/*", backend_port);
-    info!("  │  └─ 🧪 Test: http://localhost:{}/v1/test/generate-token", backend_port);
+    info!("  │  ├─ 🌐 Social Login: http://localhost:{}/v1/auth/social/providers", backend_port);
+    if cfg!(feature = "test-tokens") {
+        info!("  │  ├─ 📧 Code: http://localhost:{}/v1/code/*", backend_port);
+        info!("  │  └─ 🧪 Test: http://localhost:{}/v1/test/generate-token", backend_port);
+    } else {
+        info!("  │  └─ 📧 Code: http://localhost:{}/v1/code/*", backend_port);
+    }
     info!("  │");
     info!("  └─ v2 (需要认证):");
-    info!("     └─ 👤 User: http://localhost:{}/v2/user/me", backend_port);
+    info!("     ├─ 👤 User: http://localhost:{}/v2/user/me", backend_port);
+    info!("     ├─ 📱 Devices: http://localhost:{}/v2/devices", backend_port);
+    info!("     ├─ 🗝️ API Keys: http://localhost:{}/v2/api-keys", backend_port);
+    info!("     ├─ 🧩 OAuth Clients: http://localhost:{}/v2/oauth/clients", backend_port);
+    info!("     ├─ 🛡️ Admin Users: http://localhost:{}/v2/admin/users", backend_port);
+    info!("     └─ 🔔 Notifications: ws://localhost:{}/v2/ws", backend_port);
     info!("");
+    if cfg!(feature = "test-tokens") {
+        warn!("⚠️ ============================================================");
+        warn!("⚠️ test-tokens feature 已启用：/v1/test/generate-token 可签发任意用户（含 Admin）的 token");
+        warn!("⚠️ 仅接受本机直连请求，切勿用于生产环境");
+        warn!("⚠️ ============================================================");
+    }
     
     server.run().await
 }
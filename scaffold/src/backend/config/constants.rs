@@ -7,14 +7,62 @@ pub mod qr_code {
     /// QR 码过期时间（秒）- 默认 5 分钟
     pub const TTL_SECONDS: u64 = 300;
 
-    /// QR 码图片最小尺寸
-    pub const MIN_IMAGE_SIZE: u32 = 300;
+    /// QR 码图片最小尺寸（像素）
+    pub const MIN_IMAGE_SIZE: u32 = 128;
 
-    /// QR 码图片最大尺寸
-    pub const MAX_IMAGE_SIZE: u32 = 300;
+    /// QR 码图片最大尺寸（像素）
+    pub const MAX_IMAGE_SIZE: u32 = 1024;
+
+    /// QR 码图片默认尺寸（像素）
+    pub const DEFAULT_IMAGE_SIZE: u32 = 300;
+
+    /// QR 码默认留白宽度（模块数），符合 QR 规范的 4 个模块
+    pub const DEFAULT_MARGIN: u32 = 4;
+
+    /// QR 码最大留白宽度（模块数）
+    pub const MAX_MARGIN: u32 = 16;
+
+    /// 后台清理任务的执行间隔（秒）
+    pub const SWEEP_INTERVAL_SECONDS: u64 = 15;
+
+    /// 会话过期后保留的时间（秒），超过后从数据库中删除 - 默认 1 天
+    pub const RETENTION_SECONDS: i64 = 86400;
+
+    /// 中心 Logo 占二维码内容区域宽度的比例
+    pub const LOGO_SCALE: f32 = 0.2;
 
     /// QR 码默认容错级别
     pub const ERROR_CORRECTION_LEVEL: qrcode::EcLevel = qrcode::EcLevel::M;
+
+    /// 二维码载荷版本号，签名消息中包含此版本
+    pub const PAYLOAD_VERSION: u32 = 1;
+
+    /// 二维码登录动作
+    pub const ACTION_LOGIN: &str = "login";
+
+    /// 数字匹配时 App 端展示的候选数字个数
+    pub const MATCH_CHOICES: usize = 3;
+
+    /// 登录方式：扫码
+    pub const LOGIN_METHOD_QR: &str = "qr";
+
+    /// 登录方式：推送到已登录的 App 审批
+    pub const LOGIN_METHOD_PUSH: &str = "push";
+
+    /// 推送登录限流的统计窗口（秒）
+    pub const PUSH_RATE_WINDOW_SECONDS: i64 = 600;
+
+    /// 统计窗口内同一 IP 最多发起的推送登录次数
+    pub const PUSH_MAX_PER_IP: u64 = 10;
+
+    /// 统计窗口内同一用户最多收到的推送审批次数，超过后不再推送
+    pub const PUSH_MAX_PER_USER: u64 = 3;
+
+    /// 登录方式：OAuth 设备授权（RFC 8628），只能通过设备验证页面批准
+    pub const LOGIN_METHOD_DEVICE: &str = "device";
+
+    /// Web 端上报的 client_info 最大长度
+    pub const CLIENT_INFO_MAX_LEN: usize = 256;
 }
 
 /// JWT 相关常量
@@ -39,6 +87,267 @@ pub mod cors {
 pub mod websocket {
     /// WebSocket 连接超时时间（秒）- 5 分钟
     pub const SESSION_TIMEOUT_SECONDS: u64 = 300;
+
+    /// WebSocket 消息协议版本号，随每条服务器消息下发
+    pub const PROTOCOL_VERSION: u32 = 1;
+
+    /// 每个用户允许的最大 `/v2/ws` 连接数（多设备）
+    pub const MAX_CONNECTIONS_PER_USER: usize = 10;
+
+    /// SSE 保活注释的发送间隔（秒）
+    pub const SSE_KEEPALIVE_SECONDS: u64 = 15;
+
+    /// 长轮询最长等待时间（秒）
+    pub const LONG_POLL_MAX_WAIT_SECONDS: u64 = 30;
+
+    /// 每个会话允许的最大订阅者数量（如同时打开的标签页）
+    pub const MAX_SUBSCRIBERS_PER_SESSION: usize = 5;
+
+    /// 跨实例推送使用的 Postgres NOTIFY 通道
+    pub const BACKPLANE_CHANNEL: &str = "ws_events";
+
+    /// NOTIFY 载荷上限（字节），Postgres 限制为 8000 字节
+    pub const BACKPLANE_MAX_PAYLOAD_BYTES: usize = 7900;
+
+    /// 监听连接断开后的重连间隔（秒）
+    pub const BACKPLANE_RECONNECT_SECONDS: u64 = 5;
+}
+
+/// 设备密钥相关常量
+pub mod device_key {
+    /// 设备签名消息版本号
+    pub const SIGNATURE_VERSION: u32 = 1;
+
+    /// 签名动作：确认登录
+    pub const ACTION_CONFIRM: &str = "confirm";
+
+    /// 签名动作：拒绝登录
+    pub const ACTION_REJECT: &str = "reject";
+
+    /// 签名动作：注册新设备，签名对象为新设备的公钥
+    pub const ACTION_REGISTER: &str = "register";
+
+    /// 签名动作：吊销设备，签名对象为被吊销的设备 ID
+    pub const ACTION_REVOKE: &str = "revoke";
+
+    /// 签名时间戳与服务器时间允许的最大偏差（秒）
+    pub const SIGNATURE_MAX_SKEW_SECONDS: i64 = 300;
+
+    /// nonce 最小长度
+    pub const NONCE_MIN_LEN: usize = 16;
+
+    /// nonce 最大长度
+    pub const NONCE_MAX_LEN: usize = 128;
+
+    /// 每个用户最多可注册的设备数量
+    pub const MAX_DEVICES_PER_USER: u64 = 10;
+
+    /// 设备名称最大长度
+    pub const NAME_MAX_LEN: usize = 64;
+
+    /// 再次认证时密码错误次数的统计窗口（秒）- 15 分钟
+    pub const STEP_UP_FAILURE_WINDOW_SECONDS: i64 = 900;
+
+    /// 统计窗口内允许的最大密码错误次数，超过后暂时只能使用设备签名认证
+    pub const STEP_UP_MAX_FAILURES: u64 = 5;
+}
+
+/// OAuth 2.0 设备授权（RFC 8628）相关常量
+pub mod device_grant {
+    /// token 接口的 grant_type
+    pub const GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+    /// 默认最小轮询间隔（秒）
+    pub const POLL_INTERVAL_SECONDS: i32 = 5;
+
+    /// 轮询过快时（slow_down）增加的间隔（秒）
+    pub const SLOW_DOWN_INCREMENT_SECONDS: i32 = 5;
+
+    /// device_code 的随机字节数
+    pub const DEVICE_CODE_BYTES: usize = 32;
+
+    /// 用户码字符集：去掉元音与易混淆字符，避免组成单词或输错
+    pub const USER_CODE_ALPHABET: &[u8] = b"BCDFGHJKLMNPQRSTVWXZ";
+
+    /// 用户码长度（不含分隔符），展示为 XXXX-XXXX
+    pub const USER_CODE_LENGTH: usize = 8;
+
+    /// client_id 最大长度
+    pub const CLIENT_ID_MAX_LEN: usize = 128;
+}
+
+/// OAuth 2.0 授权服务器相关常量
+pub mod oauth {
+    /// grant_type：授权码
+    pub const GRANT_AUTHORIZATION_CODE: &str = "authorization_code";
+
+    /// grant_type：刷新 token
+    pub const GRANT_REFRESH_TOKEN: &str = "refresh_token";
+
+    /// grant_type：客户端凭证（服务账号）
+    pub const GRANT_CLIENT_CREDENTIALS: &str = "client_credentials";
+
+    /// 使用 JWT 断言认证客户端（RFC 7523 §2.2）
+    pub const CLIENT_ASSERTION_TYPE_JWT_BEARER: &str = "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
+
+    /// client_assertion 的最长有效期（秒），超过的断言直接拒绝
+    pub const CLIENT_ASSERTION_MAX_LIFETIME_SECONDS: i64 = 300;
+
+    /// 服务账号 access token 有效期（秒）- 15 分钟，不续签也不签发 refresh token
+    pub const SERVICE_TOKEN_TTL_SECONDS: usize = 900;
+
+    /// 授权码有效期（秒）
+    pub const AUTHORIZATION_CODE_TTL_SECONDS: i64 = 60;
+
+    /// access token 有效期（秒）- 1 小时
+    pub const ACCESS_TOKEN_TTL_SECONDS: usize = 3600;
+
+    /// refresh token 有效期（秒）- 30 天
+    pub const REFRESH_TOKEN_TTL_SECONDS: i64 = 30 * 86400;
+
+    /// 授权码、refresh token、client_secret 的随机字节数
+    pub const SECRET_BYTES: usize = 32;
+
+    /// 支持的 PKCE 方法（不支持 plain）
+    pub const PKCE_METHOD_S256: &str = "S256";
+
+    /// PKCE code_verifier 最小长度（RFC 7636 §4.1）
+    pub const PKCE_VERIFIER_MIN_LEN: usize = 43;
+
+    /// PKCE code_verifier 最大长度
+    pub const PKCE_VERIFIER_MAX_LEN: usize = 128;
+
+    /// 每个客户端最多登记的回调地址数量
+    pub const MAX_REDIRECT_URIS: usize = 10;
+
+    /// 客户端名称最大长度
+    pub const CLIENT_NAME_MAX_LEN: usize = 64;
+
+    /// OpenID Connect：申请 id_token
+    pub const SCOPE_OPENID: &str = "openid";
+
+    /// OpenID Connect：基本资料（preferred_username 等）
+    pub const SCOPE_PROFILE: &str = "profile";
+
+    /// OpenID Connect：邮箱
+    pub const SCOPE_EMAIL: &str = "email";
+
+    /// OpenID Connect：手机号
+    pub const SCOPE_PHONE: &str = "phone";
+
+    /// id_token 有效期（秒）
+    pub const ID_TOKEN_TTL_SECONDS: i64 = 3600;
+
+    /// 授权请求中 nonce 的最大长度
+    pub const NONCE_MAX_LEN: usize = 255;
+
+    /// OAuth access token 的 `typ` 声明（RFC 9068），用于与用户登录会话的 token 区分；此类 token 不续签
+    pub const ACCESS_TOKEN_TYP: &str = "at+jwt";
+
+    /// OAuth access token 可以访问的 `/v2` 接口及所需 scope：(方法, 路径, scope)
+    ///
+    /// 这些都是用户资源，只接受代表用户的 token；服务账号 token 不代表任何用户，一律拒绝。
+    /// 未列出的接口只接受用户本人登录的会话
+    pub const RESOURCE_SCOPES: &[(&str, &str, &str)] = &[("GET", "/v2/user/me", SCOPE_PROFILE)];
+}
+
+/// 第三方登录相关常量
+pub mod social_login {
+    /// 登录 state 有效期（秒）- 10 分钟
+    pub const STATE_TTL_SECONDS: i64 = 600;
+
+    /// 请求提供方接口的超时时间（秒）
+    pub const HTTP_TIMEOUT_SECONDS: u64 = 10;
+
+    /// 请求提供方接口时使用的 User-Agent（GitHub API 要求必须携带）
+    pub const USER_AGENT: &str = "rust-frame-social-login";
+
+    /// 登录方式，用于新登录通知
+    pub const LOGIN_METHOD: &str = "social";
+
+    /// 保存 state 哈希的 cookie，回调时与 URL 中的 state 比对，防止登录 CSRF
+    pub const STATE_COOKIE: &str = "social_login_state";
+
+    /// state cookie 的路径，只在第三方登录接口下发送
+    pub const STATE_COOKIE_PATH: &str = "/v1/auth/social";
+}
+
+/// API Key 相关常量
+pub mod api_key {
+    /// key 明文前缀，便于在日志、代码仓库中识别泄露的 key
+    pub const KEY_PREFIX: &str = "sk_";
+
+    /// 保存并展示给用户的 key 开头长度（含前缀）
+    pub const DISPLAY_PREFIX_LEN: usize = 11;
+
+    /// scope：只读（GET / HEAD / OPTIONS）
+    pub const SCOPE_READ: &str = "read";
+
+    /// scope：读写（包含只读）
+    pub const SCOPE_WRITE: &str = "write";
+
+    /// 每个用户最多可持有的有效 key 数量
+    pub const MAX_KEYS_PER_USER: u64 = 20;
+
+    /// key 名称最大长度
+    pub const NAME_MAX_LEN: usize = 64;
+
+    /// 有效期上限（天）
+    pub const MAX_EXPIRES_IN_DAYS: i64 = 365;
+
+    /// 最近使用时间的更新间隔（秒），避免每个请求都写数据库
+    pub const LAST_USED_UPDATE_INTERVAL_SECONDS: i64 = 60;
+}
+
+/// 管理后台相关常量
+pub mod admin {
+    /// 列表接口默认每页条数
+    pub const DEFAULT_PAGE_SIZE: u64 = 20;
+
+    /// 列表接口每页条数上限
+    pub const MAX_PAGE_SIZE: u64 = 100;
+
+    /// 搜索关键字最大长度
+    pub const SEARCH_MAX_LEN: usize = 128;
+
+    /// 批量操作单次最多处理的用户数
+    pub const MAX_BATCH_SIZE: usize = 500;
+
+    /// 批量导入单次最多处理的行数；带密码的行需要计算 bcrypt，耗时随行数线性增长
+    pub const MAX_IMPORT_ROWS: usize = 200;
+
+    /// 未设置密码的导入用户保存的密码哈希，不是合法的 bcrypt 哈希，任何密码都无法通过校验
+    pub const UNUSABLE_PASSWORD_HASH: &str = "!";
+
+    /// 导入用户的 user_id 最大长度
+    pub const USER_ID_MAX_LEN: usize = 64;
+}
+
+/// 用户账号自助管理相关常量
+pub mod account {
+    /// 密码最小长度
+    pub const PASSWORD_MIN_LEN: usize = 8;
+
+    /// 密码最大字节数，bcrypt 只使用前 72 字节
+    pub const PASSWORD_MAX_BYTES: usize = 72;
+
+    /// 昵称最大长度
+    pub const DISPLAY_NAME_MAX_LEN: usize = 64;
+
+    /// 头像地址最大长度
+    pub const AVATAR_URL_MAX_LEN: usize = 512;
+
+    /// 更换邮箱 / 手机号的验证码长度（数字）
+    pub const VERIFICATION_CODE_LENGTH: usize = 6;
+
+    /// 验证码有效期（秒）- 10 分钟
+    pub const VERIFICATION_CODE_TTL_SECONDS: i64 = 600;
+
+    /// 重新发送验证码的最短间隔（秒）
+    pub const VERIFICATION_RESEND_SECONDS: i64 = 60;
+
+    /// 单个验证码允许的最大失败次数，超过后需重新获取
+    pub const VERIFICATION_MAX_ATTEMPTS: i32 = 5;
 }
 
 /// 邮件相关常量
@@ -63,6 +372,12 @@ pub mod http {
 
     /// Bearer token 前缀（小写变体）
     pub const BEARER_PREFIX_LOWER: &str = "bearer ";
+
+    /// API Key header 名称
+    pub const API_KEY_HEADER: &str = "X-API-Key";
+
+    /// Authorization header 中的 API Key 前缀
+    pub const API_KEY_PREFIX: &str = "ApiKey ";
 }
 
 /// 会话状态相关常量
@@ -83,6 +398,12 @@ pub mod session {
     pub const STATUS_EXPIRED: &str = "expired";
 }
 
+/// 后台维护任务相关常量
+pub mod maintenance {
+    /// 过期数据清理任务的执行间隔（秒）
+    pub const INTERVAL_SECONDS: u64 = 60;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,12 +411,41 @@ mod tests {
     #[test]
     fn test_constants_are_positive() {
         assert!(qr_code::TTL_SECONDS > 0);
+        assert!(qr_code::SWEEP_INTERVAL_SECONDS > 0);
+        assert!(qr_code::RETENTION_SECONDS > 0);
+        assert!(maintenance::INTERVAL_SECONDS > 0);
         assert!(jwt::RENEWAL_THRESHOLD_SECONDS > 0);
         assert!(cors::MAX_AGE > 0);
+        assert!(websocket::BACKPLANE_RECONNECT_SECONDS > 0);
+        assert!(websocket::MAX_SUBSCRIBERS_PER_SESSION > 0);
+        assert!(websocket::SSE_KEEPALIVE_SECONDS > 0);
+        assert!(websocket::MAX_CONNECTIONS_PER_USER > 0);
+        assert!(websocket::LONG_POLL_MAX_WAIT_SECONDS > 0);
+        assert!(websocket::BACKPLANE_MAX_PAYLOAD_BYTES < 8000);
+        assert!(device_key::SIGNATURE_MAX_SKEW_SECONDS > 0);
+        assert!(device_key::NONCE_MIN_LEN <= device_key::NONCE_MAX_LEN);
+        assert!(device_key::STEP_UP_FAILURE_WINDOW_SECONDS > 0 && device_key::STEP_UP_MAX_FAILURES > 0);
+        assert!(device_grant::POLL_INTERVAL_SECONDS > 0);
+        assert!(device_grant::USER_CODE_LENGTH % 2 == 0);
+        assert!(oauth::AUTHORIZATION_CODE_TTL_SECONDS > 0);
+        assert!(oauth::PKCE_VERIFIER_MIN_LEN <= oauth::PKCE_VERIFIER_MAX_LEN);
+        assert!(oauth::ID_TOKEN_TTL_SECONDS > 0);
+        assert!(oauth::SERVICE_TOKEN_TTL_SECONDS > 0);
+        assert!(oauth::CLIENT_ASSERTION_MAX_LIFETIME_SECONDS > 0);
+        assert!(social_login::STATE_TTL_SECONDS > 0);
+        assert!(api_key::MAX_EXPIRES_IN_DAYS > 0);
+        assert!(api_key::DISPLAY_PREFIX_LEN > api_key::KEY_PREFIX.len());
+        assert!(admin::DEFAULT_PAGE_SIZE > 0 && admin::DEFAULT_PAGE_SIZE <= admin::MAX_PAGE_SIZE);
+        assert!(admin::MAX_BATCH_SIZE > 0 && admin::MAX_IMPORT_ROWS > 0);
+        assert!(account::PASSWORD_MIN_LEN <= account::PASSWORD_MAX_BYTES);
+        assert!(account::VERIFICATION_CODE_TTL_SECONDS > account::VERIFICATION_RESEND_SECONDS);
     }
 
     #[test]
     fn test_image_size_constraints() {
         assert!(qr_code::MIN_IMAGE_SIZE <= qr_code::MAX_IMAGE_SIZE);
+        assert!(qr_code::MIN_IMAGE_SIZE <= qr_code::DEFAULT_IMAGE_SIZE);
+        assert!(qr_code::DEFAULT_IMAGE_SIZE <= qr_code::MAX_IMAGE_SIZE);
+        assert!(qr_code::DEFAULT_MARGIN <= qr_code::MAX_MARGIN);
     }
 }
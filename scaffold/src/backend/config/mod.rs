@@ -1,6 +1,12 @@
 pub mod constants;
+pub mod policy;
 
 // 重新导出常用常量，方便使用
 pub use constants::{
-    cors, email, http, jwt, qr_code, session, websocket,
+    account, admin, api_key, cors, device_grant, device_key, email, http, jwt, maintenance, oauth, qr_code, session,
+    social_login, websocket,
+};
+pub use policy::{
+    oauth_config, proxy_config, qr_image_config, qr_link_config, qr_login_policy, social_login_config,
+    ProxyConfig, QrLinkConfig, QrLoginPolicy, QrPayloadFormat, SocialProvider,
 };
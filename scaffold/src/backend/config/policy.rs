@@ -0,0 +1,450 @@
+/// 运行时策略配置
+///
+/// 与 `constants` 不同，这里的配置项可以通过环境变量在部署时调整，
+/// 进程内首次访问时读取一次并缓存。
+use once_cell::sync::Lazy;
+use std::env;
+use std::net::IpAddr;
+use tracing::warn;
+
+/// 扫码登录策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct QrLoginPolicy {
+    /// 是否允许管理员在确认扫码时指定其他用户登录
+    /// 环境变量: `QR_LOGIN_ALLOW_ADMIN_IMPERSONATION`，默认关闭
+    pub allow_admin_impersonation: bool,
+
+    /// App 端用户在 users 表中不存在时是否自动注册（扫码即注册）
+    /// 环境变量: `QR_LOGIN_SCAN_TO_REGISTER`，默认关闭
+    pub scan_to_register: bool,
+
+    /// 确认 / 拒绝登录时是否必须携带已注册设备的签名
+    /// 关闭时，只有已注册设备密钥的用户才需要签名
+    /// 环境变量: `QR_LOGIN_REQUIRE_DEVICE_SIGNATURE`，默认关闭
+    pub require_device_signature: bool,
+}
+
+impl QrLoginPolicy {
+    /// 从环境变量加载策略
+    pub fn from_env() -> Self {
+        Self {
+            allow_admin_impersonation: env_flag("QR_LOGIN_ALLOW_ADMIN_IMPERSONATION"),
+            scan_to_register: env_flag("QR_LOGIN_SCAN_TO_REGISTER"),
+            require_device_signature: env_flag("QR_LOGIN_REQUIRE_DEVICE_SIGNATURE"),
+        }
+    }
+}
+
+static QR_LOGIN_POLICY: Lazy<QrLoginPolicy> = Lazy::new(QrLoginPolicy::from_env);
+
+/// 获取全局的扫码登录策略
+pub fn qr_login_policy() -> &'static QrLoginPolicy {
+    &QR_LOGIN_POLICY
+}
+
+/// 二维码内容的编码格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QrPayloadFormat {
+    /// 带签名的 JSON 字符串
+    #[default]
+    Json,
+    /// `https://<base_url>/qr/<session_id>?exp=..&sig=..`，通用相机扫码可打开回退页面
+    Url,
+    /// `<scheme>://qr-login/<session_id>?exp=..&sig=..`，直接唤起 App
+    DeepLink,
+}
+
+impl QrPayloadFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "url" | "https" => Some(Self::Url),
+            "deeplink" | "deep_link" | "scheme" => Some(Self::DeepLink),
+            _ => None,
+        }
+    }
+}
+
+/// 二维码链接配置
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct QrLinkConfig {
+    /// 环境变量: `QR_LOGIN_PAYLOAD_FORMAT` (json / url / deeplink)，默认 json
+    pub payload_format: QrPayloadFormat,
+
+    /// 对外访问的站点根地址，如 `https://example.com`
+    /// 环境变量: `QR_LOGIN_BASE_URL`
+    pub base_url: Option<String>,
+
+    /// App 注册的自定义 scheme，如 `rustframe`
+    /// 环境变量: `QR_LOGIN_DEEP_LINK_SCHEME`
+    pub deep_link_scheme: Option<String>,
+}
+
+impl QrLinkConfig {
+    /// 从环境变量加载配置
+    pub fn from_env() -> Self {
+        Self {
+            payload_format: env::var("QR_LOGIN_PAYLOAD_FORMAT")
+                .ok()
+                .and_then(|v| QrPayloadFormat::parse(&v))
+                .unwrap_or_default(),
+            base_url: env_string("QR_LOGIN_BASE_URL").map(|v| v.trim_end_matches('/').to_string()),
+            deep_link_scheme: env_string("QR_LOGIN_DEEP_LINK_SCHEME")
+                .map(|v| v.trim_end_matches("://").to_string()),
+        }
+    }
+}
+
+static QR_LINK_CONFIG: Lazy<QrLinkConfig> = Lazy::new(QrLinkConfig::from_env);
+
+/// 获取全局的二维码链接配置
+pub fn qr_link_config() -> &'static QrLinkConfig {
+    &QR_LINK_CONFIG
+}
+
+/// 二维码图片配置
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct QrImageConfig {
+    /// 中心 Logo 图片路径（PNG/JPEG），未配置时不支持 Logo
+    /// 环境变量: `QR_LOGIN_LOGO_PATH`
+    pub logo_path: Option<String>,
+}
+
+impl QrImageConfig {
+    /// 从环境变量加载配置
+    pub fn from_env() -> Self {
+        Self {
+            logo_path: env_string("QR_LOGIN_LOGO_PATH"),
+        }
+    }
+}
+
+static QR_IMAGE_CONFIG: Lazy<QrImageConfig> = Lazy::new(QrImageConfig::from_env);
+
+/// 获取全局的二维码图片配置
+pub fn qr_image_config() -> &'static QrImageConfig {
+    &QR_IMAGE_CONFIG
+}
+
+/// OAuth 授权服务器配置
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OAuthConfig {
+    /// 自定义授权同意页面地址。配置后 `/authorize` 会带上原始查询参数重定向到该页面，
+    /// 由该页面调用 `POST /v2/oauth/authorize` 完成授权；未配置时使用内置页面。
+    /// 环境变量: `OAUTH_CONSENT_PAGE_URL`
+    pub consent_page_url: Option<String>,
+}
+
+impl OAuthConfig {
+    /// 从环境变量加载配置
+    pub fn from_env() -> Self {
+        Self {
+            consent_page_url: env_string("OAUTH_CONSENT_PAGE_URL"),
+        }
+    }
+}
+
+static OAUTH_CONFIG: Lazy<OAuthConfig> = Lazy::new(OAuthConfig::from_env);
+
+/// 获取全局的 OAuth 配置
+pub fn oauth_config() -> &'static OAuthConfig {
+    &OAUTH_CONFIG
+}
+
+/// 受信任的代理地址，`prefix_len` 为 CIDR 前缀长度（单个地址时为 32 / 128）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrustedProxy {
+    pub network: IpAddr,
+    pub prefix_len: u8,
+}
+
+impl TrustedProxy {
+    /// 解析 `10.0.0.1` 或 `10.0.0.0/8` 形式
+    pub fn parse(value: &str) -> Option<Self> {
+        let (addr, prefix) = match value.trim().split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix.parse::<u8>().ok()?)),
+            None => (value.trim(), None),
+        };
+        let network: IpAddr = addr.parse().ok()?;
+        let max_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = prefix.unwrap_or(max_len);
+        (prefix_len <= max_len).then_some(Self { network, prefix_len })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix_len as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix_len as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// 反向代理配置
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ProxyConfig {
+    /// 只有对端地址在此列表中时才采信 `Forwarded` / `X-Forwarded-For`
+    /// 环境变量: `TRUSTED_PROXIES`（逗号分隔的 IP 或 CIDR，如 `127.0.0.1,10.0.0.0/8`），默认为空
+    pub trusted_proxies: Vec<TrustedProxy>,
+}
+
+impl ProxyConfig {
+    /// 从环境变量加载配置，无法解析的条目会被忽略
+    pub fn from_env() -> Self {
+        let trusted_proxies = env_string("TRUSTED_PROXIES")
+            .unwrap_or_default()
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .filter_map(|entry| {
+                let proxy = TrustedProxy::parse(entry);
+                if proxy.is_none() {
+                    warn!("Invalid TRUSTED_PROXIES entry '{}', skipping", entry.trim());
+                }
+                proxy
+            })
+            .collect();
+
+        Self { trusted_proxies }
+    }
+
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|proxy| proxy.contains(ip))
+    }
+}
+
+static PROXY_CONFIG: Lazy<ProxyConfig> = Lazy::new(ProxyConfig::from_env);
+
+/// 获取全局的反向代理配置
+pub fn proxy_config() -> &'static ProxyConfig {
+    &PROXY_CONFIG
+}
+
+/// 第三方登录提供方（OAuth 2.0 / OpenID Connect）
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SocialProvider {
+    /// 提供方标识，出现在路由中，如 `github`
+    pub name: String,
+    /// 展示给用户的名称
+    pub display_name: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub authorize_url: String,
+    pub token_url: String,
+    pub userinfo_url: String,
+    /// 申请的 scope（空格分隔）
+    pub scopes: String,
+    /// 用户信息中唯一标识用户的字段
+    pub subject_field: String,
+    /// 用户信息中的邮箱字段
+    pub email_field: String,
+    /// 邮箱是否已验证的字段；未配置时邮箱视为未验证，不会用于关联已有账号
+    pub email_verified_field: Option<String>,
+}
+
+impl SocialProvider {
+    /// 按提供方标识读取配置，`lookup` 接收去掉 `SOCIAL_<NAME>_` 前缀后的键名
+    ///
+    /// `github`、`google` 内置了端点与字段映射，只需配置 client id / secret；
+    /// 其他提供方（如通用 OIDC）需要完整配置端点。缺少必填项时返回 `None`。
+    pub fn from_lookup(name: &str, lookup: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let preset = match name {
+            "github" => Self {
+                display_name: "GitHub".into(),
+                authorize_url: "https://github.com/login/oauth/authorize".into(),
+                token_url: "https://github.com/login/oauth/access_token".into(),
+                userinfo_url: "https://api.github.com/user".into(),
+                scopes: "read:user user:email".into(),
+                subject_field: "id".into(),
+                email_field: "email".into(),
+                ..Default::default()
+            },
+            "google" => Self {
+                display_name: "Google".into(),
+                authorize_url: "https://accounts.google.com/o/oauth2/v2/auth".into(),
+                token_url: "https://oauth2.googleapis.com/token".into(),
+                userinfo_url: "https://openidconnect.googleapis.com/v1/userinfo".into(),
+                scopes: "openid email profile".into(),
+                subject_field: "sub".into(),
+                email_field: "email".into(),
+                email_verified_field: Some("email_verified".into()),
+                ..Default::default()
+            },
+            _ => Self {
+                display_name: name.into(),
+                scopes: "openid email profile".into(),
+                subject_field: "sub".into(),
+                email_field: "email".into(),
+                email_verified_field: Some("email_verified".into()),
+                ..Default::default()
+            },
+        };
+
+        let provider = Self {
+            name: name.to_string(),
+            display_name: lookup("DISPLAY_NAME").unwrap_or(preset.display_name),
+            client_id: lookup("CLIENT_ID")?,
+            client_secret: lookup("CLIENT_SECRET")?,
+            authorize_url: lookup("AUTHORIZE_URL").unwrap_or(preset.authorize_url),
+            token_url: lookup("TOKEN_URL").unwrap_or(preset.token_url),
+            userinfo_url: lookup("USERINFO_URL").unwrap_or(preset.userinfo_url),
+            scopes: lookup("SCOPES").unwrap_or(preset.scopes),
+            subject_field: lookup("SUBJECT_FIELD").unwrap_or(preset.subject_field),
+            email_field: lookup("EMAIL_FIELD").unwrap_or(preset.email_field),
+            email_verified_field: lookup("EMAIL_VERIFIED_FIELD").or(preset.email_verified_field),
+        };
+
+        let complete = !provider.authorize_url.is_empty()
+            && !provider.token_url.is_empty()
+            && !provider.userinfo_url.is_empty();
+        complete.then_some(provider)
+    }
+}
+
+/// 第三方登录配置
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SocialLoginConfig {
+    /// 已启用的提供方
+    /// 环境变量: `SOCIAL_LOGIN_PROVIDERS`（逗号分隔，如 `github,google,corp`），
+    /// 每个提供方的配置为 `SOCIAL_<NAME>_CLIENT_ID`、`SOCIAL_<NAME>_CLIENT_SECRET`、
+    /// `SOCIAL_<NAME>_AUTHORIZE_URL`、`SOCIAL_<NAME>_TOKEN_URL`、`SOCIAL_<NAME>_USERINFO_URL`、
+    /// `SOCIAL_<NAME>_SCOPES` 及字段映射 `SOCIAL_<NAME>_SUBJECT_FIELD` 等
+    pub providers: Vec<SocialProvider>,
+
+    /// 登录完成后跳转的前端地址，token 放在 URL fragment 中；未配置时回调直接返回 JSON
+    /// 环境变量: `SOCIAL_LOGIN_REDIRECT_URL`
+    pub redirect_url: Option<String>,
+}
+
+impl SocialLoginConfig {
+    /// 从环境变量加载配置，配置不完整的提供方会被忽略
+    pub fn from_env() -> Self {
+        let providers = env_string("SOCIAL_LOGIN_PROVIDERS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|name| name.trim().to_ascii_lowercase())
+            .filter(|name| !name.is_empty())
+            .filter_map(|name| {
+                let prefix = format!("SOCIAL_{}_", name.to_ascii_uppercase().replace('-', "_"));
+                let provider = SocialProvider::from_lookup(&name, |key| env_string(&format!("{}{}", prefix, key)));
+                if provider.is_none() {
+                    warn!("Social login provider '{}' is not fully configured, skipping", name);
+                }
+                provider
+            })
+            .collect();
+
+        Self {
+            providers,
+            redirect_url: env_string("SOCIAL_LOGIN_REDIRECT_URL"),
+        }
+    }
+
+    pub fn provider(&self, name: &str) -> Option<&SocialProvider> {
+        self.providers.iter().find(|p| p.name == name)
+    }
+}
+
+static SOCIAL_LOGIN_CONFIG: Lazy<SocialLoginConfig> = Lazy::new(SocialLoginConfig::from_env);
+
+/// 获取全局的第三方登录配置
+pub fn social_login_config() -> &'static SocialLoginConfig {
+    &SOCIAL_LOGIN_CONFIG
+}
+
+/// 读取非空字符串环境变量
+fn env_string(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+/// 读取布尔型环境变量，未设置或无法识别时视为 false
+fn env_flag(name: &str) -> bool {
+    env::var(name).map(|v| parse_flag(&v)).unwrap_or(false)
+}
+
+fn parse_flag(value: &str) -> bool {
+    matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "1" | "true" | "yes" | "on"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_flag() {
+        assert!(parse_flag("1"));
+        assert!(parse_flag("true"));
+        assert!(parse_flag(" TRUE "));
+        assert!(parse_flag("on"));
+        assert!(!parse_flag("0"));
+        assert!(!parse_flag("false"));
+        assert!(!parse_flag(""));
+        assert!(!parse_flag("maybe"));
+    }
+
+    #[test]
+    fn test_parse_payload_format() {
+        assert_eq!(QrPayloadFormat::parse("JSON"), Some(QrPayloadFormat::Json));
+        assert_eq!(QrPayloadFormat::parse("url"), Some(QrPayloadFormat::Url));
+        assert_eq!(QrPayloadFormat::parse("deeplink"), Some(QrPayloadFormat::DeepLink));
+        assert_eq!(QrPayloadFormat::parse("png"), None);
+    }
+
+    #[test]
+    fn test_social_provider_presets() {
+        let github = SocialProvider::from_lookup("github", |key| match key {
+            "CLIENT_ID" => Some("id".into()),
+            "CLIENT_SECRET" => Some("secret".into()),
+            _ => None,
+        })
+        .expect("github preset should be complete");
+        assert_eq!(github.subject_field, "id");
+        assert_eq!(github.email_verified_field, None);
+
+        // 通用提供方必须配置端点
+        let lookup = |key: &str| match key {
+            "CLIENT_ID" | "CLIENT_SECRET" => Some("x".to_string()),
+            _ => None,
+        };
+        assert!(SocialProvider::from_lookup("corp", lookup).is_none());
+        assert!(SocialProvider::from_lookup("corp", |key| match key {
+            "AUTHORIZE_URL" | "TOKEN_URL" | "USERINFO_URL" => Some("https://idp.example.com".into()),
+            _ => lookup(key),
+        })
+        .is_some());
+        assert!(SocialProvider::from_lookup("google", |_| None).is_none());
+    }
+
+    #[test]
+    fn test_trusted_proxy_matching() {
+        let proxy = TrustedProxy::parse("10.0.0.0/8").unwrap();
+        assert!(proxy.contains("10.1.2.3".parse().unwrap()));
+        assert!(!proxy.contains("11.0.0.1".parse().unwrap()));
+        assert!(!proxy.contains("::1".parse().unwrap()));
+
+        let single = TrustedProxy::parse("::1").unwrap();
+        assert_eq!(single.prefix_len, 128);
+        assert!(single.contains("::1".parse().unwrap()));
+
+        assert!(TrustedProxy::parse("0.0.0.0/0").unwrap().contains("203.0.113.7".parse().unwrap()));
+        assert!(TrustedProxy::parse("10.0.0.0/33").is_none());
+        assert!(TrustedProxy::parse("proxy.local").is_none());
+    }
+
+    #[test]
+    fn test_default_policy_is_restrictive() {
+        let policy = QrLoginPolicy::default();
+        assert!(!policy.allow_admin_impersonation);
+        assert!(!policy.scan_to_register);
+        assert!(!policy.require_device_signature);
+        assert!(ProxyConfig::default().trusted_proxies.is_empty());
+    }
+}
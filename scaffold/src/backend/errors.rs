@@ -17,6 +17,8 @@ pub enum ErrorCode {
     TokenExpired = 1004,
     LoginFailed = 1005,
     PermissionDenied = 1006,
+    DeviceSignatureInvalid = 1007,
+    TokenRevoked = 1008,
 
     // 请求相关 1100-1199
     BadRequest = 1100,
@@ -37,6 +39,7 @@ pub enum ErrorCode {
     QRCodePending = 1302,
     QRCodeScanned = 1303,
     QRCodeRejected = 1304,
+    QRCodeMatchMismatch = 1305,
 
     // 邮件相关 1400-1499
     EmailSendFailed = 1400,
@@ -63,6 +66,8 @@ impl ErrorCode {
             ErrorCode::TokenExpired => "token已过期",
             ErrorCode::LoginFailed => "登录失败",
             ErrorCode::PermissionDenied => "权限不足",
+            ErrorCode::DeviceSignatureInvalid => "设备签名无效",
+            ErrorCode::TokenRevoked => "token已被吊销",
 
             ErrorCode::BadRequest => "错误的请求",
             ErrorCode::InvalidParams => "无效的参数",
@@ -80,6 +85,7 @@ impl ErrorCode {
             ErrorCode::QRCodePending => "等待扫码",
             ErrorCode::QRCodeScanned => "已扫码，等待确认",
             ErrorCode::QRCodeRejected => "用户拒绝登录",
+            ErrorCode::QRCodeMatchMismatch => "验证数字不匹配，登录已被拒绝",
 
             ErrorCode::EmailSendFailed => "邮件发送失败",
             ErrorCode::EmailCodeInvalid => "验证码错误",
@@ -104,7 +110,9 @@ impl ErrorCode {
             | ErrorCode::TokenInvalid
             | ErrorCode::TokenExpired
             | ErrorCode::LoginFailed
-            | ErrorCode::PermissionDenied => 401,
+            | ErrorCode::PermissionDenied
+            | ErrorCode::DeviceSignatureInvalid
+            | ErrorCode::TokenRevoked => 401,
 
             ErrorCode::BadRequest
             | ErrorCode::InvalidParams
@@ -119,6 +127,7 @@ impl ErrorCode {
 
             ErrorCode::ResourceExpired
             | ErrorCode::QRCodeExpired
+            | ErrorCode::QRCodeMatchMismatch
             | ErrorCode::EmailCodeExpired => 400,
 
             ErrorCode::QRCodePending
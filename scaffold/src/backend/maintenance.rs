@@ -0,0 +1,63 @@
+use chrono::{Duration, Utc};
+use sea_orm::DbConn;
+use tracing::{error, info};
+use crate::backend::api::device::{purge_nonces_before, purge_step_up_failures_before};
+use crate::backend::api::oauth::{purge_client_assertions_before, purge_revoked_tokens_before};
+use crate::backend::api::social::purge_expired_states;
+use crate::backend::api::user::purge_contact_verifications_before;
+use crate::backend::config::{device_key, maintenance};
+
+/// 启动过期数据清理任务
+///
+/// 每隔 `maintenance::INTERVAL_SECONDS` 秒：
+/// 1. 删除已超过签名有效期的设备签名 nonce
+/// 2. 删除已超过统计窗口的设备再次认证密码错误记录
+/// 3. 删除已过期的第三方登录 state
+/// 4. 删除对应 token 已过期的吊销记录
+/// 5. 删除已过期的服务账号 client_assertion 记录
+/// 6. 删除已过期的邮箱 / 手机号更换验证码
+pub fn spawn_maintenance(db: DbConn) {
+    info!("🧹 Maintenance task started (interval: {}s)", maintenance::INTERVAL_SECONDS);
+
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(std::time::Duration::from_secs(
+            maintenance::INTERVAL_SECONDS,
+        ));
+
+        loop {
+            interval.tick().await;
+            purge_once(&db).await;
+        }
+    });
+}
+
+async fn purge_once(db: &DbConn) {
+    let now = Utc::now().naive_utc();
+
+    // 时间戳超出允许偏差的签名会被直接拒绝，对应的 nonce 无需继续保留
+    let nonce_before = now - Duration::seconds(device_key::SIGNATURE_MAX_SKEW_SECONDS * 2);
+    if let Err(e) = purge_nonces_before(db, nonce_before).await {
+        error!("Failed to purge device signature nonces: {}", e);
+    }
+
+    let failure_before = now - Duration::seconds(device_key::STEP_UP_FAILURE_WINDOW_SECONDS);
+    if let Err(e) = purge_step_up_failures_before(db, failure_before).await {
+        error!("Failed to purge device step-up failures: {}", e);
+    }
+
+    if let Err(e) = purge_expired_states(db, now).await {
+        error!("Failed to purge social login states: {}", e);
+    }
+
+    if let Err(e) = purge_revoked_tokens_before(db, now).await {
+        error!("Failed to purge revoked tokens: {}", e);
+    }
+
+    if let Err(e) = purge_client_assertions_before(db, now).await {
+        error!("Failed to purge client assertions: {}", e);
+    }
+
+    if let Err(e) = purge_contact_verifications_before(db, now).await {
+        error!("Failed to purge contact verifications: {}", e);
+    }
+}
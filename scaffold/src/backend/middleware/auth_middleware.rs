@@ -1,14 +1,17 @@
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    error, http::header, Error,
+    error, http::header, web, Error, HttpMessage,
 };
 use futures_util::future::{ready, LocalBoxFuture, Ready};
 use serde_json::json;
 use std::rc::Rc;
 use tracing::error;
 
+use crate::backend::AppState;
+use crate::backend::api::api_keys::{authenticate_api_key, scope_allows_method};
+use crate::backend::api::oauth::{is_token_revoked, oauth_route_allowed};
 use crate::backend::utils::jwt::verify_and_renew_jwt;
-use crate::backend::utils::extractors::extract_token_from_request;
+use crate::backend::utils::extractors::{api_key_from_headers, extract_token_from_head};
 use crate::backend::errors::{ErrorCode, error_response_with_path};
 
 fn is_ignored_path(_path: &str) -> bool {
@@ -17,6 +20,16 @@ fn is_ignored_path(_path: &str) -> bool {
     false
 }
 
+/// 认证依赖的数据库不可用
+fn service_unavailable(path: String) -> Error {
+    let error_resp = error_response_with_path(
+        ErrorCode::ServiceUnavailable,
+        ErrorCode::ServiceUnavailable.default_message(),
+        path,
+    );
+    error::ErrorServiceUnavailable(json!(error_resp))
+}
+
 pub struct Auth;
 
 impl<S, B> Transform<S, ServiceRequest> for Auth
@@ -63,8 +76,44 @@ impl<S, B> Service<ServiceRequest> for AuthMiddleware<S>
             return Box::pin(svc.call(req));
         }
 
-        // 提取 token 并进行验证
-        let token_result = extract_token_from_request(&req);
+        let app_state = req.app_data::<web::Data<AppState>>().cloned();
+
+        // API Key 认证：Claims 放入请求扩展供 extract_claims_from_request 使用，不续签
+        if let Some(api_key) = api_key_from_headers(req.headers()) {
+            return Box::pin(async move {
+                let Some(app_state) = app_state else {
+                    return Err(service_unavailable(path));
+                };
+                let claims = authenticate_api_key(&app_state.pg_client, &api_key).await.map_err(|e| {
+                    error!("Failed to authenticate API key: {:?}", e);
+                    service_unavailable(path.clone())
+                })?;
+
+                let Some(claims) = claims else {
+                    let error_resp = error_response_with_path(
+                        ErrorCode::TokenInvalid,
+                        "Invalid, expired or revoked API key",
+                        path,
+                    );
+                    return Err(error::ErrorUnauthorized(json!(error_resp)));
+                };
+
+                if !scope_allows_method(claims.scope.as_deref().unwrap_or_default(), req.method()) {
+                    let error_resp = error_response_with_path(
+                        ErrorCode::PermissionDenied,
+                        "API key scope does not allow this request",
+                        path,
+                    );
+                    return Err(error::ErrorForbidden(json!(error_resp)));
+                }
+
+                req.extensions_mut().insert(claims);
+                svc.call(req).await
+            });
+        }
+
+        // 提取 token 并进行验证（WebSocket 握手允许通过 access_token 查询参数传递）
+        let token_result = extract_token_from_head(req.head());
 
         let token = match token_result {
             Ok(t) => t,
@@ -82,9 +131,51 @@ impl<S, B> Service<ServiceRequest> for AuthMiddleware<S>
 
         Box::pin(async move {
             match verify_and_renew_jwt(&token) {
-                Ok(new_token) => {
-                    // 如果 token 被续签了，添加到响应头
+                Ok((new_token, claims)) => {
+                    // 已吊销的 token 不再放行，也不会被续签（与 /oauth/introspect 的判断一致）；
+                    // 无法检查吊销状态时拒绝请求
+                    let Some(app_state) = app_state else {
+                        return Err(service_unavailable(path));
+                    };
+                    let revoked = is_token_revoked(&app_state.pg_client, &token, &claims).await.map_err(|e| {
+                        error!("Failed to check token revocation: {:?}", e);
+                        service_unavailable(path.clone())
+                    })?;
+                    if revoked {
+                        let error_resp = error_response_with_path(
+                            ErrorCode::TokenRevoked,
+                            ErrorCode::TokenRevoked.default_message(),
+                            path,
+                        );
+                        return Err(error::ErrorUnauthorized(json!(error_resp)));
+                    }
+
+                    // 服务账号 token 的 user_id 是 client_id 而不是用户，不能访问 /v2 下的用户接口
+                    if claims.service_account {
+                        let error_resp = error_response_with_path(
+                            ErrorCode::PermissionDenied,
+                            "Service account tokens cannot access user endpoints",
+                            path,
+                        );
+                        return Err(error::ErrorForbidden(json!(error_resp)));
+                    }
+
+                    // OAuth 签发的 token 只能访问所授予 scope 覆盖的接口（见 oauth::RESOURCE_SCOPES）
+                    let scope = claims.scope.as_deref().unwrap_or_default();
+                    if claims.is_oauth_token() && !oauth_route_allowed(scope, req.method().as_str(), &path) {
+                        let error_resp = error_response_with_path(
+                            ErrorCode::PermissionDenied,
+                            "OAuth access token scope does not allow this request",
+                            path,
+                        );
+                        return Err(error::ErrorForbidden(json!(error_resp)));
+                    }
+
+                    // 如果 token 被续签了，添加到响应头；接口自己签发了新 token（如修改密码）时不覆盖
                     let mut response = svc.call(req).await?;
+                    if response.headers().contains_key(header::AUTHORIZATION) {
+                        return Ok(response);
+                    }
 
                     // 安全地创建 header value
                     let header_value = match header::HeaderValue::from_str(&format!("Bearer {}", new_token)) {
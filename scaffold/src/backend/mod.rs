@@ -3,8 +3,11 @@ use sea_orm::DbConn;
 pub mod models;
 pub mod app_router;
 pub mod ws_manager;
+pub mod ws_backplane;
+pub mod ws_protocol;
 pub mod errors;
 pub mod config;
+mod maintenance;
 mod middleware;
 mod utils;
 mod api;
@@ -9,3 +9,19 @@ pub mod sea_orm_active_enums;
 pub mod user_logs;
 pub mod users;
 pub mod qr_login_sessions;
+pub mod qr_session_events;
+pub mod device_keys;
+pub mod device_key_nonces;
+pub mod device_authorizations;
+pub mod oauth_clients;
+pub mod oauth_authorization_codes;
+pub mod oauth_refresh_tokens;
+pub mod oauth_consents;
+pub mod user_identities;
+pub mod social_login_states;
+pub mod revoked_tokens;
+pub mod api_keys;
+pub mod oauth_client_assertions;
+pub mod contact_verifications;
+pub mod oauth_grant_revocations;
+pub mod device_step_up_failures;
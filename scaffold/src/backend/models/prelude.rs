@@ -6,3 +6,19 @@ pub use super::password_resets::Entity as PasswordResets;
 pub use super::user_logs::Entity as UserLogs;
 pub use super::users::Entity as Users;
 pub use super::qr_login_sessions::Entity as QrLoginSessions;
+pub use super::qr_session_events::Entity as QrSessionEvents;
+pub use super::device_keys::Entity as DeviceKeys;
+pub use super::device_key_nonces::Entity as DeviceKeyNonces;
+pub use super::device_authorizations::Entity as DeviceAuthorizations;
+pub use super::oauth_clients::Entity as OauthClients;
+pub use super::oauth_authorization_codes::Entity as OauthAuthorizationCodes;
+pub use super::oauth_refresh_tokens::Entity as OauthRefreshTokens;
+pub use super::oauth_consents::Entity as OauthConsents;
+pub use super::user_identities::Entity as UserIdentities;
+pub use super::social_login_states::Entity as SocialLoginStates;
+pub use super::revoked_tokens::Entity as RevokedTokens;
+pub use super::api_keys::Entity as ApiKeys;
+pub use super::oauth_client_assertions::Entity as OauthClientAssertions;
+pub use super::contact_verifications::Entity as ContactVerifications;
+pub use super::oauth_grant_revocations::Entity as OauthGrantRevocations;
+pub use super::device_step_up_failures::Entity as DeviceStepUpFailures;
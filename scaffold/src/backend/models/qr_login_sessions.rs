@@ -20,10 +20,21 @@ pub struct Model {
     pub created_at: DateTime,
     pub expires_at: DateTime,
     pub updated_at: DateTime,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub client_ip: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub user_agent: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub client_info: Option<String>,
+    pub match_number: Option<i16>,
+    #[sea_orm(column_type = "Text")]
+    pub login_method: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_one = "super::device_authorizations::Entity")]
+    DeviceAuthorizations,
     #[sea_orm(
         belongs_to = "super::users::Entity",
         from = "Column::UserId",
@@ -32,6 +43,20 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Users,
+    #[sea_orm(has_many = "super::qr_session_events::Entity")]
+    QrSessionEvents,
+}
+
+impl Related<super::device_authorizations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DeviceAuthorizations.def()
+    }
+}
+
+impl Related<super::qr_session_events::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::QrSessionEvents.def()
+    }
 }
 
 impl Related<super::users::Entity> for Entity {
@@ -17,6 +17,8 @@ pub enum LogActionType {
     ResetPassword,
     #[sea_orm(string_value = "UPDATE_PROFILE")]
     UpdateProfile,
+    #[sea_orm(string_value = "QR_LOGIN_MISMATCH")]
+    QrLoginMismatch,
 }
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum,Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "user_role_type")]
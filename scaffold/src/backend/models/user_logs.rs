@@ -11,7 +11,7 @@ pub struct Model {
     #[sea_orm(column_type = "Text")]
     pub user_id: String,
     pub action: LogActionType,
-    #[sea_orm(column_type = "custom(\"inet\")")]
+    #[sea_orm(column_type = "custom(\"inet\")", select_as = "text", save_as = "inet")]
     pub ip_address: String,
     #[sea_orm(column_type = "Text", nullable)]
     pub user_agent: Option<String>,
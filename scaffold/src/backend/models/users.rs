@@ -21,38 +21,115 @@ pub struct Model {
     pub is_verified: Option<bool>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub display_name: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub avatar_url: Option<String>,
+    pub sessions_revoked_at: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::api_keys::Entity")]
+    ApiKeys,
     #[sea_orm(has_many = "super::auth_sessions::Entity")]
     AuthSessions,
+    #[sea_orm(has_many = "super::contact_verifications::Entity")]
+    ContactVerifications,
+    #[sea_orm(has_many = "super::device_keys::Entity")]
+    DeviceKeys,
+    #[sea_orm(has_many = "super::device_step_up_failures::Entity")]
+    DeviceStepUpFailures,
     #[sea_orm(has_many = "super::email_verifications::Entity")]
     EmailVerifications,
+    #[sea_orm(has_many = "super::oauth_authorization_codes::Entity")]
+    OauthAuthorizationCodes,
+    #[sea_orm(has_many = "super::oauth_clients::Entity")]
+    OauthClients,
+    #[sea_orm(has_many = "super::oauth_consents::Entity")]
+    OauthConsents,
+    #[sea_orm(has_many = "super::oauth_refresh_tokens::Entity")]
+    OauthRefreshTokens,
     #[sea_orm(has_many = "super::password_resets::Entity")]
     PasswordResets,
+    #[sea_orm(has_many = "super::user_identities::Entity")]
+    UserIdentities,
     #[sea_orm(has_many = "super::user_logs::Entity")]
     UserLogs,
 }
 
+impl Related<super::api_keys::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ApiKeys.def()
+    }
+}
+
 impl Related<super::auth_sessions::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::AuthSessions.def()
     }
 }
 
+impl Related<super::contact_verifications::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ContactVerifications.def()
+    }
+}
+
+impl Related<super::device_keys::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DeviceKeys.def()
+    }
+}
+
+impl Related<super::device_step_up_failures::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DeviceStepUpFailures.def()
+    }
+}
+
 impl Related<super::email_verifications::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::EmailVerifications.def()
     }
 }
 
+impl Related<super::oauth_authorization_codes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OauthAuthorizationCodes.def()
+    }
+}
+
+impl Related<super::oauth_clients::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OauthClients.def()
+    }
+}
+
+impl Related<super::oauth_consents::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OauthConsents.def()
+    }
+}
+
+impl Related<super::oauth_refresh_tokens::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OauthRefreshTokens.def()
+    }
+}
+
 impl Related<super::password_resets::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::PasswordResets.def()
     }
 }
 
+impl Related<super::user_identities::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserIdentities.def()
+    }
+}
+
 impl Related<super::user_logs::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserLogs.def()
@@ -0,0 +1,51 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use crate::backend::config::account;
+
+static EMAIL_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[^@\s]+@[^@\s]+\.[^@\s]+$").unwrap());
+static PHONE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\+?[0-9][0-9 -]{5,19}$").unwrap());
+
+/// 邮箱格式是否合法（只做基本格式检查，是否可达由验证码确认）
+pub fn is_valid_email(email: &str) -> bool {
+    EMAIL_RE.is_match(email)
+}
+
+/// 手机号格式是否合法：可带 `+` 国家码，允许空格与 `-` 分隔
+pub fn is_valid_phone(phone: &str) -> bool {
+    PHONE_RE.is_match(phone)
+}
+
+/// 校验密码长度：至少 `PASSWORD_MIN_LEN` 个字符，至多 `PASSWORD_MAX_BYTES` 字节
+pub fn validate_password(password: &str) -> Result<(), String> {
+    if password.chars().count() < account::PASSWORD_MIN_LEN {
+        return Err(format!("password must be at least {} characters", account::PASSWORD_MIN_LEN));
+    }
+    if password.len() > account::PASSWORD_MAX_BYTES {
+        return Err(format!("password must be at most {} bytes", account::PASSWORD_MAX_BYTES));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contact_formats() {
+        assert!(is_valid_email("alice@example.com"));
+        assert!(!is_valid_email("not-an-email"));
+        assert!(!is_valid_email("a b@example.com"));
+
+        assert!(is_valid_phone("+86 138-0013-8000"));
+        assert!(is_valid_phone("13800138000"));
+        assert!(!is_valid_phone("12345"));
+        assert!(!is_valid_phone("call me"));
+    }
+
+    #[test]
+    fn test_validate_password() {
+        assert!(validate_password("correct horse").is_ok());
+        assert!(validate_password("short").is_err());
+        assert!(validate_password(&"x".repeat(account::PASSWORD_MAX_BYTES + 1)).is_err());
+    }
+}
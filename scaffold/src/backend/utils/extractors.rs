@@ -1,6 +1,11 @@
-use actix_web::HttpMessage;
-use crate::backend::config::http;
-use crate::backend::errors::AppError;
+use actix_web::dev::RequestHead;
+use actix_web::http::header::{self, HeaderMap};
+use actix_web::{HttpMessage, HttpRequest};
+use serde::Deserialize;
+use std::net::IpAddr;
+use crate::backend::config::{http, proxy_config, ProxyConfig};
+use crate::backend::errors::{AppError, ErrorCode};
+use crate::backend::utils::jwt::{verify_jwt, Claims};
 
 /// 从 HTTP 请求中提取 JWT Token
 ///
@@ -26,7 +31,75 @@ use crate::backend::errors::AppError;
 /// assert_eq!(token, "my_token");
 /// ```
 pub fn extract_token_from_request(req: &impl HttpMessage) -> Result<String, AppError> {
-    req.headers()
+    bearer_token(req.headers()).ok_or_else(|| {
+        AppError::auth("Missing or invalid Authorization header. Expected format: Bearer <token>")
+    })
+}
+
+/// 从请求中提取并验证 JWT，返回当前用户的 Claims
+///
+/// 适用于 `/v2` 下需要知道当前用户身份的接口；通过 API Key 认证的请求
+/// 由 `Auth` 中间件放入请求扩展中的 Claims 直接返回
+pub fn extract_claims_from_request(req: &impl HttpMessage) -> Result<Claims, AppError> {
+    if let Some(claims) = req.extensions().get::<Claims>() {
+        return Ok(claims.clone());
+    }
+
+    let token = extract_token_from_request(req)?;
+    verify_jwt(&token)
+        .map(|data| data.claims)
+        .map_err(|_| AppError::custom(ErrorCode::TokenInvalid, "Invalid or expired token"))
+}
+
+#[derive(Deserialize)]
+struct AccessTokenQuery {
+    access_token: Option<String>,
+}
+
+/// 从请求中提取 JWT Token，WebSocket 握手请求额外支持查询参数
+///
+/// 浏览器的 WebSocket API 无法设置 Authorization header，因此 `Upgrade: websocket`
+/// 请求允许通过 `?access_token=<token>` 传递；普通请求仍只接受 Authorization header。
+pub fn extract_token_from_head(head: &RequestHead) -> Result<String, AppError> {
+    if let Some(token) = bearer_token(&head.headers) {
+        return Ok(token);
+    }
+
+    if is_websocket_upgrade(&head.headers) {
+        let query = head.uri.query().unwrap_or_default();
+        if let Some(token) = actix_web::web::Query::<AccessTokenQuery>::from_query(query)
+            .ok()
+            .and_then(|q| q.into_inner().access_token)
+            .filter(|t| !t.is_empty())
+        {
+            return Ok(token);
+        }
+    }
+
+    Err(AppError::auth("Missing or invalid Authorization header. Expected format: Bearer <token>"))
+}
+
+/// 提取 API Key，支持 `X-API-Key: <key>` 与 `Authorization: ApiKey <key>`
+pub fn api_key_from_headers(headers: &HeaderMap) -> Option<String> {
+    let from_header = headers
+        .get(http::API_KEY_HEADER)
+        .and_then(|header| header.to_str().ok());
+    let from_authorization = || {
+        headers
+            .get(http::AUTH_HEADER)
+            .and_then(|header| header.to_str().ok())
+            .and_then(|header_str| header_str.strip_prefix(http::API_KEY_PREFIX))
+    };
+
+    from_header
+        .or_else(from_authorization)
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(str::to_string)
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    headers
         .get(http::AUTH_HEADER)
         .and_then(|header| header.to_str().ok())
         .and_then(|header_str| {
@@ -34,12 +107,93 @@ pub fn extract_token_from_request(req: &impl HttpMessage) -> Result<String, AppE
                 .strip_prefix(http::BEARER_PREFIX)
                 .or_else(|| header_str.strip_prefix(http::BEARER_PREFIX_LOWER))
         })
-        .ok_or_else(|| {
-            AppError::auth("Missing or invalid Authorization header. Expected format: Bearer <token>")
-        })
         .map(|s| s.to_string())
 }
 
+fn is_websocket_upgrade(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::UPGRADE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("websocket"))
+}
+
+/// 获取客户端 IP
+///
+/// 默认使用对端地址；只有对端是 `TRUSTED_PROXIES` 中的代理时才采信 `Forwarded` /
+/// `X-Forwarded-For`，并从右往左跳过受信任的代理。结果总是合法的 IP 字符串，可直接写入 `inet` 类型字段。
+///
+/// # Arguments
+/// * `req` - HTTP 请求引用
+///
+/// # Returns
+/// * 客户端 IP，无法识别时返回 `0.0.0.0`
+pub fn client_ip(req: &HttpRequest) -> String {
+    resolve_client_ip(req.peer_addr().map(|addr| addr.ip()), req.headers(), proxy_config())
+        .map(|ip| ip.to_string())
+        .unwrap_or_else(|| "0.0.0.0".to_string())
+}
+
+fn resolve_client_ip(peer: Option<IpAddr>, headers: &HeaderMap, proxies: &ProxyConfig) -> Option<IpAddr> {
+    let mut client = peer?;
+    if !proxies.is_trusted(client) {
+        return Some(client);
+    }
+
+    for hop in forwarded_chain(headers).iter().rev() {
+        // 无法解析的地址（如 `unknown`）之前的内容都不可信，停在最后一个受信任的代理
+        let Some(ip) = parse_ip(hop) else { break };
+        client = ip;
+        if !proxies.is_trusted(ip) {
+            break;
+        }
+    }
+    Some(client)
+}
+
+/// 代理链上的地址，从客户端到最近的代理；优先使用 `Forwarded`（RFC 7239）
+fn forwarded_chain(headers: &HeaderMap) -> Vec<String> {
+    let values = |name: header::HeaderName| -> Vec<String> {
+        headers
+            .get_all(name)
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(|v| v.trim().to_string())
+            .collect()
+    };
+
+    let forwarded: Vec<String> = values(header::FORWARDED)
+        .iter()
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (key, value) = pair.trim().split_once('=')?;
+                key.eq_ignore_ascii_case("for").then(|| value.trim_matches('"').to_string())
+            })
+        })
+        .collect();
+    if !forwarded.is_empty() {
+        return forwarded;
+    }
+
+    values(header::HeaderName::from_static("x-forwarded-for"))
+}
+
+/// 获取请求的 User-Agent
+pub fn user_agent(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get(actix_web::http::header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+}
+
+/// 解析 IP，兼容 `ip:port`、`[ipv6]` 与 `[ipv6]:port` 形式
+fn parse_ip(addr: &str) -> Option<IpAddr> {
+    let addr = addr.trim();
+    addr.parse::<IpAddr>()
+        .ok()
+        .or_else(|| addr.parse::<std::net::SocketAddr>().ok().map(|s| s.ip()))
+        .or_else(|| addr.strip_prefix('[')?.strip_suffix(']')?.parse().ok())
+}
+
 /// 检查请求路径是否在排除列表中
 ///
 /// 用于中间件判断某些路径是否需要跳过认证
@@ -58,11 +212,12 @@ pub fn is_excluded_path(path: &str, excluded_paths: &[&str]) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use actix_web::test;
+    use actix_web::test as atest;
+    use crate::backend::config::policy::TrustedProxy;
 
     #[test]
     fn test_extract_token_valid() {
-        let req = test::TestRequest::default()
+        let req = atest::TestRequest::default()
             .insert_header(("Authorization", "Bearer test_token_123"))
             .to_http_request();
 
@@ -73,7 +228,7 @@ mod tests {
 
     #[test]
     fn test_extract_token_lowercase_bearer() {
-        let req = test::TestRequest::default()
+        let req = atest::TestRequest::default()
             .insert_header(("Authorization", "bearer test_token_123"))
             .to_http_request();
 
@@ -84,7 +239,7 @@ mod tests {
 
     #[test]
     fn test_extract_token_missing_header() {
-        let req = test::TestRequest::default()
+        let req = atest::TestRequest::default()
             .to_http_request();
 
         let result = extract_token_from_request(&req);
@@ -93,7 +248,7 @@ mod tests {
 
     #[test]
     fn test_extract_token_invalid_format() {
-        let req = test::TestRequest::default()
+        let req = atest::TestRequest::default()
             .insert_header(("Authorization", "InvalidFormat test_token"))
             .to_http_request();
 
@@ -101,6 +256,96 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_extract_token_from_websocket_query() {
+        let req = atest::TestRequest::default()
+            .uri("/v2/ws?access_token=ws_token")
+            .insert_header(("Upgrade", "websocket"))
+            .to_http_request();
+        assert_eq!(extract_token_from_head(req.head()).unwrap(), "ws_token");
+
+        // 普通请求不接受查询参数中的 token
+        let req = atest::TestRequest::default()
+            .uri("/v2/user/me?access_token=ws_token")
+            .to_http_request();
+        assert!(extract_token_from_head(req.head()).is_err());
+
+        // Authorization header 优先
+        let req = atest::TestRequest::default()
+            .uri("/v2/ws?access_token=ws_token")
+            .insert_header(("Upgrade", "websocket"))
+            .insert_header(("Authorization", "Bearer header_token"))
+            .to_http_request();
+        assert_eq!(extract_token_from_head(req.head()).unwrap(), "header_token");
+    }
+
+    #[test]
+    fn test_api_key_from_headers() {
+        let req = atest::TestRequest::default()
+            .insert_header(("X-API-Key", "sk_from_header"))
+            .to_http_request();
+        assert_eq!(api_key_from_headers(req.headers()).as_deref(), Some("sk_from_header"));
+
+        let req = atest::TestRequest::default()
+            .insert_header(("Authorization", "ApiKey sk_from_authorization"))
+            .to_http_request();
+        assert_eq!(api_key_from_headers(req.headers()).as_deref(), Some("sk_from_authorization"));
+
+        // Bearer token 不是 API Key
+        let req = atest::TestRequest::default()
+            .insert_header(("Authorization", "Bearer jwt"))
+            .to_http_request();
+        assert!(api_key_from_headers(req.headers()).is_none());
+    }
+
+    fn proxies(entries: &[&str]) -> ProxyConfig {
+        ProxyConfig {
+            trusted_proxies: entries.iter().map(|e| TrustedProxy::parse(e).unwrap()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_client_ip_ignores_forwarded_header_from_untrusted_peer() {
+        let req = atest::TestRequest::default()
+            .insert_header(("X-Forwarded-For", "203.0.113.7"))
+            .to_http_request();
+        let peer = "198.51.100.1".parse().ok();
+
+        assert_eq!(resolve_client_ip(peer, req.headers(), &proxies(&[])), peer);
+        assert_eq!(resolve_client_ip(peer, req.headers(), &proxies(&["10.0.0.0/8"])), peer);
+        assert_eq!(resolve_client_ip(None, req.headers(), &proxies(&[])), None);
+    }
+
+    #[test]
+    fn test_client_ip_from_trusted_proxy() {
+        let config = proxies(&["10.0.0.0/8"]);
+        let peer = "10.0.0.2".parse().ok();
+
+        // 客户端伪造的最左侧地址会被忽略，取最右侧不受信任的地址
+        let req = atest::TestRequest::default()
+            .insert_header(("X-Forwarded-For", "1.2.3.4, 203.0.113.7, 10.0.0.1"))
+            .to_http_request();
+        assert_eq!(resolve_client_ip(peer, req.headers(), &config), "203.0.113.7".parse().ok());
+
+        let req = atest::TestRequest::default()
+            .insert_header(("Forwarded", r#"for=192.0.2.60;proto=https, for="[2001:db8::1]:4711""#))
+            .to_http_request();
+        assert_eq!(resolve_client_ip(peer, req.headers(), &config), "2001:db8::1".parse().ok());
+
+        let req = atest::TestRequest::default()
+            .insert_header(("X-Forwarded-For", "not-an-ip"))
+            .to_http_request();
+        assert_eq!(resolve_client_ip(peer, req.headers(), &config), peer);
+    }
+
+    #[test]
+    fn test_parse_ip_with_port() {
+        assert_eq!(parse_ip("127.0.0.1:8080"), Some("127.0.0.1".parse().unwrap()));
+        assert_eq!(parse_ip("[::1]:8080"), Some("::1".parse().unwrap()));
+        assert_eq!(parse_ip("::1"), Some("::1".parse().unwrap()));
+        assert_eq!(parse_ip("[::1]"), Some("::1".parse().unwrap()));
+    }
+
     #[test]
     fn test_is_excluded_path() {
         let excluded = vec!["/ping", "/qr-login", "/ws"];
@@ -15,6 +15,11 @@ pub fn hash_str(data: &str) -> String {
     hash_bytes(data.as_bytes())
 }
 
+/// 常量时间比较，用于比较 secret 的哈希，避免通过响应时间逐字节猜测
+pub fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 pub trait Hash: fmt::Display {
     fn hash(&self) -> String {
         hash_bytes(self.to_string().as_bytes())
@@ -37,8 +42,9 @@ pub fn hash_password(password: &str) -> Result<String> {
     Ok(hash(password, DEFAULT_COST)?)
 }
 
+/// 哈希格式无效（例如导入用户的不可用密码标记）时视为密码错误
 pub fn verify_password(password: &str, hash: &str) -> bool {
-    verify(password, hash).unwrap()
+    verify(password, hash).unwrap_or(false)
 }
 
 #[cfg(test)]
@@ -60,6 +66,19 @@ mod tests {
         let hash = "+86 13818658534".hash();
         println!("{}", hash);
     }
+    #[test]
+    fn test_verify_password_rejects_invalid_hash() {
+        assert!(!verify_password("any password", "!"));
+        assert!(!verify_password("any password", ""));
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(&hash_str("secret"), &hash_str("secret")));
+        assert!(!constant_time_eq(&hash_str("secret"), &hash_str("other")));
+        assert!(!constant_time_eq("abc", "abcd"));
+    }
+
     #[test]
     fn test_hash_password() {
         let password = "123456";
@@ -1,21 +1,56 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation, TokenData};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::env;
 use chrono::Utc;
 use crate::backend::models::sea_orm_active_enums::UserRoleType;
-use crate::backend::config::jwt;
+use crate::backend::config::{jwt, oauth};
 
 /// 定义 JWT 的负载
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Claims {
     pub user_id: String, // 用户 ID
     pub username: String, // 用户链上地址
     pub role: Option<UserRoleType>,
     pub exp: usize,  // 过期时间戳 (Unix 时间)
+    /// 通过 OAuth 签发时的客户端 ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// 通过 OAuth 签发时授予的 scope（空格分隔）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// 服务账号通过 client_credentials 获得的 token，`user_id` 为服务账号的 client_id
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub service_account: bool,
+    /// 签发时间，由 `create_jwt` 填写；早于用户吊销全部会话的时间则视为已吊销
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iat: Option<usize>,
+    /// 签发时间（毫秒），由 `create_jwt` 填写；判断是否早于吊销时间时使用，避免同一秒内签发的旧 token 逃过吊销
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iat_ms: Option<i64>,
+    /// token 类型，OAuth 签发的 access token 为 `oauth::ACCESS_TOKEN_TYP`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+    /// 通过 API Key 认证时的 key ID，只存在于请求上下文中，不写入 JWT
+    #[serde(skip)]
+    pub api_key_id: Option<i64>,
 }
 
-/// 加载密钥
-pub fn load_keys() -> (EncodingKey, DecodingKey) {
+impl Claims {
+    /// 是否为 OAuth 签发的 token（包括服务账号），这类 token 只能访问所授予 scope 覆盖的接口
+    pub fn is_oauth_token(&self) -> bool {
+        self.client_id.is_some() || self.typ.as_deref() == Some(oauth::ACCESS_TOKEN_TYP)
+    }
+
+    /// 是否为用户本人登录会话的 token（不是 API Key、OAuth 客户端或服务账号）
+    pub fn is_first_party(&self) -> bool {
+        self.api_key_id.is_none() && !self.is_oauth_token() && !self.service_account
+    }
+}
+
+/// 加载密钥原始字节 (私钥为 PKCS#8 DER，公钥为 32 字节 Ed25519 公钥)
+pub fn load_key_bytes() -> (Vec<u8>, Vec<u8>) {
     let private_key_str = env::var("JWT_PRIVATE_KEY")
         .unwrap_or_else(|_| "GD8M1Qm17WXoukx8QqqfvYtM9zCSR83R1yZSuMbZ9JJtwayF39rabnwd26jMsLLw8LkHLT31x1TLZYT6ypKpPMgW1apMno2LrB4UBL56pZff5DukXkTf".to_string());
     let public_key_str = env::var("JWT_PUBLIC_KEY")
@@ -24,22 +59,65 @@ pub fn load_keys() -> (EncodingKey, DecodingKey) {
     let private_key_bytes = bs58::decode(private_key_str).into_vec().expect("Failed to decode jwt private key");
     let public_key_bytes = bs58::decode(public_key_str).into_vec().expect("Failed to decode jwt public key");
 
+    (private_key_bytes, public_key_bytes)
+}
+
+/// 加载密钥
+pub fn load_keys() -> (EncodingKey, DecodingKey) {
+    let (private_key_bytes, public_key_bytes) = load_key_bytes();
+
     let encoding_key = EncodingKey::from_ed_der(&private_key_bytes);
     let decoding_key = DecodingKey::from_ed_der(&public_key_bytes);
 
     (encoding_key, decoding_key)
 }
 
-/// 生成 JWT
+/// 生成 JWT，`iat` / `iat_ms` 设为当前时间
 pub fn create_jwt(new_user: &Claims) -> String {
     let header = Header::new(Algorithm::EdDSA);
     let (encoding_key, _) = load_keys();
+    let now = Utc::now();
+    let claims = Claims {
+        iat: Some(now.timestamp() as usize),
+        iat_ms: Some(now.timestamp_millis()),
+        ..new_user.clone()
+    };
+
+    encode(&header, &claims, &encoding_key).expect("Failed to create JWT")
+}
 
-    encode(&header, &new_user, &encoding_key).expect("Failed to create JWT")
+/// 签名公钥的 key ID：公钥 SHA-256 的 Base64URL 编码前 16 个字符
+pub fn key_id() -> String {
+    let (_, public_key_bytes) = load_key_bytes();
+    let mut kid = URL_SAFE_NO_PAD.encode(Sha256::digest(&public_key_bytes));
+    kid.truncate(16);
+    kid
 }
 
-/// 验证并续签 JWT
-pub fn verify_and_renew_jwt(token: &str) -> Result<String, jsonwebtoken::errors::Error> {
+/// 以 JWK 形式导出签名公钥（RFC 8037），供 OpenID Connect 客户端校验 id_token
+pub fn public_jwk() -> serde_json::Value {
+    let (_, public_key_bytes) = load_key_bytes();
+    serde_json::json!({
+        "kty": "OKP",
+        "crv": "Ed25519",
+        "x": URL_SAFE_NO_PAD.encode(&public_key_bytes),
+        "use": "sig",
+        "alg": "EdDSA",
+        "kid": key_id(),
+    })
+}
+
+/// 签发 OpenID Connect id_token，header 中携带 `kid`
+pub fn create_id_token<T: Serialize>(claims: &T) -> String {
+    let mut header = Header::new(Algorithm::EdDSA);
+    header.kid = Some(key_id());
+    let (encoding_key, _) = load_keys();
+
+    encode(&header, claims, &encoding_key).expect("Failed to create id_token")
+}
+
+/// 验证并续签 JWT，返回（续签后或原始的）token 与原始 token 的 Claims
+pub fn verify_and_renew_jwt(token: &str) -> Result<(String, Claims), jsonwebtoken::errors::Error> {
     // 解码验证 JWT
     let (_, decoding_key) = load_keys();
     let mut validation = Validation::new(Algorithm::EdDSA);
@@ -50,8 +128,9 @@ pub fn verify_and_renew_jwt(token: &str) -> Result<String, jsonwebtoken::errors:
     // 获取当前时间
     let now = Utc::now().timestamp() as usize;
 
-    // 检查过期时间，如果剩余时间少于阈值，则续签
-    if token_data.claims.exp - now <= jwt::RENEWAL_THRESHOLD_SECONDS {
+    // 检查过期时间，如果剩余时间少于阈值，则续签；
+    // OAuth 签发的 token（包括服务账号）不续签，到期后由客户端通过 refresh token 或重新申请获取
+    if !token_data.claims.is_oauth_token() && token_data.claims.exp - now <= jwt::RENEWAL_THRESHOLD_SECONDS {
         let renewed_claims = Claims {
             exp: now + jwt::DEFAULT_EXPIRATION_SECONDS,
             ..token_data.claims.clone()
@@ -59,11 +138,11 @@ pub fn verify_and_renew_jwt(token: &str) -> Result<String, jsonwebtoken::errors:
 
         // 生成新的 JWT
         let new_token = create_jwt(&renewed_claims);
-        return Ok(new_token);
+        return Ok((new_token, token_data.claims));
     }
 
     // 如果未过期或不需要续签，直接返回原始 JWT
-    Ok(token.to_string())
+    Ok((token.to_string(), token_data.claims))
 }
 
 pub fn verify_jwt(token: &str) -> Result<TokenData<Claims>, jsonwebtoken::errors::Error> {
@@ -86,6 +165,7 @@ mod tests {
             username: "test_user".to_string(),
             role: Some(UserRoleType::Admin),
             exp: (Utc::now().timestamp() as usize + 60 * 60 * 24), // 1天后过期
+            ..Default::default()
         };
 
         let token = create_jwt(&new_user);
@@ -97,6 +177,40 @@ mod tests {
         assert_eq!(claims.claims.role, new_user.role);
         assert_eq!(claims.claims.username, new_user.username);
         assert!(claims.claims.exp > Utc::now().timestamp() as usize);
+        assert!(claims.claims.iat.is_some());
+    }
+
+    #[test]
+    fn test_oauth_token_is_not_renewed() {
+        let oauth_claims = Claims {
+            user_id: "user123".to_string(),
+            exp: Utc::now().timestamp() as usize + 60,
+            client_id: Some("client_abc".to_string()),
+            typ: Some(oauth::ACCESS_TOKEN_TYP.to_string()),
+            ..Default::default()
+        };
+        let token = create_jwt(&oauth_claims);
+        let (renewed, claims) = verify_and_renew_jwt(&token).expect("Failed to verify valid JWT");
+        assert_eq!(renewed, token);
+        assert!(claims.is_oauth_token());
+
+        let session_claims = Claims { client_id: None, typ: None, ..oauth_claims };
+        let token = create_jwt(&session_claims);
+        let (renewed, claims) = verify_and_renew_jwt(&token).expect("Failed to verify valid JWT");
+        assert_ne!(renewed, token);
+        assert!(!claims.is_oauth_token());
+    }
+
+    #[test]
+    fn test_id_token_kid_matches_jwk() {
+        let token = create_id_token(&serde_json::json!({ "sub": "user123" }));
+        let header = jsonwebtoken::decode_header(&token).expect("Failed to decode header");
+        assert_eq!(header.alg, Algorithm::EdDSA);
+        assert_eq!(header.kid.as_deref(), public_jwk()["kid"].as_str());
+
+        let (_, public_key_bytes) = load_key_bytes();
+        let x = public_jwk()["x"].as_str().unwrap().to_string();
+        assert_eq!(URL_SAFE_NO_PAD.decode(x).unwrap(), public_key_bytes);
     }
 
     #[test]
@@ -106,6 +220,7 @@ mod tests {
             username: "test_user".to_string(),
             role: Some(UserRoleType::Admin),
             exp: (Utc::now().timestamp() as usize + 60  * 120), // 3分钟后过期
+            ..Default::default()
         };
 
         let token = create_jwt(&new_user);
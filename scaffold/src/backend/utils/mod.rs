@@ -1,4 +1,6 @@
 pub mod jwt;
 pub mod hash;
 pub mod extractors;
+pub mod contact;
+pub mod signing;
 pub mod validators;
\ No newline at end of file
@@ -0,0 +1,61 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use ring::signature::{Ed25519KeyPair, UnparsedPublicKey, ED25519};
+use crate::backend::errors::AppError;
+use crate::backend::utils::jwt::load_key_bytes;
+
+/// 使用服务端 Ed25519 私钥（与 JWT 签名共用同一把密钥）对消息签名
+///
+/// 返回 URL 安全的 Base64（无填充）编码签名，可直接放入 URL 查询参数
+pub fn sign_message(message: &[u8]) -> Result<String, AppError> {
+    let (private_key, _) = load_key_bytes();
+    let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(&private_key)
+        .map_err(|e| AppError::internal(format!("Invalid signing key: {}", e)))?;
+
+    Ok(URL_SAFE_NO_PAD.encode(key_pair.sign(message).as_ref()))
+}
+
+/// 使用服务端公钥校验 `sign_message` 生成的签名
+pub fn verify_server_signature(message: &[u8], signature: &str) -> bool {
+    let (_, public_key) = load_key_bytes();
+    verify_ed25519(&public_key, message, signature)
+}
+
+/// 使用任意 Ed25519 公钥校验 URL 安全 Base64 编码的签名
+pub fn verify_ed25519(public_key: &[u8], message: &[u8], signature: &str) -> bool {
+    let Ok(signature) = URL_SAFE_NO_PAD.decode(signature.trim_end_matches('=')) else {
+        return false;
+    };
+
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(message, &signature)
+        .is_ok()
+}
+
+/// 服务端公钥（bs58 编码），供 App 端离线校验签名
+pub fn server_public_key_bs58() -> String {
+    let (_, public_key) = load_key_bytes();
+    bs58::encode(public_key).into_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let message = b"qr-login|v1|session|1700000000";
+        let signature = sign_message(message).expect("Failed to sign");
+
+        assert!(verify_server_signature(message, &signature));
+        assert!(!verify_server_signature(b"tampered", &signature));
+        assert!(!verify_server_signature(message, "not-a-signature"));
+    }
+
+    #[test]
+    fn test_public_key_matches_signing_key() {
+        let public_key = bs58::decode(server_public_key_bs58()).into_vec().unwrap();
+        let signature = sign_message(b"hello").unwrap();
+
+        assert!(verify_ed25519(&public_key, b"hello", &signature));
+    }
+}
@@ -0,0 +1,185 @@
+use sea_orm::sqlx::postgres::PgListener;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbBackend, DbConn, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, Set, Statement,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use crate::backend::config::websocket;
+use crate::backend::models::prelude::QrSessionEvents;
+use crate::backend::models::qr_session_events;
+use crate::backend::ws_manager::WsManager;
+use crate::backend::ws_protocol::{UserEvent, WsEvent};
+
+/// 跨实例广播的 WebSocket 状态事件
+///
+/// 通过 Postgres `NOTIFY` 发布到所有实例，每个实例再推送给本地持有的连接。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WsBroadcast {
+    /// 发布事件的实例 ID，用于忽略自己发出的通知（本地已直接推送）
+    pub origin: String,
+    pub session_id: String,
+    /// 持久化后的事件 ID，未持久化时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<i64>,
+    pub event: WsEvent,
+}
+
+/// 跨实例广播的用户事件（`/v2/ws`）
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UserBroadcast {
+    /// 发布事件的实例 ID
+    pub origin: String,
+    pub user_id: String,
+    pub event: UserEvent,
+}
+
+/// NOTIFY 通道上传输的消息
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BackplaneMessage {
+    /// 扫码会话事件
+    Session(WsBroadcast),
+    /// 用户事件
+    User(UserBroadcast),
+}
+
+impl BackplaneMessage {
+    fn origin(&self) -> &str {
+        match self {
+            BackplaneMessage::Session(event) => &event.origin,
+            BackplaneMessage::User(event) => &event.origin,
+        }
+    }
+}
+
+/// 持久化会话事件，返回事件 ID
+pub async fn record_event(db: &DbConn, session_id: &str, event: &WsEvent) -> Result<i64, DbErr> {
+    let payload = serde_json::to_value(event)
+        .map_err(|e| DbErr::Custom(format!("Failed to serialize event: {}", e)))?;
+
+    let record = qr_session_events::ActiveModel {
+        session_id: Set(session_id.to_string()),
+        event: Set(payload),
+        created_at: Set(Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    Ok(record.id)
+}
+
+/// 查询会话在 `after` 之后的事件（按 ID 升序），`after` 为空时返回全部事件
+pub async fn events_after(
+    db: &DbConn,
+    session_id: &str,
+    after: Option<i64>,
+) -> Result<Vec<(i64, WsEvent)>, DbErr> {
+    let mut query = QrSessionEvents::find()
+        .filter(qr_session_events::Column::SessionId.eq(session_id));
+    if let Some(after) = after {
+        query = query.filter(qr_session_events::Column::Id.gt(after));
+    }
+
+    let records = query
+        .order_by_asc(qr_session_events::Column::Id)
+        .all(db)
+        .await?;
+
+    Ok(records
+        .into_iter()
+        .filter_map(|record| match serde_json::from_value::<WsEvent>(record.event) {
+            Ok(event) => Some((record.id, event)),
+            Err(e) => {
+                warn!("⚠️  Skipping malformed stored event {}: {}", record.id, e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// 通过 `pg_notify` 发布事件
+pub async fn publish(db: &DbConn, message: &BackplaneMessage) -> Result<(), DbErr> {
+    let payload = serde_json::to_string(message)
+        .map_err(|e| DbErr::Custom(format!("Failed to serialize event: {}", e)))?;
+
+    if payload.len() > websocket::BACKPLANE_MAX_PAYLOAD_BYTES {
+        return Err(DbErr::Custom(format!(
+            "Event payload too large for NOTIFY: {} bytes",
+            payload.len()
+        )));
+    }
+
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "SELECT pg_notify($1, $2)",
+        [websocket::BACKPLANE_CHANNEL.into(), payload.into()],
+    ))
+    .await?;
+    Ok(())
+}
+
+/// 启动跨实例事件监听
+///
+/// 使用连接池中的一个连接执行 `LISTEN`，收到其他实例发布的事件后推送给本地连接；
+/// 连接断开时按 `websocket::BACKPLANE_RECONNECT_SECONDS` 间隔重连。
+pub fn spawn_backplane_listener(db: DbConn, ws_manager: WsManager) {
+    actix_web::rt::spawn(async move {
+        loop {
+            if let Err(e) = listen(&db, &ws_manager).await {
+                error!("❌ WebSocket backplane listener failed: {}", e);
+            }
+            actix_web::rt::time::sleep(std::time::Duration::from_secs(
+                websocket::BACKPLANE_RECONNECT_SECONDS,
+            ))
+            .await;
+        }
+    });
+}
+
+async fn listen(db: &DbConn, ws_manager: &WsManager) -> Result<(), sea_orm::sqlx::Error> {
+    let mut listener = PgListener::connect_with(db.get_postgres_connection_pool()).await?;
+    listener.listen(websocket::BACKPLANE_CHANNEL).await?;
+    info!("📡 WebSocket backplane listening on channel: {}", websocket::BACKPLANE_CHANNEL);
+
+    loop {
+        let notification = listener.recv().await?;
+        match serde_json::from_str::<BackplaneMessage>(notification.payload()) {
+            Ok(message) if message.origin() == ws_manager.instance_id() => {}
+            Ok(BackplaneMessage::Session(event)) => ws_manager.deliver_local(&event).await,
+            Ok(BackplaneMessage::User(event)) => {
+                ws_manager.deliver_user_local(&event.user_id, &event.event).await
+            }
+            Err(e) => warn!("⚠️  Ignoring malformed backplane event: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_broadcast_roundtrip() {
+        let original = BackplaneMessage::Session(WsBroadcast {
+            origin: "instance-a".to_string(),
+            session_id: "session".to_string(),
+            event_id: Some(42),
+            event: WsEvent::confirmed(),
+        });
+        let payload = serde_json::to_string(&original).unwrap();
+        let parsed: BackplaneMessage = serde_json::from_str(&payload).unwrap();
+        assert_eq!(parsed, original);
+        assert_eq!(parsed.origin(), "instance-a");
+
+        let original = BackplaneMessage::User(UserBroadcast {
+            origin: "instance-b".to_string(),
+            user_id: "user".to_string(),
+            event: UserEvent::forced_logout("Token expired"),
+        });
+        let payload = serde_json::to_string(&original).unwrap();
+        let parsed: BackplaneMessage = serde_json::from_str(&payload).unwrap();
+        assert_eq!(parsed, original);
+    }
+}
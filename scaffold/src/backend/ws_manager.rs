@@ -1,95 +1,397 @@
 use actix_ws::Session;
+use sea_orm::DbConn;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::info;
+use tokio::sync::{mpsc, RwLock};
+use tracing::{error, info};
+use uuid::Uuid;
+use crate::backend::config::websocket;
+use crate::backend::ws_backplane::{self, BackplaneMessage, UserBroadcast, WsBroadcast};
+use crate::backend::ws_protocol::{UserEvent, WsEvent};
+
+/// 订阅者接收事件的方式
+#[derive(Clone)]
+enum Sink {
+    /// WebSocket 连接，直接发送消息
+    WebSocket(Session),
+    /// 进程内通道，供 SSE / 长轮询等非 WebSocket 传输使用
+    Channel(mpsc::UnboundedSender<WsBroadcast>),
+}
+
+/// 单个订阅者
+struct Subscriber {
+    // 连接 ID，用于断开时只移除自己
+    id: u64,
+    sink: Sink,
+}
+
+/// 已登录用户的 WebSocket 连接（`/v2/ws`）
+struct UserConnection {
+    id: u64,
+    session: Session,
+}
+
+/// 通过通道订阅会话事件（SSE、长轮询）
+///
+/// 终态事件推送后通道关闭，`recv` 返回 `None`；丢弃时自动取消订阅。
+pub struct Subscription {
+    manager: WsManager,
+    session_id: String,
+    id: u64,
+    receiver: mpsc::UnboundedReceiver<WsBroadcast>,
+}
+
+impl Subscription {
+    /// 等待下一个事件
+    pub async fn recv(&mut self) -> Option<WsBroadcast> {
+        self.receiver.recv().await
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let manager = self.manager.clone();
+        let session_id = std::mem::take(&mut self.session_id);
+        let id = self.id;
+        actix_web::rt::spawn(async move {
+            manager.remove_connection(&session_id, id).await;
+        });
+    }
+}
 
 /// WebSocket连接管理器
 /// 用于管理所有活跃的WebSocket连接，并支持向特定会话推送消息
+///
+/// 同一会话可以有多个订阅者（如多个浏览器标签页），数量上限为
+/// `websocket::MAX_SUBSCRIBERS_PER_SESSION`。
+///
+/// 另外按 `user_id` 维护已登录用户的连接（同一用户可有多个设备），
+/// 通过 `notify_user` 向用户推送强制下线、新设备登录提醒、管理员广播等事件。
+///
+/// 连接只保存在本实例内存中；配置了 backplane 后，状态推送会同时通过
+/// Postgres `NOTIFY` 广播给其他实例，由持有连接的实例完成推送。
 #[derive(Clone)]
 pub struct WsManager {
-    // session_id -> WebSocket Session
-    connections: Arc<RwLock<HashMap<String, Session>>>,
+    // session_id -> 订阅该会话的 WebSocket 连接
+    connections: Arc<RwLock<HashMap<String, Vec<Subscriber>>>>,
+    // user_id -> 该用户的 WebSocket 连接
+    user_connections: Arc<RwLock<HashMap<String, Vec<UserConnection>>>>,
+    // 连接 ID 生成器
+    next_connection_id: Arc<AtomicU64>,
+    // 本实例 ID，用于忽略自己发布的广播
+    instance_id: Arc<str>,
+    // 跨实例广播使用的数据库连接
+    backplane: Option<DbConn>,
 }
 
 impl WsManager {
-    /// 创建新的WebSocket管理器
+    /// 创建新的WebSocket管理器（仅本地推送）
     pub fn new() -> Self {
         info!("🔌 WebSocket Manager initialized");
         Self {
             connections: Arc::new(RwLock::new(HashMap::new())),
+            user_connections: Arc::new(RwLock::new(HashMap::new())),
+            next_connection_id: Arc::new(AtomicU64::new(1)),
+            instance_id: Uuid::new_v4().to_string().into(),
+            backplane: None,
         }
     }
 
-    /// 添加新的WebSocket连接
-    pub async fn add_connection(&self, session_id: String, session: Session) {
+    /// 创建启用 Postgres LISTEN/NOTIFY 跨实例推送的管理器
+    ///
+    /// 需要同时调用 `ws_backplane::spawn_backplane_listener` 接收其他实例的事件。
+    pub fn with_backplane(db: DbConn) -> Self {
+        let manager = Self {
+            backplane: Some(db),
+            ..Self::new()
+        };
+        info!("📡 WebSocket backplane enabled (instance: {})", manager.instance_id);
+        manager
+    }
+
+    /// 本实例 ID
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// 添加新的WebSocket连接，返回连接 ID
+    ///
+    /// 会话订阅者已达上限时返回 `None`，调用方应关闭该连接
+    pub async fn add_connection(&self, session_id: String, session: Session) -> Option<u64> {
+        self.add_subscriber(session_id, Sink::WebSocket(session)).await
+    }
+
+    /// 通过通道订阅会话事件，订阅者已达上限时返回 `None`
+    pub async fn subscribe(&self, session_id: &str) -> Option<Subscription> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let id = self.add_subscriber(session_id.to_string(), Sink::Channel(sender)).await?;
+        Some(Subscription {
+            manager: self.clone(),
+            session_id: session_id.to_string(),
+            id,
+            receiver,
+        })
+    }
+
+    async fn add_subscriber(&self, session_id: String, sink: Sink) -> Option<u64> {
         let mut connections = self.connections.write().await;
-        connections.insert(session_id.clone(), session);
-        info!("✅ WebSocket connected for session: {}", session_id);
-        info!("📊 Active connections: {}", connections.len());
+        let subscribers = connections.entry(session_id.clone()).or_default();
+        if subscribers.len() >= websocket::MAX_SUBSCRIBERS_PER_SESSION {
+            info!("⚠️  Too many subscribers for session: {}", session_id);
+            return None;
+        }
+
+        let id = self.next_connection_id.fetch_add(1, Ordering::Relaxed);
+        subscribers.push(Subscriber { id, sink });
+        info!("✅ Subscriber connected for session: {} (connection: {}, subscribers: {})", session_id, id, subscribers.len());
+        info!("📊 Active connections: {}", Self::count(&connections));
+        Some(id)
     }
 
     /// 移除WebSocket连接
     /// 
-    /// 注意：如果连接已被其他地方移除（如notify_status），此方法会静默返回
-    pub async fn remove_connection(&self, session_id: &str) {
+    /// 只移除指定 ID 的连接；如果连接已被其他地方移除（如notify_status），此方法会静默返回
+    pub async fn remove_connection(&self, session_id: &str, connection_id: u64) {
         let mut connections = self.connections.write().await;
-        if connections.remove(session_id).is_some() {
-            info!("🔌 WebSocket disconnected for session: {}", session_id);
-            info!("📊 Active connections: {}", connections.len());
+        let Some(subscribers) = connections.get_mut(session_id) else {
+            // 如果连接不存在，说明已被其他地方清理，不需要重复日志
+            return;
+        };
+
+        let before = subscribers.len();
+        subscribers.retain(|s| s.id != connection_id);
+        let removed = subscribers.len() < before;
+        if subscribers.is_empty() {
+            connections.remove(session_id);
+        }
+
+        if removed {
+            info!("🔌 WebSocket disconnected for session: {} (connection: {})", session_id, connection_id);
+            info!("📊 Active connections: {}", Self::count(&connections));
         }
-        // 如果连接不存在，说明已被其他地方清理，不需要重复日志
     }
 
-    /// 推送状态更新到指定会话
-    /// 
-    /// 注意：此方法会：
+    /// 推送事件到指定会话
+    ///
+    /// 启用 backplane 时先持久化事件（用于重连补发），再推送给本实例的连接并广播给其他实例；
+    /// 终态事件推送后会关闭连接
+    pub async fn notify(&self, session_id: &str, event: WsEvent) {
+        let event_id = match &self.backplane {
+            Some(db) => match ws_backplane::record_event(db, session_id, &event).await {
+                Ok(id) => Some(id),
+                Err(e) => {
+                    error!("❌ Failed to record event for session {}: {}", session_id, e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let event = WsBroadcast {
+            origin: self.instance_id.to_string(),
+            session_id: session_id.to_string(),
+            event_id,
+            event,
+        };
+        self.dispatch(event).await;
+    }
+
+    async fn dispatch(&self, event: WsBroadcast) {
+        self.deliver_local(&event).await;
+
+        if let Some(db) = &self.backplane {
+            let session_id = event.session_id.clone();
+            if let Err(e) = ws_backplane::publish(db, &BackplaneMessage::Session(event)).await {
+                error!("❌ Failed to publish status for session {}: {}", session_id, e);
+            }
+        }
+    }
+
+    /// 将事件推送给本实例持有的连接
+    ///
+    /// 终态事件会：
     /// 1. 从连接管理器中移除连接（避免重复访问）
     /// 2. 发送状态消息
     /// 3. 主动关闭WebSocket连接
-    pub async fn notify_status(&self, session_id: &str, status: &str, web_token: Option<&str>) {
-        let mut connections = self.connections.write().await;
+    pub async fn deliver_local(&self, event: &WsBroadcast) {
+        let close = event.event.is_terminal();
+        let sinks: Vec<(u64, Sink)> = if close {
+            self.connections
+                .write()
+                .await
+                .remove(&event.session_id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|s| (s.id, s.sink))
+                .collect()
+        } else {
+            self.connections
+                .read()
+                .await
+                .get(&event.session_id)
+                .map(|subscribers| subscribers.iter().map(|s| (s.id, s.sink.clone())).collect())
+                .unwrap_or_default()
+        };
 
-        if let Some(mut session) = connections.remove(session_id) {
-            // 释放锁，避免阻塞其他操作
-            drop(connections);
-            
-            let message = if let Some(token) = web_token {
-                format!(
-                    r#"{{"status":"{}","web_token":"{}","message":"Login successful"}}"#,
-                    status, token
-                )
-            } else {
-                format!(
-                    r#"{{"status":"{}","message":"Status updated"}}"#,
-                    status
-                )
-            };
-
-            info!("🔔 Pushing status update to session {}: {}", session_id, status);
-
-            // 发送消息
-            if let Err(e) = session.text(message).await {
-                info!("❌ Failed to send message: {}", e);
+        if sinks.is_empty() {
+            info!("⚠️  No local WebSocket connection found for session: {}", event.session_id);
+            return;
+        }
+
+        info!(
+            "🔔 Pushing status update to session {} ({} subscribers): {}",
+            event.session_id,
+            sinks.len(),
+            event.event.name()
+        );
+
+        let message = event.event.to_message_with_id(event.event_id);
+        for (id, sink) in sinks {
+            match sink {
+                Sink::WebSocket(mut session) => {
+                    // 发送消息
+                    if let Err(e) = session.text(message.clone()).await {
+                        info!("❌ Failed to send message: {}", e);
+                        if !close {
+                            self.remove_connection(&event.session_id, id).await;
+                        }
+                        continue;
+                    }
+
+                    if close {
+                        // 关闭连接（会触发ws_status中的清理逻辑，但连接已从HashMap移除）
+                        let _ = session.close(None).await;
+                    }
+                }
+                // 终态时通道随 sender 一起释放，接收方读完最后一个事件后结束
+                Sink::Channel(sender) => {
+                    if sender.send(event.clone()).is_err() && !close {
+                        self.remove_connection(&event.session_id, id).await;
+                    }
+                }
             }
+        }
 
-            // 关闭连接（会触发ws_status中的清理逻辑，但连接已从HashMap移除）
-            let _ = session.close(None).await;
+        if close {
+            info!("✅ Status pushed and connections closed for session: {}", event.session_id);
+        }
+    }
 
-            info!("✅ Status pushed and connection closed for session: {}", session_id);
+    /// 添加已登录用户的WebSocket连接，返回连接 ID
+    ///
+    /// 用户连接数已达上限时返回 `None`，调用方应关闭该连接
+    pub async fn add_user_connection(&self, user_id: &str, session: Session) -> Option<u64> {
+        let mut connections = self.user_connections.write().await;
+        let user_sessions = connections.entry(user_id.to_string()).or_default();
+        if user_sessions.len() >= websocket::MAX_CONNECTIONS_PER_USER {
+            info!("⚠️  Too many WebSocket connections for user: {}", user_id);
+            return None;
+        }
+
+        let id = self.next_connection_id.fetch_add(1, Ordering::Relaxed);
+        user_sessions.push(UserConnection { id, session });
+        info!("✅ User WebSocket connected: {} (connection: {}, devices: {})", user_id, id, user_sessions.len());
+        Some(id)
+    }
+
+    /// 移除已登录用户的WebSocket连接
+    pub async fn remove_user_connection(&self, user_id: &str, connection_id: u64) {
+        let mut connections = self.user_connections.write().await;
+        let Some(user_sessions) = connections.get_mut(user_id) else {
+            return;
+        };
+
+        let before = user_sessions.len();
+        user_sessions.retain(|c| c.id != connection_id);
+        let removed = user_sessions.len() < before;
+        if user_sessions.is_empty() {
+            connections.remove(user_id);
+        }
+
+        if removed {
+            info!("🔌 User WebSocket disconnected: {} (connection: {})", user_id, connection_id);
+        }
+    }
+
+    /// 推送事件给指定用户的所有设备
+    ///
+    /// 先推送给本实例的连接，再广播给其他实例；`ForcedLogout` 推送后关闭连接
+    pub async fn notify_user(&self, user_id: &str, event: UserEvent) {
+        self.deliver_user_local(user_id, &event).await;
+
+        if let Some(db) = &self.backplane {
+            let message = BackplaneMessage::User(UserBroadcast {
+                origin: self.instance_id.to_string(),
+                user_id: user_id.to_string(),
+                event,
+            });
+            if let Err(e) = ws_backplane::publish(db, &message).await {
+                error!("❌ Failed to publish event for user {}: {}", user_id, e);
+            }
+        }
+    }
+
+    /// 将用户事件推送给本实例持有的连接
+    pub async fn deliver_user_local(&self, user_id: &str, event: &UserEvent) {
+        let close = event.is_terminal();
+        let sessions: Vec<(u64, Session)> = if close {
+            self.user_connections
+                .write()
+                .await
+                .remove(user_id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|c| (c.id, c.session))
+                .collect()
         } else {
-            info!("⚠️  No active WebSocket connection found for session: {}", session_id);
+            self.user_connections
+                .read()
+                .await
+                .get(user_id)
+                .map(|conns| conns.iter().map(|c| (c.id, c.session.clone())).collect())
+                .unwrap_or_default()
+        };
+
+        if sessions.is_empty() {
+            return;
+        }
+
+        info!("🔔 Pushing {} event to user {} ({} devices)", event.name(), user_id, sessions.len());
+
+        let message = event.to_message();
+        for (id, mut session) in sessions {
+            if let Err(e) = session.text(message.clone()).await {
+                info!("❌ Failed to send message: {}", e);
+                if !close {
+                    self.remove_user_connection(user_id, id).await;
+                }
+                continue;
+            }
+
+            if close {
+                let _ = session.close(None).await;
+            }
         }
     }
 
+    fn count(connections: &HashMap<String, Vec<Subscriber>>) -> usize {
+        connections.values().map(Vec::len).sum()
+    }
+
     /// 获取当前活跃连接数
     pub async fn get_connection_count(&self) -> usize {
-        self.connections.read().await.len()
+        Self::count(&*self.connections.read().await)
+    }
+
+    /// 获取某个会话的订阅者数量
+    pub async fn subscriber_count(&self, session_id: &str) -> usize {
+        self.connections.read().await.get(session_id).map_or(0, Vec::len)
     }
 
     /// 检查某个会话是否有活跃连接
     pub async fn has_connection(&self, session_id: &str) -> bool {
-        self.connections.read().await.contains_key(session_id)
+        self.subscriber_count(session_id).await > 0
     }
 }
 
@@ -98,3 +400,77 @@ impl Default for WsManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_multiple_subscribers_per_session() {
+        let manager = WsManager::new();
+        let mut first = manager.subscribe("s1").await.unwrap();
+        let mut second = manager.subscribe("s1").await.unwrap();
+        let _other = manager.subscribe("s2").await.unwrap();
+
+        assert_eq!(manager.subscriber_count("s1").await, 2);
+        assert_eq!(manager.subscriber_count("s2").await, 1);
+        assert_eq!(manager.get_connection_count().await, 3);
+
+        // 非终态事件推送给该会话的全部订阅者
+        manager.notify("s1", WsEvent::scanned()).await;
+        assert_eq!(first.recv().await.unwrap().event, WsEvent::scanned());
+        assert_eq!(second.recv().await.unwrap().event, WsEvent::scanned());
+
+        // 移除其中一个不影响其他订阅者
+        manager.remove_connection("s1", first.id).await;
+        assert_eq!(manager.subscriber_count("s1").await, 1);
+        manager.notify("s1", WsEvent::scanned()).await;
+        assert_eq!(second.recv().await.unwrap().event, WsEvent::scanned());
+    }
+
+    #[actix_web::test]
+    async fn test_subscriber_cap_is_per_session() {
+        let manager = WsManager::new();
+        let mut subscriptions = Vec::new();
+        for _ in 0..websocket::MAX_SUBSCRIBERS_PER_SESSION {
+            subscriptions.push(manager.subscribe("s1").await.expect("below the cap"));
+        }
+
+        assert!(manager.subscribe("s1").await.is_none());
+        assert_eq!(manager.subscriber_count("s1").await, websocket::MAX_SUBSCRIBERS_PER_SESSION);
+        assert!(manager.subscribe("s2").await.is_some());
+    }
+
+    #[actix_web::test]
+    async fn test_session_removed_when_last_subscriber_leaves() {
+        let manager = WsManager::new();
+        let first = manager.subscribe("s1").await.unwrap();
+        let second = manager.subscribe("s1").await.unwrap();
+
+        manager.remove_connection("s1", first.id).await;
+        assert!(manager.has_connection("s1").await);
+        // 重复移除是安全的
+        manager.remove_connection("s1", first.id).await;
+        assert_eq!(manager.subscriber_count("s1").await, 1);
+
+        // 丢弃订阅时自动取消订阅
+        drop(second);
+        actix_web::rt::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert!(!manager.has_connection("s1").await);
+        assert!(!manager.connections.read().await.contains_key("s1"));
+    }
+
+    #[actix_web::test]
+    async fn test_terminal_event_closes_all_subscribers() {
+        let manager = WsManager::new();
+        let mut first = manager.subscribe("s1").await.unwrap();
+        let mut second = manager.subscribe("s1").await.unwrap();
+
+        manager.notify("s1", WsEvent::expired()).await;
+        assert_eq!(manager.subscriber_count("s1").await, 0);
+        for subscription in [&mut first, &mut second] {
+            assert_eq!(subscription.recv().await.unwrap().event, WsEvent::expired());
+            assert!(subscription.recv().await.is_none());
+        }
+    }
+}
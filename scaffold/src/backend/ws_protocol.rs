@@ -0,0 +1,322 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use crate::backend::config::websocket;
+use crate::backend::errors::ErrorCode;
+use crate::backend::models::qr_login_sessions;
+
+/// 服务器推送给客户端的 WebSocket 事件
+///
+/// 以 `status` 字段区分事件类型，兼容早期客户端按 `data.status` 判断状态的写法。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum WsEvent {
+    /// 连接建立
+    Connected { message: String },
+    /// App 已扫码，等待确认
+    Scanned { message: String },
+    /// 登录已确认
+    ///
+    /// 事件会持久化并经 NOTIFY 广播，因此不携带 Web 端 token；
+    /// 客户端收到后通过 `GET /v1/qr-login/status/{session_id}` 获取 token。
+    Confirmed { message: String },
+    /// 登录被拒绝或取消
+    Rejected { message: String },
+    /// 二维码已过期
+    Expired { message: String },
+    /// 错误（如命令无效、连接数超限）
+    Error { code: i32, message: String },
+    /// 心跳 / 对客户端 ping 命令的响应
+    Ping { timestamp: i64 },
+}
+
+impl WsEvent {
+    pub fn connected() -> Self {
+        WsEvent::Connected { message: "Waiting for confirmation".to_string() }
+    }
+
+    pub fn scanned() -> Self {
+        WsEvent::Scanned { message: "Scanned, waiting for confirmation".to_string() }
+    }
+
+    pub fn confirmed() -> Self {
+        WsEvent::Confirmed { message: "Login successful".to_string() }
+    }
+
+    pub fn rejected(message: &str) -> Self {
+        WsEvent::Rejected { message: message.to_string() }
+    }
+
+    pub fn expired() -> Self {
+        WsEvent::Expired { message: "QR code expired".to_string() }
+    }
+
+    pub fn error(code: ErrorCode, message: &str) -> Self {
+        WsEvent::Error { code: code as i32, message: message.to_string() }
+    }
+
+    pub fn ping() -> Self {
+        WsEvent::Ping { timestamp: Utc::now().timestamp() }
+    }
+
+    /// 事件名称（即 `status` 字段的值）
+    pub fn name(&self) -> &'static str {
+        match self {
+            WsEvent::Connected { .. } => "connected",
+            WsEvent::Scanned { .. } => "scanned",
+            WsEvent::Confirmed { .. } => "confirmed",
+            WsEvent::Rejected { .. } => "rejected",
+            WsEvent::Expired { .. } => "expired",
+            WsEvent::Error { .. } => "error",
+            WsEvent::Ping { .. } => "ping",
+        }
+    }
+
+    /// 是否为终态事件（推送后关闭连接）
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            WsEvent::Confirmed { .. } | WsEvent::Rejected { .. } | WsEvent::Expired { .. }
+        )
+    }
+
+    /// 根据会话的当前状态生成对应事件
+    pub fn from_session(session: &qr_login_sessions::Model) -> Self {
+        match session.status.as_str() {
+            "confirmed" => WsEvent::confirmed(),
+            "rejected" => WsEvent::rejected("Login rejected"),
+            "expired" => WsEvent::expired(),
+            _ if session.expires_at < Utc::now().naive_utc() => WsEvent::expired(),
+            "scanned" => WsEvent::scanned(),
+            _ => WsEvent::connected(),
+        }
+    }
+
+    /// 序列化为带协议版本号的消息
+    pub fn to_message(&self) -> String {
+        self.to_message_with_id(None)
+    }
+
+    /// 序列化为带事件 ID 的消息，客户端重连时通过 `last_event_id` 请求补发之后的事件
+    pub fn to_message_with_id(&self, id: Option<i64>) -> String {
+        serde_json::to_string(&WsMessage {
+            version: websocket::PROTOCOL_VERSION,
+            id,
+            event: self.clone(),
+        })
+        .unwrap_or_default()
+    }
+}
+
+/// 发送给客户端的消息信封
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WsMessage {
+    /// 协议版本号
+    pub version: u32,
+    /// 事件 ID（仅持久化的状态事件有 ID）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    #[serde(flatten)]
+    pub event: WsEvent,
+}
+
+/// 推送给已登录用户（`/v2/ws`）的事件
+///
+/// 以 `type` 字段区分事件类型，同一用户的所有设备都会收到。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UserEvent {
+    /// 连接建立
+    Connected { user_id: String },
+    /// 强制下线（如 token 过期、被管理员踢出），推送后关闭连接
+    ForcedLogout { reason: String },
+    /// 账号在新设备 / 新浏览器登录
+    NewLogin {
+        method: String,
+        ip: Option<String>,
+        browser: Option<String>,
+        at: i64,
+    },
+    /// Web 端请求登录，需要用户在 App 上批准或拒绝
+    LoginApproval {
+        session_id: String,
+        ip: Option<String>,
+        browser: Option<String>,
+        client_info: Option<String>,
+        /// 候选数字，用户需选择 Web 端页面上显示的那一个
+        match_choices: Vec<i16>,
+        expires_at: i64,
+    },
+    /// 管理员广播
+    Broadcast { title: String, message: String },
+    /// 错误（如命令无效）
+    Error { code: i32, message: String },
+    /// 心跳 / 对客户端 ping 命令的响应
+    Ping { timestamp: i64 },
+}
+
+impl UserEvent {
+    pub fn forced_logout(reason: &str) -> Self {
+        UserEvent::ForcedLogout { reason: reason.to_string() }
+    }
+
+    pub fn new_login(method: &str, ip: Option<&str>, browser: Option<&str>) -> Self {
+        UserEvent::NewLogin {
+            method: method.to_string(),
+            ip: ip.map(str::to_string),
+            browser: browser.map(str::to_string),
+            at: Utc::now().timestamp(),
+        }
+    }
+
+    /// 推送审批请求，携带 Web 端上下文与候选数字
+    pub fn login_approval(
+        session: &qr_login_sessions::Model,
+        browser: Option<String>,
+        match_choices: Vec<i16>,
+    ) -> Self {
+        UserEvent::LoginApproval {
+            session_id: session.session_id.clone(),
+            ip: session.client_ip.clone(),
+            browser,
+            client_info: session.client_info.clone(),
+            match_choices,
+            expires_at: session.expires_at.and_utc().timestamp(),
+        }
+    }
+
+    pub fn error(code: ErrorCode, message: &str) -> Self {
+        UserEvent::Error { code: code as i32, message: message.to_string() }
+    }
+
+    pub fn ping() -> Self {
+        UserEvent::Ping { timestamp: Utc::now().timestamp() }
+    }
+
+    /// 事件名称（即 `type` 字段的值）
+    pub fn name(&self) -> &'static str {
+        match self {
+            UserEvent::Connected { .. } => "connected",
+            UserEvent::ForcedLogout { .. } => "forced_logout",
+            UserEvent::NewLogin { .. } => "new_login",
+            UserEvent::LoginApproval { .. } => "login_approval",
+            UserEvent::Broadcast { .. } => "broadcast",
+            UserEvent::Error { .. } => "error",
+            UserEvent::Ping { .. } => "ping",
+        }
+    }
+
+    /// 是否在推送后关闭连接
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, UserEvent::ForcedLogout { .. })
+    }
+
+    /// 序列化为带协议版本号的消息
+    pub fn to_message(&self) -> String {
+        serde_json::to_string(&UserMessage {
+            version: websocket::PROTOCOL_VERSION,
+            event: self.clone(),
+        })
+        .unwrap_or_default()
+    }
+}
+
+/// 发送给已登录用户的消息信封
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UserMessage {
+    /// 协议版本号
+    pub version: u32,
+    #[serde(flatten)]
+    pub event: UserEvent,
+}
+
+/// 客户端发送的命令
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum WsCommand {
+    /// 取消本次扫码登录
+    Cancel,
+    /// 重新获取会话当前状态，并补发 `last_event_id` 之后的事件
+    Resync {
+        #[serde(default)]
+        last_event_id: Option<i64>,
+    },
+    /// 应用层心跳
+    Ping,
+}
+
+impl WsCommand {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|e| format!("Invalid command: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_message_shape() {
+        let value: serde_json::Value =
+            serde_json::from_str(&WsEvent::confirmed().to_message()).unwrap();
+        assert_eq!(value["version"], websocket::PROTOCOL_VERSION);
+        assert_eq!(value["status"], "confirmed");
+        assert!(value.get("web_token").is_none());
+        assert_eq!(value["message"], "Login successful");
+        assert!(value.get("id").is_none());
+
+        let value: serde_json::Value =
+            serde_json::from_str(&WsEvent::scanned().to_message_with_id(Some(3))).unwrap();
+        assert_eq!(value["id"], 3);
+        assert_eq!(value["status"], "scanned");
+    }
+
+    #[test]
+    fn test_event_roundtrip() {
+        let event = WsEvent::error(ErrorCode::RateLimitExceeded, "Too many connections");
+        let parsed: WsMessage = serde_json::from_str(&event.to_message()).unwrap();
+        assert_eq!(parsed.event, event);
+        assert_eq!(parsed.event.name(), "error");
+    }
+
+    #[test]
+    fn test_terminal_events() {
+        assert!(WsEvent::confirmed().is_terminal());
+        assert!(WsEvent::rejected("no").is_terminal());
+        assert!(WsEvent::expired().is_terminal());
+        assert!(!WsEvent::scanned().is_terminal());
+        assert!(!WsEvent::connected().is_terminal());
+        assert!(!WsEvent::ping().is_terminal());
+    }
+
+    #[test]
+    fn test_user_event_message_shape() {
+        let value: serde_json::Value =
+            serde_json::from_str(&UserEvent::forced_logout("Token expired").to_message()).unwrap();
+        assert_eq!(value["version"], websocket::PROTOCOL_VERSION);
+        assert_eq!(value["type"], "forced_logout");
+        assert_eq!(value["reason"], "Token expired");
+        assert!(UserEvent::forced_logout("x").is_terminal());
+        assert!(!UserEvent::ping().is_terminal());
+
+        let event = UserEvent::new_login("qr", Some("1.2.3.4"), None);
+        let parsed: UserMessage = serde_json::from_str(&event.to_message()).unwrap();
+        assert_eq!(parsed.event, event);
+        assert_eq!(parsed.event.name(), "new_login");
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(WsCommand::parse(r#"{"command":"cancel"}"#), Ok(WsCommand::Cancel));
+        assert_eq!(
+            WsCommand::parse(r#"{"command":"resync"}"#),
+            Ok(WsCommand::Resync { last_event_id: None })
+        );
+        assert_eq!(
+            WsCommand::parse(r#"{"command":"resync","last_event_id":7}"#),
+            Ok(WsCommand::Resync { last_event_id: Some(7) })
+        );
+        assert_eq!(WsCommand::parse(r#"{"command":"ping"}"#), Ok(WsCommand::Ping));
+        assert!(WsCommand::parse(r#"{"command":"explode"}"#).is_err());
+        assert!(WsCommand::parse("hello").is_err());
+    }
+}